@@ -0,0 +1,321 @@
+use std::{
+    env, fs,
+    io::{self, Cursor, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use audiotags::Tag;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use icy_sixel::{DiffusionMethod, MethodForLargest, MethodForRep, PixelFormat, Quality};
+use image::{imageops::FilterType, DynamicImage, ImageFormat};
+use ratatui::{
+    buffer::Buffer,
+    crossterm::{cursor::MoveTo, queue, style::Print, terminal},
+    layout::Rect,
+    style::Color,
+};
+use serde::Deserialize;
+
+/// Имена файлов обложки в каталоге альбома (без расширения) в порядке предпочтения
+const COVER_FILE_NAMES: [&str; 4] = ["cover", "folder", "front", "album"];
+const COVER_FILE_EXTENSIONS: [&str; 3] = ["jpg", "jpeg", "png"];
+
+/// Размер ячейки в пикселях, если терминал его не сообщает
+const DEFAULT_CELL_SIZE: (u16, u16) = (8, 16);
+
+/// Сколько символов base64 передается в одной команде протокола kitty
+const KITTY_CHUNK_SIZE: usize = 4096;
+/// Удаляет все картинки kitty вместе с данными
+const KITTY_DELETE_ALL: &str = "\x1b_Ga=d,d=A,q=2\x1b\\";
+
+const HALF_BLOCK: &str = "▀";
+
+/// Способ вывода картинок в терминал
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphicsProtocol {
+    Kitty,
+    Sixel,
+    Iterm2,
+    /// Полублоки `▀` с цветом фона и текста: по два пикселя на ячейку, работает везде
+    HalfBlocks,
+}
+
+impl GraphicsProtocol {
+    /// Определяет лучший протокол, который поддерживает терминал, по его переменным окружения
+    ///
+    /// Внутри tmux протоколы без настройки passthrough не работают, поэтому там всегда
+    /// используются полублоки.
+    pub fn detect() -> Self {
+        let var = |name| env::var(name).unwrap_or_default();
+        let is_set = |name| env::var_os(name).is_some();
+        let term = var("TERM");
+        let term_program = var("TERM_PROGRAM");
+
+        if is_set("TMUX") || term.starts_with("screen") || term.starts_with("tmux") {
+            Self::HalfBlocks
+        } else if is_set("KITTY_WINDOW_ID") || term.contains("kitty") || term_program == "ghostty" {
+            Self::Kitty
+        } else if is_set("ITERM_SESSION_ID") || matches!(term_program.as_str(), "iTerm.app" | "WezTerm") {
+            Self::Iterm2
+        } else if is_set("KONSOLE_VERSION")
+            || is_set("WT_SESSION")
+            || term.contains("sixel")
+            || term.starts_with("foot")
+            || term.starts_with("mlterm")
+        {
+            Self::Sixel
+        } else {
+            Self::HalfBlocks
+        }
+    }
+}
+
+/// Обложка трека: встроенная в теги, а если ее нет или она не читается - файл обложки
+/// из каталога трека
+pub fn load_cover(path: &str) -> Option<DynamicImage> {
+    let embedded = Tag::new()
+        .read_from_path(path)
+        .ok()
+        .and_then(|tag| tag.album_cover().and_then(|cover| image::load_from_memory(cover.data).ok()));
+
+    embedded.or_else(|| {
+        let file = find_cover_file(Path::new(path))?;
+        image::load_from_memory(&fs::read(file).ok()?).ok()
+    })
+}
+
+/// Файл обложки рядом с треком; имена сравниваются без учета регистра
+fn find_cover_file(track: &Path) -> Option<PathBuf> {
+    let covers: Vec<(usize, PathBuf)> = fs::read_dir(track.parent()?)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let stem = path.file_stem()?.to_string_lossy().to_lowercase();
+            let extension = path.extension()?.to_string_lossy().to_lowercase();
+            let rank = COVER_FILE_NAMES.iter().position(|name| *name == stem)?;
+            COVER_FILE_EXTENSIONS.contains(&extension.as_str()).then_some((rank, path))
+        })
+        .collect();
+
+    covers.into_iter().min_by_key(|(rank, _)| *rank).map(|(_, path)| path)
+}
+
+/// Размер ячейки терминала в пикселях
+pub fn cell_size() -> (u16, u16) {
+    match terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => {
+            (size.width / size.columns, size.height / size.rows)
+        }
+        _ => DEFAULT_CELL_SIZE,
+    }
+}
+
+/// Прямоугольник в центре `area`, в который картинка вписывается с сохранением пропорций
+pub fn fit_area(image: &DynamicImage, area: Rect, cell_size: (u16, u16)) -> Rect {
+    let (cell_width, cell_height) = (cell_size.0 as f64, cell_size.1 as f64);
+    let scale = (area.width as f64 * cell_width / image.width() as f64)
+        .min(area.height as f64 * cell_height / image.height() as f64);
+
+    let width = ((image.width() as f64 * scale / cell_width).round() as u16).clamp(1, area.width);
+    let height = ((image.height() as f64 * scale / cell_height).round() as u16).clamp(1, area.height);
+    Rect { x: area.x + (area.width - width) / 2, y: area.y + (area.height - height) / 2, width, height }
+}
+
+/// Картинка, подготовленная для вывода в прямоугольник заданного размера
+pub enum EncodedArt {
+    /// Цвета верхней и нижней половин ячеек, построчно
+    HalfBlocks(Vec<(Color, Color)>),
+    /// Управляющая последовательность, которая выводится в левом верхнем углу прямоугольника
+    Escape(Arc<String>),
+}
+
+/// Масштабирует картинку под прямоугольник из `columns` x `rows` ячеек и кодирует ее
+/// для протокола терминала
+pub fn encode(
+    image: &DynamicImage,
+    protocol: GraphicsProtocol,
+    (columns, rows): (u16, u16),
+    (cell_width, cell_height): (u16, u16),
+) -> Option<EncodedArt> {
+    let width = columns as u32 * cell_width as u32;
+    let height = rows as u32 * cell_height as u32;
+
+    let escape = match protocol {
+        GraphicsProtocol::HalfBlocks => {
+            let pixels = image.resize_exact(columns as u32, rows as u32 * 2, FilterType::Triangle).to_rgb8();
+            let color = |x, y| {
+                let [r, g, b] = pixels.get_pixel(x, y).0;
+                Color::Rgb(r, g, b)
+            };
+            let cells = (0..rows as u32)
+                .flat_map(|row| (0..columns as u32).map(move |column| (column, row)))
+                .map(|(column, row)| (color(column, row * 2), color(column, row * 2 + 1)))
+                .collect();
+            return Some(EncodedArt::HalfBlocks(cells));
+        }
+        GraphicsProtocol::Kitty => {
+            kitty_escape(&png(&image.resize_exact(width, height, FilterType::Triangle))?, columns, rows)
+        }
+        GraphicsProtocol::Iterm2 => {
+            iterm2_escape(&png(&image.resize_exact(width, height, FilterType::Triangle))?, columns, rows)
+        }
+        GraphicsProtocol::Sixel => {
+            // Sixel рисует полосами по 6 пикселей: последняя не должна вылезать за прямоугольник
+            let pixels = image.resize_exact(width, height / 6 * 6, FilterType::Triangle).to_rgb8();
+            icy_sixel::sixel_string(
+                pixels.as_raw(),
+                pixels.width() as i32,
+                pixels.height() as i32,
+                PixelFormat::RGB888,
+                DiffusionMethod::Stucki,
+                MethodForLargest::Auto,
+                MethodForRep::Auto,
+                Quality::HIGH,
+            )
+            .ok()?
+        }
+    };
+    Some(EncodedArt::Escape(Arc::new(escape)))
+}
+
+fn png(image: &DynamicImage) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png).ok()?;
+    Some(bytes)
+}
+
+/// Передает PNG частями и размещает его в `columns` x `rows` ячейках, не сдвигая курсор
+fn kitty_escape(png: &[u8], columns: u16, rows: u16) -> String {
+    let data = STANDARD.encode(png);
+    let chunks: Vec<&str> = (0..data.len())
+        .step_by(KITTY_CHUNK_SIZE)
+        .map(|start| &data[start..(start + KITTY_CHUNK_SIZE).min(data.len())])
+        .collect();
+
+    let mut escape = String::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = (index + 1 < chunks.len()) as u8;
+        match index {
+            0 => escape.push_str(&format!(
+                "\x1b_Gf=100,a=T,c={},r={},C=1,q=2,m={};{}\x1b\\",
+                columns, rows, more, chunk
+            )),
+            _ => escape.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk)),
+        }
+    }
+    escape
+}
+
+fn iterm2_escape(png: &[u8], columns: u16, rows: u16) -> String {
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=0:{}\x07",
+        png.len(),
+        columns,
+        rows,
+        STANDARD.encode(png)
+    )
+}
+
+/// Рисует полублоки прямо в буфер
+pub fn render_half_blocks(cells: &[(Color, Color)], area: Rect, buf: &mut Buffer) {
+    for (index, (top, bottom)) in cells.iter().enumerate() {
+        let x = area.x + (index % area.width as usize) as u16;
+        let y = area.y + (index / area.width as usize) as u16;
+        buf[(x, y)].set_symbol(HALF_BLOCK).set_fg(*top).set_bg(*bottom);
+    }
+}
+
+/// Картинка, выведенная или ожидающая вывода в терминал
+#[derive(Clone)]
+struct Placement {
+    area: Rect,
+    escape: Arc<String>,
+}
+
+impl Placement {
+    fn same(this: &Option<Self>, other: &Option<Self>) -> bool {
+        match (this, other) {
+            (Some(this), Some(other)) => this.area == other.area && Arc::ptr_eq(&this.escape, &other.escape),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Default)]
+struct ArtOutputState {
+    enabled: bool,
+    pending: Option<Placement>,
+    shown: Option<Placement>,
+    frame_area: Rect,
+}
+
+/// Вывод картинок графическими протоколами в обход буфера ratatui
+///
+/// В буфере у ячейки только символ, поэтому при отрисовке кадра ячейки под картинкой
+/// пропускаются, а сама картинка выводится в терминал после кадра и только если она
+/// изменилась, сдвинулась или терминал перерисован целиком.
+#[derive(Clone)]
+pub struct ArtOutput {
+    protocol: GraphicsProtocol,
+    state: Arc<Mutex<ArtOutputState>>,
+}
+
+impl ArtOutput {
+    pub fn new(protocol: GraphicsProtocol) -> Self {
+        let state = ArtOutputState { enabled: true, ..ArtOutputState::default() };
+        Self { protocol, state: Arc::new(Mutex::new(state)) }
+    }
+
+    pub fn protocol(&self) -> GraphicsProtocol {
+        self.protocol
+    }
+
+    /// Разрешает или запрещает вывод картинок в следующем кадре: поверх модального окна
+    /// картинка оказалась бы выше него
+    pub fn set_enabled(&self, enabled: bool) {
+        self.state.lock().unwrap().enabled = enabled;
+    }
+
+    /// Отмечает ячейки под картинкой как пропускаемые и запоминает картинку для вывода
+    /// после кадра. Пока вывод запрещен, место под картинкой остается пустым.
+    pub fn place(&self, escape: &Arc<String>, area: Rect, buf: &mut Buffer) {
+        let mut state = self.state.lock().unwrap();
+        if !state.enabled {
+            return;
+        }
+
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                buf[(x, y)].set_skip(true);
+            }
+        }
+        state.pending = Some(Placement { area, escape: escape.clone() });
+    }
+
+    /// Выводит в терминал картинку, размещенную в только что отрисованном кадре
+    pub fn flush(&self, frame_area: Rect) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let pending = state.pending.take();
+        if Placement::same(&pending, &state.shown) && frame_area == state.frame_area {
+            return Ok(());
+        }
+
+        let mut stdout = io::stdout();
+        // Картинки kitty не затираются текстом, старую нужно удалить явно
+        if self.protocol == GraphicsProtocol::Kitty && state.shown.is_some() {
+            queue!(stdout, Print(KITTY_DELETE_ALL))?;
+        }
+        if let Some(placement) = &pending {
+            queue!(stdout, MoveTo(placement.area.x, placement.area.y), Print(placement.escape.as_str()))?;
+        }
+        stdout.flush()?;
+
+        state.shown = pending;
+        state.frame_area = frame_area;
+        Ok(())
+    }
+}
@@ -1,8 +1,25 @@
 use std::{
-    sync::{Arc, Mutex},
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{Arc, Condvar, Mutex},
+    thread,
     time::Duration,
 };
 
+use crate::metadata::StreamInfo;
+
+/// Сколько правок плейлиста можно отменить
+const HISTORY_LIMIT: usize = 100;
+
+/// Название плейлиста, который создается, когда других нет
+const DEFAULT_PLAYLIST_NAME: &str = "Default";
+
+/// Сколько показывается сообщение в строке состояния
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(3);
+/// Сколько показывается всплывающее уведомление
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+/// Сколько уведомлений видно одновременно; новые вытесняют старые
+const TOAST_LIMIT: usize = 4;
+
 #[derive(Default, Clone, PartialEq)]
 pub enum PlayerState {
     #[default]
@@ -15,132 +32,1518 @@ pub enum PlayerState {
 ///
 /// # Fields
 ///
+/// * `path` - Путь к файлу трека
 /// * `title` - Название трека
 /// * `artist` - Исполнитель
 /// * `album` - Альбом
+/// * `year` - Год
+/// * `track_number` - Номер трека в альбоме
+/// * `disc_number` - Номер диска
+/// * `genre` - Жанр
+/// * `bitrate` - Средний битрейт в кбит/с
+/// * `stream` - Кодек, частота дискретизации и разрядность
 /// * `duration` - Полная длительность трека
 /// * `played_duration` - прошеднее время воспроизведения трека
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct CurrentTrackInfo {
+    pub path: String,
     pub title: String,
     pub artist: String,
     pub album: String,
+    pub year: Option<i32>,
+    pub track_number: Option<u16>,
+    pub disc_number: Option<u16>,
+    pub genre: Option<String>,
+    pub bitrate: Option<u32>,
+    pub stream: StreamInfo,
     pub duration: Duration,
     pub played_duration: Duration,
 }
 
-impl CurrentTrackInfo {
-    pub fn new(
-        title: String,
-        artist: String,
-        album: String,
-        duration: Duration,
-        played_duration: Duration,
-    ) -> Self {
-        Self { title, artist, album, duration, played_duration }
-    }
+/// Изменение одного из полей состояния, о котором уведомляются подписчики
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StateChange {
+    Exit,
+    PlayerState,
+    Playlist,
+    CurrentTrack,
+    ShuffleState,
+    RepeatState,
+    Volume,
+    CurrentTrackInfo,
+    TerminalFocused,
+    StatusMessage,
+    Queue,
+    Playlists,
+    LibraryVisible,
+    LyricsVisible,
+    MissingTracks,
+    TagEditor,
+    Organiser,
+    Help,
+    Toasts,
+}
+
+/// Именованный плейлист
+///
+/// # Fields
+///
+/// * `id` - Постоянный номер плейлиста, не меняющийся при удалении соседних
+/// * `name` - Название, оно же имя файла плейлиста
+/// * `tracks` - Пути к файлам треков
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedPlaylist {
+    pub id: u64,
+    pub name: String,
+    pub tracks: Vec<String>,
+}
+
+/// Важность всплывающего уведомления, от нее зависит цвет рамки
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+/// Всплывающее уведомление
+///
+/// # Fields
+///
+/// * `id` - Номер уведомления, по которому его снимает таймер или щелчок
+/// * `level` - Важность уведомления
+/// * `text` - Текст уведомления
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub id: u64,
+    pub level: ToastLevel,
+    pub text: String,
+}
+
+/// Ссылка на трек: номер плейлиста и позиция трека в нем
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TrackRef {
+    playlist: u64,
+    index: usize,
+}
+
+/// Играющий трек, удаленный из своего плейлиста
+///
+/// # Fields
+///
+/// * `place` - Трек, занявший место удаленного (индекс может быть равен длине плейлиста,
+///   если удаленный трек был последним): обычный порядок продолжится с него
+/// * `path` - Путь к файлу, который продолжает играть
+#[derive(Debug, Clone)]
+struct RemovedTrack {
+    place: TrackRef,
+    path: String,
+}
+
+/// Снимок плейлиста для отмены и повтора правок
+///
+/// # Fields
+///
+/// * `label` - Описание правки для строки состояния
+/// * `playlist` - Номер плейлиста
+/// * `tracks` - Треки плейлиста до правки
+/// * `current_track` - Индекс текущего трека до правки, если он был в этом плейлисте
+/// * `playback_id` - Номер запуска воспроизведения на момент снимка
+#[derive(Clone)]
+struct PlaylistSnapshot {
+    label: String,
+    playlist: u64,
+    tracks: Vec<String>,
+    current_track: Option<usize>,
+    playback_id: u64,
+}
+
+/// Сообщение в строке состояния
+///
+/// # Fields
+///
+/// * `id` - Номер сообщения, по которому его снимает таймер
+/// * `text` - Текст сообщения
+#[derive(Clone)]
+struct StatusMessage {
+    id: u64,
+    text: String,
 }
 
 /// Состояние приложения
 ///
+/// Изменяется только внутри [`AppState::update`], поэтому изменение нескольких полей
+/// видно остальным потокам целиком. Каждый сеттер запоминает, что поле изменилось.
+///
 /// # Fields
 ///
 /// * `exit` - Флаг выхода из приложения
+/// * `player_state` - Состояние воспроизведения
+/// * `playlists` - Плейлисты, всегда хотя бы один
+/// * `active_playlist` - Индекс плейлиста, открытого в интерфейсе; правки применяются к нему
+/// * `next_playlist_id` - Номер для следующего созданного плейлиста
+/// * `current_track` - Текущий трек; может находиться не в открытом плейлисте
+/// * `playback_id` - Номер запуска воспроизведения, растет при каждом запуске трека
+/// * `queue` - Очередь воспроизведения: треки, которые играют раньше остальных
+/// * `resume_track` - Трек, после которого продолжится обычный порядок, когда очередь иссякнет
+/// * `removed_track` - Текущий трек, если его уже удалили из плейлиста, а он еще играет
 /// * `shuffle_state` - Флаг состояния перемешивания
 /// * `repeat_state` - Флаг состояния повтора
 /// * `volume` - Громкость (от 0 до 1)
-/// * `play_progress` - Прогресс воспроизведения (от 0 до 1)
-/// * `playlist` - Плейлист
-#[derive(Default, Clone)]
-pub struct AppState {
-    exit: Arc<Mutex<bool>>,
+/// * `current_track_info` - Информация о текущем треке
+/// * `terminal_focused` - Находится ли окно терминала в фокусе
+/// * `undo_stack` - Снимки плейлиста для отмены правок, последний - самый свежий
+/// * `redo_stack` - Снимки плейлиста для повтора отмененных правок
+/// * `status_message` - Сообщение в строке состояния
+/// * `library_visible` - Показана ли медиатека вместо обозревателя файлов
+/// * `lyrics_visible` - Показан ли текст песни вместо очереди
+/// * `missing_tracks` - Треки плейлистов, файлов которых нет на диске
+/// * `tag_editor_tracks` - Треки, теги которых открыты в редакторе; пусто, если редактор закрыт
+/// * `organiser_tracks` - Треки, которые раскладываются по шаблону; пусто, если окно закрыто
+/// * `help_open` - Открыта ли справка по клавишам и мыши
+/// * `toasts` - Всплывающие уведомления, последнее - самое свежее
+/// * `next_toast_id` - Номер следующего уведомления
+#[derive(Clone)]
+pub struct State {
+    exit: bool,
+
+    player_state: PlayerState,
+
+    playlists: Vec<NamedPlaylist>,
+    active_playlist: usize,
+    next_playlist_id: u64,
+
+    current_track: Option<TrackRef>,
+    playback_id: u64,
+
+    queue: VecDeque<TrackRef>,
+    resume_track: Option<TrackRef>,
+    removed_track: Option<RemovedTrack>,
+
+    shuffle_state: bool,
+    repeat_state: bool,
+
+    volume: f32,
+
+    current_track_info: Option<CurrentTrackInfo>,
+
+    terminal_focused: bool,
+
+    undo_stack: VecDeque<PlaylistSnapshot>,
+    redo_stack: Vec<PlaylistSnapshot>,
+
+    status_message: Option<StatusMessage>,
+
+    library_visible: bool,
+    lyrics_visible: bool,
 
-    player_state: Arc<Mutex<PlayerState>>,
+    missing_tracks: HashSet<String>,
 
-    playlist: Arc<Mutex<Vec<String>>>,
+    tag_editor_tracks: Vec<String>,
+    organiser_tracks: Vec<String>,
 
-    shuffle_state: Arc<Mutex<bool>>,
-    repeat_state: Arc<Mutex<bool>>,
+    help_open: bool,
 
-    volume: Arc<Mutex<f32>>,
+    toasts: VecDeque<Toast>,
+    next_toast_id: u64,
 
-    current_track_info: Arc<Mutex<Option<CurrentTrackInfo>>>,
+    changes: Vec<StateChange>,
 }
 
-impl AppState {
-    pub fn set_exit(&self, value: bool) {
-        if let Ok(mut exit) = self.exit.lock() {
-            *exit = value;
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            exit: false,
+            player_state: PlayerState::default(),
+            playlists: vec![NamedPlaylist { id: 0, name: DEFAULT_PLAYLIST_NAME.to_string(), tracks: vec![] }],
+            active_playlist: 0,
+            next_playlist_id: 1,
+            current_track: None,
+            playback_id: 0,
+            queue: VecDeque::new(),
+            resume_track: None,
+            removed_track: None,
+            shuffle_state: false,
+            repeat_state: false,
+            volume: 1.0,
+            current_track_info: None,
+            terminal_focused: true,
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            status_message: None,
+            library_visible: false,
+            lyrics_visible: false,
+            missing_tracks: HashSet::new(),
+            tag_editor_tracks: Vec::new(),
+            organiser_tracks: Vec::new(),
+            help_open: false,
+            toasts: VecDeque::new(),
+            next_toast_id: 0,
+            changes: Vec::new(),
         }
     }
+}
 
-    pub fn should_exit(&self) -> bool {
-        *self.exit.lock().unwrap()
+impl State {
+    fn changed(&mut self, change: StateChange) {
+        if !self.changes.contains(&change) {
+            self.changes.push(change);
+        }
+    }
+
+    pub fn exit(&self) -> bool {
+        self.exit
     }
 
-    pub fn set_player_state(&self, value: PlayerState) {
-        if let Ok(mut player_state) = self.player_state.lock() {
-            *player_state = value;
+    pub fn set_exit(&mut self, value: bool) {
+        if self.exit != value {
+            self.exit = value;
+            self.changed(StateChange::Exit);
         }
     }
 
-    pub fn player_state(&self) -> PlayerState {
-        self.player_state.lock().unwrap().clone()
+    pub fn player_state(&self) -> &PlayerState {
+        &self.player_state
     }
 
-    pub fn set_shuffle_state(&self, value: bool) {
-        if let Ok(mut shuffle_state) = self.shuffle_state.lock() {
-            *shuffle_state = value;
+    pub fn set_player_state(&mut self, value: PlayerState) {
+        if self.player_state != value {
+            self.player_state = value;
+            self.changed(StateChange::PlayerState);
+        }
+    }
+
+    pub fn playlists(&self) -> &[NamedPlaylist] {
+        &self.playlists
+    }
+
+    /// Индекс открытого плейлиста
+    pub fn active_playlist(&self) -> usize {
+        self.active_playlist
+    }
+
+    pub fn set_active_playlist(&mut self, index: usize) {
+        if index < self.playlists.len() && index != self.active_playlist {
+            self.active_playlist = index;
+            self.changed(StateChange::Playlists);
+            self.changed(StateChange::Playlist);
+            self.changed(StateChange::CurrentTrack);
+        }
+    }
+
+    /// Треки открытого плейлиста
+    pub fn playlist(&self) -> &[String] {
+        &self.playlists[self.active_playlist].tracks
+    }
+
+    fn active_id(&self) -> u64 {
+        self.playlists[self.active_playlist].id
+    }
+
+    fn playlist_by_id(&self, id: u64) -> Option<&NamedPlaylist> {
+        self.playlists.iter().find(|playlist| playlist.id == id)
+    }
+
+    fn track_path(&self, track: TrackRef) -> Option<&String> {
+        self.playlist_by_id(track.playlist)?.tracks.get(track.index)
+    }
+
+    /// Заменяет все плейлисты, например загруженными с диска. Текущий трек, очередь
+    /// и история правок относятся к прежним плейлистам и сбрасываются.
+    ///
+    /// # Args:
+    /// * `playlists` - Названия и треки плейлистов; если пусто, создается пустой плейлист
+    /// * `active` - Индекс плейлиста, который нужно открыть
+    pub fn set_playlists(&mut self, playlists: Vec<(String, Vec<String>)>, active: usize) {
+        self.playlists.clear();
+        for (name, tracks) in playlists {
+            self.push_playlist(&name, tracks);
+        }
+        if self.playlists.is_empty() {
+            self.push_playlist(DEFAULT_PLAYLIST_NAME, vec![]);
+        }
+        self.active_playlist = active.min(self.playlists.len() - 1);
+
+        self.current_track = None;
+        self.queue.clear();
+        self.resume_track = None;
+        self.removed_track = None;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.changed(StateChange::Playlists);
+        self.changed(StateChange::Playlist);
+        self.changed(StateChange::CurrentTrack);
+        self.changed(StateChange::Queue);
+    }
+
+    /// Создает пустой плейлист и открывает его
+    ///
+    /// # Returns:
+    /// Индекс нового плейлиста
+    pub fn create_playlist(&mut self, name: &str) -> usize {
+        self.push_playlist(name, vec![]);
+        self.set_active_playlist(self.playlists.len() - 1);
+        self.changed(StateChange::Playlists);
+        self.active_playlist
+    }
+
+    fn push_playlist(&mut self, name: &str, tracks: Vec<String>) {
+        let name = self.unique_playlist_name(name, None);
+        self.playlists.push(NamedPlaylist { id: self.next_playlist_id, name, tracks });
+        self.next_playlist_id += 1;
+    }
+
+    pub fn rename_playlist(&mut self, index: usize, name: &str) {
+        if index >= self.playlists.len() {
+            return;
+        }
+
+        let name = self.unique_playlist_name(name, Some(index));
+        if self.playlists[index].name != name {
+            self.playlists[index].name = name;
+            self.changed(StateChange::Playlists);
+        }
+    }
+
+    /// Удаляет плейлист вместе с его треками в очереди и историей правок.
+    /// Последний оставшийся плейлист удалить нельзя.
+    ///
+    /// # Returns:
+    /// `true`, если плейлист удален
+    pub fn delete_playlist(&mut self, index: usize) -> bool {
+        if index >= self.playlists.len() || self.playlists.len() == 1 {
+            return false;
+        }
+
+        let id = self.playlists.remove(index).id;
+        if index < self.active_playlist || self.active_playlist == self.playlists.len() {
+            self.active_playlist -= 1;
+        }
+
+        if self.current_track.is_some_and(|track| track.playlist == id) {
+            self.current_track = None;
+        }
+        if self.resume_track.is_some_and(|track| track.playlist == id) {
+            self.resume_track = None;
+        }
+        if self.removed_track.as_ref().is_some_and(|removed| removed.place.playlist == id) {
+            self.removed_track = None;
+        }
+        self.queue.retain(|track| track.playlist != id);
+        self.undo_stack.retain(|snapshot| snapshot.playlist != id);
+        self.redo_stack.retain(|snapshot| snapshot.playlist != id);
+
+        self.changed(StateChange::Playlists);
+        self.changed(StateChange::Playlist);
+        self.changed(StateChange::CurrentTrack);
+        self.changed(StateChange::Queue);
+        true
+    }
+
+    /// Название, не совпадающее (без учета регистра) с названиями других плейлистов:
+    /// к занятому названию добавляется номер
+    fn unique_playlist_name(&self, name: &str, except: Option<usize>) -> String {
+        let name = match name.trim() {
+            "" => DEFAULT_PLAYLIST_NAME,
+            name => name,
+        };
+        let taken = |candidate: &str| {
+            self.playlists.iter().enumerate().any(|(index, playlist)| {
+                Some(index) != except && playlist.name.to_lowercase() == candidate.to_lowercase()
+            })
+        };
+
+        if !taken(name) {
+            return name.to_string();
+        }
+        (2..).map(|number| format!("{} {}", name, number)).find(|candidate| !taken(candidate)).unwrap()
+    }
+
+    /// Добавляет треки открытого плейлиста в конец другого плейлиста.
+    /// Правка запоминается в истории целевого плейлиста.
+    pub fn send_tracks(&mut self, indices: &[usize], target: usize, label: impl Into<String>) {
+        if target >= self.playlists.len() || target == self.active_playlist {
+            return;
+        }
+
+        let tracks: Vec<String> =
+            indices.iter().filter_map(|&index| self.playlist().get(index).cloned()).collect();
+        self.push_undo(self.playlist_snapshot(self.playlists[target].id, label.into()));
+        self.playlists[target].tracks.extend(tracks);
+        self.changed(StateChange::Playlist);
+    }
+
+    /// Заменяет пути перемещенных треков во всех плейлистах. Правка каждого затронутого
+    /// плейлиста попадает в историю.
+    ///
+    /// # Args:
+    /// * `moves` - Новый путь для каждого прежнего
+    ///
+    /// # Returns:
+    /// Сколько записей плейлистов изменилось
+    pub fn relocate_tracks(&mut self, moves: &HashMap<String, String>, label: impl Into<String>) -> usize {
+        let label = label.into();
+        for position in 0..self.playlists.len() {
+            if self.playlists[position].tracks.iter().any(|track| moves.contains_key(track)) {
+                self.push_undo(self.playlist_snapshot(self.playlists[position].id, label.clone()));
+            }
+        }
+        self.rename_tracks(moves)
+    }
+
+    /// Заменяет пути переименованных файлов во всех плейлистах, не записывая правку в историю:
+    /// отмена вернула бы пути, по которым файлов уже нет
+    ///
+    /// # Returns:
+    /// Сколько записей плейлистов изменилось
+    pub fn rename_tracks(&mut self, moves: &HashMap<String, String>) -> usize {
+        let mut relocated = 0;
+        for playlist in &mut self.playlists {
+            for track in &mut playlist.tracks {
+                if let Some(new_path) = moves.get(track) {
+                    *track = new_path.clone();
+                    relocated += 1;
+                }
+            }
+        }
+
+        if relocated > 0 {
+            self.set_tracks_missing(moves.keys().cloned(), false);
+            self.changed(StateChange::Playlist);
+        }
+        relocated
+    }
+
+    pub fn add_tracks(&mut self, track_file_paths: Vec<String>) {
+        let active = self.active_playlist;
+        self.playlists[active].tracks.extend(track_file_paths);
+        self.changed(StateChange::Playlist);
+    }
+
+    /// Собирает открытый плейлист из его же треков: на `i`-е место встает трек, стоявший на месте
+    /// `order[i]`. Треки, не попавшие в `order`, удаляются.
+    ///
+    /// Все правки плейлиста проходят здесь: указатели на текущий трек и треки очереди следуют
+    /// за треками, а указатели на удаленные треки сбрасываются. Удаленный текущий трек
+    /// доигрывает, а обычный порядок после него продолжится с трека, занявшего его место.
+    pub fn rearrange_playlist(&mut self, order: &[usize]) {
+        if order.iter().any(|&index| index >= self.playlist().len()) {
+            return;
+        }
+
+        let id = self.active_id();
+        // Первый оставшийся трек не раньше `index` прежнего плейлиста
+        let place = |index: usize| TrackRef {
+            playlist: id,
+            index: order.iter().position(|&kept| kept >= index).unwrap_or(order.len()),
+        };
+        let removed_track = self.removed_track.take().map(|removed| match removed.place.playlist == id {
+            true => RemovedTrack { place: place(removed.place.index), ..removed },
+            false => removed,
+        });
+        // Трек из очереди, удаленный после другого удаленного трека, не меняет место продолжения
+        let removed_track = match self.current_track {
+            Some(track) if track.playlist == id && !order.contains(&track.index) => {
+                let path = self.playlist()[track.index].clone();
+                match removed_track {
+                    Some(removed) => Some(RemovedTrack { path, ..removed }),
+                    None => Some(RemovedTrack { place: place(track.index + 1), path }),
+                }
+            }
+            _ => removed_track,
+        };
+        let remap = |track: TrackRef| match track.playlist == id {
+            true => {
+                order.iter().position(|&index| index == track.index).map(|index| TrackRef { index, ..track })
+            }
+            false => Some(track),
+        };
+
+        let tracks = order.iter().map(|&index| self.playlist()[index].clone()).collect();
+        let active = self.active_playlist;
+        self.playlists[active].tracks = tracks;
+        self.current_track = self.current_track.and_then(remap);
+        self.resume_track = self.resume_track.and_then(remap);
+        self.queue = self.queue.iter().filter_map(|&track| remap(track)).collect();
+        self.removed_track = removed_track;
+
+        self.changed(StateChange::Playlist);
+        self.changed(StateChange::CurrentTrack);
+        self.changed(StateChange::Queue);
+    }
+
+    /// Удаляет треки с указанными индексами
+    ///
+    /// # Returns:
+    /// Примененный порядок (см. [`State::rearrange_playlist`])
+    pub fn remove_tracks(&mut self, indices: &[usize]) -> Vec<usize> {
+        let order: Vec<usize> = (0..self.playlist().len()).filter(|index| !indices.contains(index)).collect();
+        self.rearrange_playlist(&order);
+        order
+    }
+
+    /// Перемещает треки с указанными индексами единым блоком перед треком `before`
+    /// (или в конец, если `before` равен длине плейлиста). Порядок треков внутри блока сохраняется.
+    ///
+    /// # Returns:
+    /// Примененный порядок (см. [`State::rearrange_playlist`])
+    pub fn move_tracks(&mut self, indices: &[usize], before: usize) -> Vec<usize> {
+        let len = self.playlist().len();
+        let mut moved: Vec<usize> = indices.iter().copied().filter(|&index| index < len).collect();
+        moved.sort_unstable();
+        moved.dedup();
+
+        let rest = (0..len).filter(|index| !moved.contains(index));
+        let insert_at = rest.clone().take_while(|&index| index < before).count();
+
+        let mut order: Vec<usize> = rest.collect();
+        order.splice(insert_at..insert_at, moved);
+        self.rearrange_playlist(&order);
+        order
+    }
+
+    /// Очищает плейлист
+    pub fn clear_playlist(&mut self) {
+        self.rearrange_playlist(&[]);
+    }
+
+    /// Оставляет только первое вхождение каждого файла
+    ///
+    /// # Returns:
+    /// Примененный порядок (см. [`State::rearrange_playlist`])
+    pub fn remove_duplicates(&mut self) -> Vec<usize> {
+        let mut seen = HashSet::new();
+        let playlist = self.playlist();
+        let order: Vec<usize> = (0..playlist.len()).filter(|&index| seen.insert(&playlist[index])).collect();
+        self.rearrange_playlist(&order);
+        order
+    }
+
+    /// Запоминает открытый плейлист перед правкой, чтобы ее можно было отменить.
+    /// Отмененные правки после новой правки повторить уже нельзя.
+    ///
+    /// # Args:
+    /// * `label` - Описание правки для строки состояния
+    pub fn checkpoint_playlist(&mut self, label: impl Into<String>) {
+        self.push_undo(self.playlist_snapshot(self.active_id(), label.into()));
+    }
+
+    fn push_undo(&mut self, snapshot: PlaylistSnapshot) {
+        self.undo_stack.push_back(snapshot);
+        if self.undo_stack.len() > HISTORY_LIMIT {
+            self.undo_stack.pop_front();
+        }
+        self.redo_stack.clear();
+    }
+
+    /// Отменяет последнюю правку плейлиста и открывает измененный плейлист
+    ///
+    /// # Returns:
+    /// Описание отмененной правки или `None`, если отменять нечего
+    pub fn undo_playlist(&mut self) -> Option<String> {
+        let snapshot = self.undo_stack.pop_back()?;
+        self.redo_stack.push(self.playlist_snapshot(snapshot.playlist, snapshot.label.clone()));
+        Some(self.restore_playlist(snapshot))
+    }
+
+    /// Повторяет последнюю отмененную правку плейлиста и открывает измененный плейлист
+    ///
+    /// # Returns:
+    /// Описание повторенной правки или `None`, если повторять нечего
+    pub fn redo_playlist(&mut self) -> Option<String> {
+        let snapshot = self.redo_stack.pop()?;
+        self.undo_stack.push_back(self.playlist_snapshot(snapshot.playlist, snapshot.label.clone()));
+        Some(self.restore_playlist(snapshot))
+    }
+
+    fn playlist_snapshot(&self, playlist: u64, label: String) -> PlaylistSnapshot {
+        PlaylistSnapshot {
+            label,
+            playlist,
+            tracks: self.playlist_by_id(playlist).map(|playlist| playlist.tracks.clone()).unwrap_or_default(),
+            current_track: self
+                .current_track
+                .filter(|track| track.playlist == playlist)
+                .map(|track| track.index),
+            playback_id: self.playback_id,
+        }
+    }
+
+    /// Восстанавливает плейлист из снимка
+    ///
+    /// Если с момента снимка запускался другой трек, индекс текущего трека из снимка уже
+    /// ни о чем не говорит, и текущий трек ищется в восстановленном плейлисте по пути.
+    /// Текущий трек, которого в восстановленном плейлисте нет, доигрывает как удаленный.
+    /// Очередь не входит в историю правок, поэтому ее треки тоже ищутся по пути.
+    fn restore_playlist(&mut self, snapshot: PlaylistSnapshot) -> String {
+        let Some(position) = self.playlists.iter().position(|playlist| playlist.id == snapshot.playlist)
+        else {
+            return snapshot.label;
+        };
+
+        let id = snapshot.playlist;
+        let path_of =
+            |track: TrackRef| (track.playlist == id).then(|| self.track_path(track).cloned()).flatten();
+        let current_path = self.current_track.and_then(path_of);
+        let resume_path = self.resume_track.and_then(path_of);
+        let queue_paths: Vec<Option<String>> = self.queue.iter().map(|&track| path_of(track)).collect();
+
+        self.playlists[position].tracks = snapshot.tracks;
+        let tracks = &self.playlists[position].tracks;
+        let find = |path: Option<String>| {
+            let index = tracks.iter().position(|track| Some(track) == path.as_ref())?;
+            Some(TrackRef { playlist: id, index })
+        };
+
+        let previous_track = self.current_track.filter(|track| track.playlist == id);
+        if previous_track.is_some() {
+            self.current_track = match snapshot.playback_id == self.playback_id {
+                true => snapshot.current_track.map(|index| TrackRef { playlist: id, index }),
+                false => find(current_path.clone()),
+            };
+        }
+        let lost_track = match (previous_track, self.current_track) {
+            (Some(track), None) => current_path.map(|path| (track, path)),
+            _ => None,
+        };
+        if self.resume_track.is_some_and(|track| track.playlist == id) {
+            self.resume_track = find(resume_path);
+        }
+        // Отмена удаления возвращает доигрывающему треку его место в порядке воспроизведения
+        if let Some(removed) = self.removed_track.take_if(|removed| removed.place.playlist == id) {
+            match find(Some(removed.path.clone())) {
+                Some(track) if self.current_track.is_none() && lost_track.is_none() => {
+                    self.current_track = Some(track)
+                }
+                Some(track) => self.resume_track = self.resume_track.or(Some(track)),
+                None => {
+                    let place = TrackRef { index: removed.place.index.min(tracks.len()), ..removed.place };
+                    self.removed_track = Some(RemovedTrack { place, ..removed });
+                }
+            }
+        }
+        if let Some((track, path)) = lost_track {
+            let place = match self.removed_track.take_if(|removed| removed.place.playlist == id) {
+                Some(removed) => removed.place,
+                None => TrackRef { index: track.index.min(tracks.len()), ..track },
+            };
+            self.removed_track = Some(RemovedTrack { place, path });
+        }
+        self.queue = self
+            .queue
+            .iter()
+            .zip(queue_paths)
+            .filter_map(|(&track, path)| if track.playlist == id { find(path) } else { Some(track) })
+            .collect();
+
+        self.set_active_playlist(position);
+        self.changed(StateChange::Playlist);
+        self.changed(StateChange::CurrentTrack);
+        self.changed(StateChange::Queue);
+        snapshot.label
+    }
+
+    /// Индекс текущего трека в открытом плейлисте
+    pub fn current_track(&self) -> Option<usize> {
+        self.current_track.filter(|track| track.playlist == self.active_id()).map(|track| track.index)
+    }
+
+    /// Путь к файлу текущего трека, в том числе уже удаленного из плейлиста
+    pub fn current_track_path(&self) -> Option<&String> {
+        match self.current_track {
+            Some(track) => self.track_path(track),
+            None => self.removed_track.as_ref().map(|removed| &removed.path),
+        }
+    }
+
+    /// Число треков в плейлисте текущего трека, который не обязательно открыт
+    pub fn current_playlist_len(&self) -> usize {
+        let playlist = match (self.current_track, &self.removed_track) {
+            (Some(track), _) => track.playlist,
+            (None, Some(removed)) => removed.place.playlist,
+            (None, None) => return 0,
+        };
+        self.playlist_by_id(playlist).map_or(0, |playlist| playlist.tracks.len())
+    }
+
+    pub fn playback_id(&self) -> u64 {
+        self.playback_id
+    }
+
+    /// Запускает выбранный пользователем трек открытого плейлиста: обычный порядок продолжится
+    /// уже от него
+    pub fn play_track(&mut self, index: usize) {
+        self.play_track_ref(TrackRef { playlist: self.active_id(), index });
+    }
+
+    fn play_track_ref(&mut self, track: TrackRef) {
+        self.resume_track = None;
+        self.removed_track = None;
+        self.launch_track(track);
+    }
+
+    /// Запускает трек с начала: новый трек, нулевая позиция и `Playing` одним изменением
+    fn launch_track(&mut self, track: TrackRef) {
+        if self.track_path(track).is_none() {
+            return;
+        }
+
+        self.current_track = Some(track);
+        self.playback_id += 1;
+        self.current_track_info = None;
+        self.player_state = PlayerState::Playing;
+
+        self.changed(StateChange::CurrentTrack);
+        self.changed(StateChange::CurrentTrackInfo);
+        self.changed(StateChange::PlayerState);
+    }
+
+    /// Останавливает воспроизведение и сбрасывает информацию о треке
+    pub fn stop(&mut self) {
+        self.set_player_state(PlayerState::Stopped);
+        self.set_current_track_info(None);
+    }
+
+    /// Переходит к следующему треку (см. [`State::take_next_track`]), а если его нет - останавливается
+    pub fn skip_forward(&mut self) {
+        match self.take_next_track() {
+            Some(track) => self.launch_track(track),
+            None => self.stop(),
+        }
+    }
+
+    /// Переходит к предыдущему треку плейлиста, с повтором - с первого на последний.
+    /// Если предыдущего трека нет, текущий трек начинается сначала. Для удаленного трека
+    /// предыдущим считается трек перед занявшим его место.
+    pub fn skip_back(&mut self) {
+        let current = match (self.current_track, &self.removed_track) {
+            (Some(track), _) => track,
+            (None, Some(removed)) => removed.place,
+            (None, None) => return,
+        };
+        let len = self.playlist_by_id(current.playlist).map_or(0, |playlist| playlist.tracks.len());
+
+        let index = match current.index {
+            0 if self.repeat_state => len.saturating_sub(1),
+            index => index.saturating_sub(1),
+        };
+        self.play_track_ref(TrackRef { index, ..current });
+    }
+
+    /// Выбирает следующий трек: сначала очередь, затем обычный порядок с места, где он прервался
+    ///
+    /// При перемешивании трек выбирается случайно (кроме текущего), с повтором плейлист
+    /// после последнего трека начинается заново.
+    fn take_next_track(&mut self) -> Option<TrackRef> {
+        while let Some(track) = self.queue.pop_front() {
+            self.changed(StateChange::Queue);
+            if self.track_path(track).is_none() {
+                continue;
+            }
+            // После удаленного трека порядок и так продолжится с занявшего его место
+            if self.resume_track.is_none() && self.removed_track.is_none() {
+                self.resume_track = self.current_track;
+            }
+            return Some(track);
+        }
+
+        // Порядок продолжается после текущего трека, а после удаленного - с занявшего его место
+        let (playlist, current, next) =
+            match (self.resume_track.take(), self.removed_track.take(), self.current_track) {
+                (Some(track), _, _) | (None, None, Some(track)) => {
+                    (track.playlist, Some(track.index), track.index + 1)
+                }
+                (None, Some(removed), _) => (removed.place.playlist, None, removed.place.index),
+                (None, None, None) => (self.active_id(), None, 0),
+            };
+        let len = self.playlist_by_id(playlist).map_or(0, |playlist| playlist.tracks.len());
+
+        let index = if self.shuffle_state && len > 1 {
+            let random = fastrand::usize(..len - 1);
+            match current {
+                Some(current) if random >= current => random + 1,
+                _ => random,
+            }
+        } else {
+            match next {
+                next if next < len => next,
+                _ if self.repeat_state && len > 0 => 0,
+                _ => return None,
+            }
+        };
+        Some(TrackRef { playlist, index })
+    }
+
+    /// Пути к трекам очереди в порядке воспроизведения
+    pub fn queued_tracks(&self) -> impl Iterator<Item = &String> {
+        self.queue.iter().filter_map(|&track| self.track_path(track))
+    }
+
+    /// Путь к треку, который заиграет следующим, если это известно заранее: при перемешивании
+    /// следующий трек вне очереди выбирается случайно только в момент перехода
+    pub fn next_track_path(&self) -> Option<&String> {
+        if let Some(path) = self.queued_tracks().next() {
+            return Some(path);
+        }
+        if self.shuffle_state {
+            return None;
+        }
+
+        let next = match (self.resume_track, &self.removed_track, self.current_track) {
+            (Some(track), _, _) | (None, None, Some(track)) => TrackRef { index: track.index + 1, ..track },
+            (None, Some(removed), _) => removed.place,
+            (None, None, None) => return None,
+        };
+        let len = self.playlist_by_id(next.playlist).map_or(0, |playlist| playlist.tracks.len());
+        let index = match next.index {
+            index if index < len => index,
+            _ if self.repeat_state && len > 0 => 0,
+            _ => return None,
+        };
+        self.track_path(TrackRef { index, ..next })
+    }
+
+    /// Добавляет треки открытого плейлиста в конец очереди
+    pub fn enqueue(&mut self, indices: &[usize]) {
+        let tracks = self.track_refs(indices);
+        self.queue.extend(tracks);
+        self.changed(StateChange::Queue);
+    }
+
+    /// Ставит треки открытого плейлиста в начало очереди, чтобы они заиграли сразу после текущего
+    pub fn enqueue_next(&mut self, indices: &[usize]) {
+        for track in self.track_refs(indices).into_iter().rev() {
+            self.queue.push_front(track);
+        }
+        self.changed(StateChange::Queue);
+    }
+
+    fn track_refs(&self, indices: &[usize]) -> Vec<TrackRef> {
+        let playlist = self.active_id();
+        indices
+            .iter()
+            .filter(|&&index| index < self.playlist().len())
+            .map(|&index| TrackRef { playlist, index })
+            .collect()
+    }
+
+    /// Убирает трек из очереди по его позиции в ней
+    pub fn remove_from_queue(&mut self, position: usize) {
+        if self.queue.remove(position).is_some() {
+            self.changed(StateChange::Queue);
         }
     }
 
+    /// Запускает трек очереди вне порядка, убирая его из очереди
+    pub fn play_from_queue(&mut self, position: usize) {
+        let Some(track) = self.queue.remove(position) else { return };
+
+        self.changed(StateChange::Queue);
+        if self.resume_track.is_none() && self.removed_track.is_none() {
+            self.resume_track = self.current_track;
+        }
+        self.launch_track(track);
+    }
+
     pub fn shuffle_state(&self) -> bool {
-        *self.shuffle_state.lock().unwrap()
+        self.shuffle_state
     }
 
-    pub fn set_repeat_state(&self, value: bool) {
-        if let Ok(mut repeat_state) = self.repeat_state.lock() {
-            *repeat_state = value;
+    pub fn set_shuffle_state(&mut self, value: bool) {
+        if self.shuffle_state != value {
+            self.shuffle_state = value;
+            self.changed(StateChange::ShuffleState);
         }
     }
 
     pub fn repeat_state(&self) -> bool {
-        *self.repeat_state.lock().unwrap()
+        self.repeat_state
     }
 
-    pub fn set_volume(&self, value: f32) {
-        if let Ok(mut volume) = self.volume.lock() {
-            *volume = value;
+    pub fn set_repeat_state(&mut self, value: bool) {
+        if self.repeat_state != value {
+            self.repeat_state = value;
+            self.changed(StateChange::RepeatState);
         }
     }
 
     pub fn volume(&self) -> f32 {
-        *self.volume.lock().unwrap()
+        self.volume
     }
 
-    pub fn add_track(&self, track_file_path: String) {
-        if let Ok(mut playlist) = self.playlist.lock() {
-            playlist.push(track_file_path);
+    pub fn set_volume(&mut self, value: f32) {
+        if self.volume != value {
+            self.volume = value;
+            self.changed(StateChange::Volume);
         }
     }
 
-    pub fn playlist(&self) -> Vec<String> {
-        self.playlist.lock().unwrap().clone()
+    pub fn current_track_info(&self) -> Option<&CurrentTrackInfo> {
+        self.current_track_info.as_ref()
     }
 
-    pub fn set_playlist(&self, value: Vec<String>) {
-        if let Ok(mut playlist) = self.playlist.lock() {
-            *playlist = value;
+    pub fn set_current_track_info(&mut self, value: Option<CurrentTrackInfo>) {
+        if self.current_track_info != value {
+            self.current_track_info = value;
+            self.changed(StateChange::CurrentTrackInfo);
         }
     }
 
-    pub fn set_current_track_info(&self, value: Option<CurrentTrackInfo>) {
-        if let Ok(mut current_track_info) = self.current_track_info.lock() {
-            *current_track_info = value;
+    pub fn terminal_focused(&self) -> bool {
+        self.terminal_focused
+    }
+
+    pub fn set_terminal_focused(&mut self, value: bool) {
+        if self.terminal_focused != value {
+            self.terminal_focused = value;
+            self.changed(StateChange::TerminalFocused);
+        }
+    }
+
+    pub fn library_visible(&self) -> bool {
+        self.library_visible
+    }
+
+    pub fn set_library_visible(&mut self, value: bool) {
+        if self.library_visible != value {
+            self.library_visible = value;
+            self.changed(StateChange::LibraryVisible);
+        }
+    }
+
+    pub fn lyrics_visible(&self) -> bool {
+        self.lyrics_visible
+    }
+
+    pub fn set_lyrics_visible(&mut self, value: bool) {
+        if self.lyrics_visible != value {
+            self.lyrics_visible = value;
+            self.changed(StateChange::LyricsVisible);
+        }
+    }
+
+    pub fn help_open(&self) -> bool {
+        self.help_open
+    }
+
+    pub fn set_help_open(&mut self, value: bool) {
+        if self.help_open != value {
+            self.help_open = value;
+            self.changed(StateChange::Help);
+        }
+    }
+
+    pub fn is_track_missing(&self, path: &str) -> bool {
+        self.missing_tracks.contains(path)
+    }
+
+    pub fn missing_tracks(&self) -> impl Iterator<Item = &String> {
+        self.missing_tracks.iter()
+    }
+
+    /// Отмечает треки как отсутствующие на диске (`missing`) или снова найденные
+    pub fn set_tracks_missing(&mut self, paths: impl IntoIterator<Item = String>, missing: bool) {
+        let mut changed = false;
+        for path in paths {
+            changed |= match missing {
+                true => self.missing_tracks.insert(path),
+                false => self.missing_tracks.remove(&path),
+            };
+        }
+
+        if changed {
+            self.changed(StateChange::MissingTracks);
+        }
+    }
+
+    pub fn tag_editor_tracks(&self) -> &[String] {
+        &self.tag_editor_tracks
+    }
+
+    /// Открывает редактор тегов для треков; пустой список закрывает его
+    pub fn set_tag_editor_tracks(&mut self, tracks: Vec<String>) {
+        if self.tag_editor_tracks != tracks {
+            self.tag_editor_tracks = tracks;
+            self.changed(StateChange::TagEditor);
+        }
+    }
+
+    pub fn organiser_tracks(&self) -> &[String] {
+        &self.organiser_tracks
+    }
+
+    /// Открывает окно раскладки файлов по шаблону; пустой список закрывает его
+    pub fn set_organiser_tracks(&mut self, tracks: Vec<String>) {
+        if self.organiser_tracks != tracks {
+            self.organiser_tracks = tracks;
+            self.changed(StateChange::Organiser);
+        }
+    }
+
+    pub fn status_message(&self) -> Option<&str> {
+        self.status_message.as_ref().map(|message| message.text.as_str())
+    }
+
+    /// Показывает сообщение в строке состояния вместо предыдущего
+    ///
+    /// # Returns:
+    /// Номер сообщения для [`State::clear_status_message`]
+    fn set_status_message(&mut self, text: String) -> u64 {
+        let id = self.status_message.as_ref().map_or(0, |message| message.id + 1);
+        self.status_message = Some(StatusMessage { id, text });
+        self.changed(StateChange::StatusMessage);
+        id
+    }
+
+    /// Убирает сообщение, если его еще не сменило другое
+    fn clear_status_message(&mut self, id: u64) {
+        if self.status_message.as_ref().is_some_and(|message| message.id == id) {
+            self.status_message = None;
+            self.changed(StateChange::StatusMessage);
         }
     }
 
+    pub fn toasts(&self) -> &VecDeque<Toast> {
+        &self.toasts
+    }
+
+    /// Добавляет уведомление, вытесняя самое старое, если их слишком много
+    ///
+    /// # Returns:
+    /// Номер уведомления для [`State::dismiss_toast`]
+    fn push_toast(&mut self, level: ToastLevel, text: String) -> u64 {
+        let id = self.next_toast_id;
+        self.next_toast_id += 1;
+        self.toasts.push_back(Toast { id, level, text });
+        if self.toasts.len() > TOAST_LIMIT {
+            self.toasts.pop_front();
+        }
+        self.changed(StateChange::Toasts);
+        id
+    }
+
+    /// Убирает уведомление, если оно еще показано
+    pub fn dismiss_toast(&mut self, id: u64) {
+        let count = self.toasts.len();
+        self.toasts.retain(|toast| toast.id != id);
+        if self.toasts.len() != count {
+            self.changed(StateChange::Toasts);
+        }
+    }
+}
+
+/// Тип подписчика на изменения состояния
+type Subscriber = dyn Fn(&AppState, &[StateChange]) + Send + Sync;
+
+/// Хранилище состояния приложения с подпиской на изменения
+///
+/// # Fields
+///
+/// * `state` - Единое состояние приложения
+/// * `subscribers` - Подписчики на изменения состояния
+/// * `redraw` - Флаг необходимости перерисовки и уведомление о нем
+#[derive(Clone)]
+pub struct AppState {
+    state: Arc<Mutex<State>>,
+
+    subscribers: Arc<Mutex<Vec<Arc<Subscriber>>>>,
+
+    redraw: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        let app_state = Self {
+            state: Default::default(),
+            subscribers: Default::default(),
+            redraw: Arc::new((Mutex::new(true), Condvar::new())),
+        };
+
+        // Любое изменение состояния может изменить вид интерфейса
+        app_state.subscribe(|app_state, _| app_state.request_redraw());
+
+        app_state
+    }
+}
+
+impl AppState {
+    /// Подписывается на изменения состояния
+    ///
+    /// Подписчик вызывается в потоке, изменившем состояние, после снятия блокировки,
+    /// поэтому может сам читать и изменять состояние.
+    pub fn subscribe<F>(&self, subscriber: F)
+    where
+        F: Fn(&AppState, &[StateChange]) + Send + Sync + 'static,
+    {
+        self.subscribers.lock().unwrap().push(Arc::new(subscriber));
+    }
+
+    /// Атомарно изменяет состояние и уведомляет подписчиков об изменившихся полях
+    pub fn update<R>(&self, update: impl FnOnce(&mut State) -> R) -> R {
+        let (result, changes) = {
+            let mut state = self.state.lock().unwrap();
+            let result = update(&mut state);
+            (result, std::mem::take(&mut state.changes))
+        };
+
+        if !changes.is_empty() {
+            let subscribers = self.subscribers.lock().unwrap().clone();
+            for subscriber in subscribers {
+                subscriber(self, &changes);
+            }
+        }
+
+        result
+    }
+
+    /// Читает согласованный снимок состояния
+    pub fn read<R>(&self, read: impl FnOnce(&State) -> R) -> R {
+        read(&self.state.lock().unwrap())
+    }
+
+    /// Помечает интерфейс как требующий перерисовки и будит главный цикл
+    pub fn request_redraw(&self) {
+        let (dirty, condvar) = &*self.redraw;
+        if let Ok(mut dirty) = dirty.lock() {
+            *dirty = true;
+            condvar.notify_all();
+        }
+    }
+
+    /// Ожидает запроса на перерисовку не дольше `timeout`
+    ///
+    /// # Returns:
+    /// `true`, если перерисовка была запрошена (флаг при этом сбрасывается)
+    pub fn wait_for_redraw(&self, timeout: Duration) -> bool {
+        let (dirty, condvar) = &*self.redraw;
+        let Ok(guard) = dirty.lock() else { return false };
+        let Ok((mut guard, _)) = condvar.wait_timeout_while(guard, timeout, |dirty| !*dirty) else {
+            return false;
+        };
+
+        std::mem::replace(&mut *guard, false)
+    }
+
+    pub fn set_exit(&self, value: bool) {
+        self.update(|state| state.set_exit(value));
+    }
+
+    pub fn should_exit(&self) -> bool {
+        self.read(|state| state.exit())
+    }
+
+    pub fn player_state(&self) -> PlayerState {
+        self.read(|state| state.player_state().clone())
+    }
+
+    pub fn set_shuffle_state(&self, value: bool) {
+        self.update(|state| state.set_shuffle_state(value));
+    }
+
+    pub fn shuffle_state(&self) -> bool {
+        self.read(|state| state.shuffle_state())
+    }
+
+    pub fn set_repeat_state(&self, value: bool) {
+        self.update(|state| state.set_repeat_state(value));
+    }
+
+    pub fn repeat_state(&self) -> bool {
+        self.read(|state| state.repeat_state())
+    }
+
+    pub fn set_volume(&self, value: f32) {
+        self.update(|state| state.set_volume(value));
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.read(|state| state.volume())
+    }
+
+    pub fn playlist(&self) -> Vec<String> {
+        self.read(|state| state.playlist().to_vec())
+    }
+
+    pub fn stop(&self) {
+        self.update(|state| state.stop());
+    }
+
+    pub fn set_current_track_info(&self, value: Option<CurrentTrackInfo>) {
+        self.update(|state| state.set_current_track_info(value));
+    }
+
     pub fn current_track_info(&self) -> Option<CurrentTrackInfo> {
-        self.current_track_info.lock().unwrap().clone()
+        self.read(|state| state.current_track_info().cloned())
+    }
+
+    pub fn set_terminal_focused(&self, value: bool) {
+        self.update(|state| state.set_terminal_focused(value));
+    }
+
+    pub fn terminal_focused(&self) -> bool {
+        self.read(|state| state.terminal_focused())
+    }
+
+    /// Показывает сообщение в строке состояния на несколько секунд
+    pub fn show_status(&self, text: impl Into<String>) {
+        let id = self.update(|state| state.set_status_message(text.into()));
+
+        let app_state = self.clone();
+        thread::spawn(move || {
+            thread::sleep(STATUS_MESSAGE_DURATION);
+            app_state.update(|state| state.clear_status_message(id));
+        });
+    }
+
+    pub fn status_message(&self) -> Option<String> {
+        self.read(|state| state.status_message().map(str::to_string))
+    }
+
+    /// Показывает всплывающее уведомление на несколько секунд
+    pub fn notify(&self, level: ToastLevel, text: impl Into<String>) {
+        let id = self.update(|state| state.push_toast(level, text.into()));
+
+        let app_state = self.clone();
+        thread::spawn(move || {
+            thread::sleep(TOAST_DURATION);
+            app_state.update(|state| state.dismiss_toast(id));
+        });
+    }
+
+    pub fn toasts(&self) -> Vec<Toast> {
+        self.read(|state| state.toasts().iter().cloned().collect())
+    }
+
+    /// Добавляет треки в открытый плейлист так, чтобы добавление можно было отменить
+    ///
+    /// # Args:
+    /// * `separator` - Разделитель сведений в строке состояния из темы
+    pub fn add_tracks(&self, tracks: Vec<String>, separator: &str) {
+        let label = format!("Add {} tracks", tracks.len());
+        self.update(|state| {
+            state.checkpoint_playlist(&label);
+            state.add_tracks(tracks);
+        });
+        self.show_status(format!("{}{}Ctrl-Z to undo", label, separator));
+    }
+
+    pub fn library_visible(&self) -> bool {
+        self.read(|state| state.library_visible())
+    }
+
+    pub fn lyrics_visible(&self) -> bool {
+        self.read(|state| state.lyrics_visible())
+    }
+
+    pub fn tag_editor_open(&self) -> bool {
+        self.read(|state| !state.tag_editor_tracks().is_empty())
+    }
+
+    pub fn organiser_open(&self) -> bool {
+        self.read(|state| !state.organiser_tracks().is_empty())
+    }
+
+    pub fn help_open(&self) -> bool {
+        self.read(|state| state.help_open())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(tracks: &[&str]) -> State {
+        let mut state = State::default();
+        state.set_playlists(
+            vec![("Test".to_string(), tracks.iter().map(|track| track.to_string()).collect())],
+            0,
+        );
+        state
+    }
+
+    fn tracks(state: &State) -> Vec<&str> {
+        state.playlist().iter().map(String::as_str).collect()
+    }
+
+    fn current_path(state: &State) -> Option<&str> {
+        state.current_track_path().map(String::as_str)
+    }
+
+    fn next_path(state: &State) -> Option<&str> {
+        state.next_track_path().map(String::as_str)
+    }
+
+    fn queued(state: &State) -> Vec<&str> {
+        state.queued_tracks().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn moved_playing_track_stays_current() {
+        let mut state = state(&["a", "b", "c", "d"]);
+        state.play_track(0);
+
+        state.move_tracks(&[0], 3);
+        assert_eq!(tracks(&state), ["b", "c", "a", "d"]);
+        assert_eq!(state.current_track(), Some(2));
+        assert_eq!(next_path(&state), Some("d"));
+    }
+
+    #[test]
+    fn removed_playing_track_continues_from_its_place() {
+        let mut state = state(&["a", "b", "c", "d"]);
+        state.play_track(1);
+
+        state.remove_tracks(&[1]);
+        assert_eq!(state.current_track(), None);
+        assert_eq!(current_path(&state), Some("b"));
+        assert_eq!(next_path(&state), Some("c"));
+
+        state.skip_forward();
+        assert_eq!(state.current_track(), Some(1));
+        assert_eq!(current_path(&state), Some("c"));
+    }
+
+    #[test]
+    fn skip_back_from_removed_track_plays_the_one_before_its_place() {
+        let mut state = state(&["a", "b", "c", "d"]);
+        state.play_track(2);
+        state.remove_tracks(&[1, 2]);
+
+        state.skip_back();
+        assert_eq!(current_path(&state), Some("a"));
+    }
+
+    #[test]
+    fn undo_and_redo_round_trip() {
+        let mut state = state(&["a", "b", "c"]);
+        state.checkpoint_playlist("Move");
+        state.move_tracks(&[2], 0);
+        state.checkpoint_playlist("Remove");
+        state.remove_tracks(&[0]);
+        assert_eq!(tracks(&state), ["a", "b"]);
+
+        assert_eq!(state.undo_playlist().as_deref(), Some("Remove"));
+        assert_eq!(tracks(&state), ["c", "a", "b"]);
+        assert_eq!(state.undo_playlist().as_deref(), Some("Move"));
+        assert_eq!(tracks(&state), ["a", "b", "c"]);
+        assert_eq!(state.undo_playlist(), None);
+
+        assert_eq!(state.redo_playlist().as_deref(), Some("Move"));
+        assert_eq!(tracks(&state), ["c", "a", "b"]);
+        assert_eq!(state.redo_playlist().as_deref(), Some("Remove"));
+        assert_eq!(tracks(&state), ["a", "b"]);
+        assert_eq!(state.redo_playlist(), None);
+    }
+
+    #[test]
+    fn new_edit_drops_undone_edits() {
+        let mut state = state(&["a", "b"]);
+        state.checkpoint_playlist("Remove");
+        state.remove_tracks(&[0]);
+        state.undo_playlist();
+
+        state.checkpoint_playlist("Clear");
+        state.clear_playlist();
+        assert_eq!(state.redo_playlist(), None);
+        assert_eq!(state.undo_playlist().as_deref(), Some("Clear"));
+        assert_eq!(tracks(&state), ["a", "b"]);
+    }
+
+    #[test]
+    fn undo_restores_removed_playing_track() {
+        let mut state = state(&["a", "b", "c"]);
+        state.play_track(1);
+        state.checkpoint_playlist("Remove");
+        state.remove_tracks(&[1]);
+
+        state.undo_playlist();
+        assert_eq!(tracks(&state), ["a", "b", "c"]);
+        assert_eq!(state.current_track(), Some(1));
+        assert_eq!(next_path(&state), Some("c"));
+
+        state.redo_playlist();
+        assert_eq!(state.current_track(), None);
+        assert_eq!(current_path(&state), Some("b"));
+        assert_eq!(next_path(&state), Some("c"));
+    }
+
+    #[test]
+    fn undo_finds_current_track_by_path_after_another_track_started() {
+        let mut state = state(&["a", "b", "c"]);
+        state.play_track(0);
+        state.checkpoint_playlist("Move");
+        state.move_tracks(&[0], 3);
+        state.play_track(0);
+        assert_eq!(current_path(&state), Some("b"));
+
+        state.undo_playlist();
+        assert_eq!(state.current_track(), Some(1));
+        assert_eq!(current_path(&state), Some("b"));
+    }
+
+    #[test]
+    fn history_keeps_only_the_latest_edits() {
+        let mut state = state(&[]);
+        for edit in 0..HISTORY_LIMIT + 5 {
+            state.checkpoint_playlist(edit.to_string());
+            state.add_tracks(vec![edit.to_string()]);
+        }
+
+        let undone: Vec<String> = std::iter::from_fn(|| state.undo_playlist()).collect();
+        assert_eq!(undone.len(), HISTORY_LIMIT);
+        assert_eq!(undone.first().map(String::as_str), Some("104"));
+        assert_eq!(undone.last().map(String::as_str), Some("5"));
+        assert_eq!(state.playlist().len(), 5);
+    }
+
+    #[test]
+    fn queue_follows_reordered_tracks() {
+        let mut state = state(&["a", "b", "c", "d"]);
+        state.enqueue(&[3, 1]);
+
+        state.move_tracks(&[3], 0);
+        assert_eq!(tracks(&state), ["d", "a", "b", "c"]);
+        assert_eq!(queued(&state), ["d", "b"]);
+
+        state.remove_tracks(&[0]);
+        assert_eq!(queued(&state), ["b"]);
+
+        state.checkpoint_playlist("Sort");
+        state.rearrange_playlist(&[2, 1, 0]);
+        assert_eq!(queued(&state), ["b"]);
+        state.undo_playlist();
+        assert_eq!(queued(&state), ["b"]);
+        assert_eq!(state.queue.front().map(|track| track.index), Some(1));
+    }
+
+    #[test]
+    fn order_resumes_after_queued_track() {
+        let mut state = state(&["a", "b", "c", "d"]);
+        state.play_track(0);
+        state.enqueue(&[3]);
+
+        state.skip_forward();
+        assert_eq!(current_path(&state), Some("d"));
+        // Порядок продолжается после трека, игравшего до очереди, где бы он теперь ни стоял
+        state.move_tracks(&[1], 0);
+        assert_eq!(tracks(&state), ["b", "a", "c", "d"]);
+        assert_eq!(next_path(&state), Some("c"));
+        state.skip_forward();
+        assert_eq!(current_path(&state), Some("c"));
+    }
+
+    #[test]
+    fn queued_track_after_removed_track_keeps_its_place() {
+        let mut state = state(&["a", "b", "c", "d"]);
+        state.play_track(1);
+        state.enqueue(&[3]);
+        state.remove_tracks(&[1]);
+        assert_eq!(queued(&state), ["d"]);
+
+        state.skip_forward();
+        assert_eq!(current_path(&state), Some("d"));
+        state.skip_forward();
+        assert_eq!(current_path(&state), Some("c"));
     }
 }
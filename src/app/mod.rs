@@ -1,46 +1,107 @@
 mod app_state;
 pub use app_state::AppState;
 pub use app_state::CurrentTrackInfo;
+pub use app_state::NamedPlaylist;
 pub use app_state::PlayerState;
+pub use app_state::State;
+pub use app_state::StateChange;
+pub use app_state::Toast;
+pub use app_state::ToastLevel;
 
 use ratatui::crossterm::event::DisableBracketedPaste;
+use ratatui::crossterm::event::DisableFocusChange;
 use ratatui::crossterm::event::EnableBracketedPaste;
+use ratatui::crossterm::event::EnableFocusChange;
 use ratatui::{
     buffer::Buffer,
     crossterm::{
         self,
-        event::{DisableMouseCapture, EnableMouseCapture},
+        event::{DisableMouseCapture, EnableMouseCapture, KeyCode},
     },
     layout::{Constraint, Direction, Flex, Layout, Rect},
     style::Stylize,
-    symbols::border,
-    text::Line,
+    text::{Line, Span},
     widgets::{Block, Padding, Widget},
     DefaultTerminal,
 };
 
 use std::io::Error;
-use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::interaction::{EventHandler, InteractiveWidget};
 use crate::{
+    album_art::{ArtOutput, GraphicsProtocol},
     components::{
-        last_track_button, next_track_button, play_button, playlist_widget, progress_bar, repeat_toggle,
-        shuffle_toggle, stop_button, volume_control,
+        file_browser, help_overlay, last_track_button, library_browser, lyrics_panel, mini_player,
+        next_track_button, now_playing, organiser, play_button, playlist_tabs, playlist_widget, progress_bar,
+        queue_widget, repeat_toggle, shuffle_toggle, stop_button, tag_editor, toasts, visualizer,
+        volume_control, TOAST_HEIGHT,
     },
+    config::{Config, LayoutPreset},
+    library::Library,
+    metadata::MetadataCache,
+    play_counts::PlayCounts,
     player::Player,
+    playlist_store::PlaylistStore,
+    theme::Theme,
+    watcher::watch_tracks,
+    waveform::WaveformCache,
 };
 
-const FRAME_TIME: u64 = 62; // ~ 16 fps
+/// Минимальный интервал между кадрами (~ 60 fps), чтобы не перерисовывать на каждое движение мыши
+const MIN_FRAME_TIME: Duration = Duration::from_millis(16);
+
+/// Размеры (ширина, высота) модальных окон
+const TAG_EDITOR_SIZE: (u16, u16) = (72, 22);
+const ORGANISER_SIZE: (u16, u16) = (100, 30);
+const HELP_SIZE: (u16, u16) = (90, 40);
+
+/// Строк под сведения о треке в панели текущего трека
+const NOW_PLAYING_INFO_HEIGHT: u16 = 7;
+
+/// Ширина столбца всплывающих уведомлений
+const TOASTS_WIDTH: u16 = 40;
+
+/// Высота панели визуализации вместе с рамкой
+const VISUALIZER_HEIGHT: u16 = 8;
+/// Высота полосы прогресса и ряда кнопок вместе с рамками
+const PROGRESS_BAR_HEIGHT: u16 = 3;
+const CONTROLS_HEIGHT: u16 = 3;
+/// Наименьшая высота вкладок и списков, при которой над ними еще помещается визуализация
+const MIN_UPPER_HEIGHT: u16 = 10;
+
+/// Ширина кнопок, регулятора громкости и переключателей
+const BUTTON_WIDTH: u16 = 9;
+const VOLUME_CONTROL_WIDTH: u16 = 12;
+const TOGGLE_WIDTH: u16 = 6;
+
+/// С какой ширины рядом с плейлистом помещается обозреватель, а с какой - еще и колонка
+/// с текущим треком и очередью
+const TWO_COLUMNS_MIN_WIDTH: u16 = 90;
+const THREE_COLUMNS_MIN_WIDTH: u16 = 140;
+
+/// Наименьший размер терминала, при котором раскладка `auto` еще рисует панели, а не мини-плеер
+const MIN_FULL_LAYOUT_WIDTH: u16 = 40;
+const MIN_FULL_LAYOUT_HEIGHT: u16 = 14;
+
+/// Как долго главный цикл ждет запроса перерисовки, прежде чем проверить флаг выхода
+const REDRAW_WAIT_TIMEOUT: Duration = Duration::from_millis(500);
+/// Как часто перерисовывается интерфейс в режиме простоя
+const IDLE_FRAME_TIME: Duration = Duration::from_millis(500);
 
 /// Главное приложение
 pub struct App {
     app_state: AppState,
     event_handler: EventHandler,
-    player: Player,
 
     playlist: InteractiveWidget,
+    playlist_tabs: InteractiveWidget,
+    queue: InteractiveWidget,
+    file_browser: InteractiveWidget,
+    library_browser: InteractiveWidget,
+    now_playing: InteractiveWidget,
+    lyrics_panel: InteractiveWidget,
+    visualizer: InteractiveWidget,
     progress_bar: InteractiveWidget,
     play_button: InteractiveWidget,
     last_track_button: InteractiveWidget,
@@ -49,30 +110,103 @@ pub struct App {
     volume_control: InteractiveWidget,
     shuffle_toggle: InteractiveWidget,
     repeat_toggle: InteractiveWidget,
+    tag_editor: InteractiveWidget,
+    organiser: InteractiveWidget,
+    help_overlay: InteractiveWidget,
+    mini_player: InteractiveWidget,
+    toasts: InteractiveWidget,
+    art_output: ArtOutput,
+    playlist_store: PlaylistStore,
+    theme: Theme,
+    layout: LayoutPreset,
 }
 
 impl Default for App {
     fn default() -> Self {
         let app_state = AppState::default();
-        
+
         let mut event_handler = EventHandler::new(&app_state);
-        let player = Player::new(&app_state);
-
-        let playlist = event_handler.register_component(playlist_widget(&app_state));
-        let progress_bar = event_handler.register_component(progress_bar(&app_state, &player));
-        let play_button = event_handler.register_component(play_button(&app_state, &player));
-        let last_track_button = event_handler.register_component(last_track_button(&app_state));
-        let next_track_button = event_handler.register_component(next_track_button(&app_state));
-        let stop_button = event_handler.register_component(stop_button(&app_state, &player));
-        let volume_control = event_handler.register_component(volume_control(&app_state, &player));
-        let shuffle_toggle = event_handler.register_component(shuffle_toggle(&app_state));
-        let repeat_toggle = event_handler.register_component(repeat_toggle(&app_state));
+        event_handler.register_component(app_bindings(&app_state));
+        let config = Config::load();
+        let metadata_cache = MetadataCache::open();
+        let player = Player::new(&app_state, &metadata_cache);
+        let play_counts = PlayCounts::load();
+        play_counts.track(&app_state);
+        let playlist_store = PlaylistStore::open();
+        let library = Library::open(&config.library, &metadata_cache);
+        library.rescan(&app_state);
+        let theme = Theme::load(&config.theme);
+        watch_tracks(&app_state, &library, &config.library, &theme);
+        let art_protocol = config.now_playing.art_protocol.unwrap_or_else(GraphicsProtocol::detect);
+        let art_output = ArtOutput::new(art_protocol);
+
+        let playlist = event_handler.register_component(playlist_widget(
+            &app_state,
+            &event_handler,
+            &metadata_cache,
+            &play_counts,
+            &playlist_store,
+            &library,
+            &config.playlist,
+            &theme,
+        ));
+        let playlist_tabs =
+            event_handler.register_component(playlist_tabs(&app_state, &event_handler, &theme));
+        let queue = event_handler.register_component(queue_widget(&app_state, &metadata_cache, &theme));
+        let file_browser = event_handler.register_component(file_browser(
+            &app_state,
+            &metadata_cache,
+            &config.library,
+            &theme,
+        ));
+        let library_browser = event_handler.register_component(library_browser(&app_state, &library, &theme));
+        let now_playing =
+            event_handler.register_component(now_playing(&app_state, &metadata_cache, &art_output, &theme));
+        let lyrics_panel = event_handler.register_component(lyrics_panel(&app_state, &player, &theme));
+        let visualizer = event_handler.register_component(visualizer(&app_state, &player, &theme));
+        let waveforms = WaveformCache::default();
+        let progress_bar = event_handler.register_component(progress_bar(
+            &app_state,
+            &player,
+            &waveforms,
+            &config.progress_bar,
+            &theme,
+        ));
+        let play_button = event_handler.register_component(play_button(&app_state, &theme));
+        let last_track_button = event_handler.register_component(last_track_button(&app_state, &theme));
+        let next_track_button = event_handler.register_component(next_track_button(&app_state, &theme));
+        let stop_button = event_handler.register_component(stop_button(&app_state, &theme));
+        let volume_control = event_handler.register_component(volume_control(&app_state, &theme));
+        let shuffle_toggle = event_handler.register_component(shuffle_toggle(&app_state, &theme));
+        let repeat_toggle = event_handler.register_component(repeat_toggle(&app_state, &theme));
+        let mini_player = event_handler.register_component(mini_player(&app_state, &player, &theme));
+        // Модальные окна регистрируются последними, чтобы лежать поверх остальных виджетов
+        let tag_editor = event_handler.register_component(tag_editor(&app_state, &metadata_cache, &theme));
+        let organiser =
+            event_handler.register_component(organiser(&app_state, &metadata_cache, &config.library, &theme));
+        let help_overlay = event_handler.register_component(help_overlay(&app_state, &event_handler, &theme));
+        let toasts = event_handler.register_component(toasts(&app_state, &theme));
+        track_modals(
+            &app_state,
+            &event_handler,
+            [
+                (tag_editor.clone(), TAG_EDITOR_SIZE),
+                (organiser.clone(), ORGANISER_SIZE),
+                (help_overlay.clone(), HELP_SIZE),
+            ],
+        );
 
         Self {
             app_state,
             event_handler,
-            player,
             playlist,
+            playlist_tabs,
+            queue,
+            file_browser,
+            library_browser,
+            now_playing,
+            lyrics_panel,
+            visualizer,
             progress_bar,
             play_button,
             last_track_button,
@@ -81,6 +215,15 @@ impl Default for App {
             volume_control,
             shuffle_toggle,
             repeat_toggle,
+            tag_editor,
+            organiser,
+            help_overlay,
+            mini_player,
+            toasts,
+            art_output,
+            playlist_store,
+            theme,
+            layout: config.layout.preset,
         }
     }
 }
@@ -89,107 +232,290 @@ impl App {
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), Error> {
         self.setup()?;
         self.main_loop(terminal)?;
+        // Последние правки могли еще не дождаться фоновой записи
+        self.playlist_store.flush(&self.app_state);
         self.cleanup()?;
         Ok(())
     }
 
     fn setup(&mut self) -> Result<(), Error> {
         // Включаем захват мыши
-        crossterm::execute!(
-            std::io::stdout(),
-            EnableMouseCapture,
-            EnableBracketedPaste
-        )?;
-
-        // Устанавливаем громкость по умолчанию на 100%
-        self.app_state.set_volume(1.0);
+        crossterm::execute!(std::io::stdout(), EnableMouseCapture, EnableBracketedPaste, EnableFocusChange)?;
 
         // Запускаем обработчик событий
         self.event_handler.start()?;
 
-        // Запускаем обработчик прогресса воспроизведения трека
-        launch_track_progression_handler(&self.app_state);
-
         Ok(())
     }
 
     fn cleanup(&self) -> Result<(), Error> {
-        crossterm::execute!(
-            std::io::stdout(),
-            DisableMouseCapture,
-            DisableBracketedPaste
-        )
+        crossterm::execute!(std::io::stdout(), DisableMouseCapture, DisableBracketedPaste, DisableFocusChange)
     }
 
+    /// Перерисовывает интерфейс только по запросу: при изменении состояния, тике позиции
+    /// воспроизведения или пользовательском вводе
+    ///
+    /// В режиме простоя запросы не теряются, а копятся и рисуются одним кадром не чаще
+    /// [`IDLE_FRAME_TIME`]: окно без фокуса может быть видно, и остановка или уведомление
+    /// должны в нем появиться.
     fn main_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<(), Error> {
+        let mut pending = false;
+        let mut last_frame = Instant::now();
         while !self.app_state.should_exit() {
-            terminal.draw(|frame| frame.render_widget(&mut *self, frame.area()))?;
-            std::thread::sleep(std::time::Duration::from_millis(FRAME_TIME));
+            pending |= self.app_state.wait_for_redraw(REDRAW_WAIT_TIMEOUT);
+            if !pending || (self.is_idle() && last_frame.elapsed() < IDLE_FRAME_TIME) {
+                continue;
+            }
+            pending = false;
+
+            let frame_start = Instant::now();
+            last_frame = frame_start;
+            let frame_area = terminal.draw(|frame| frame.render_widget(&mut *self, frame.area()))?.area;
+            self.art_output.flush(frame_area)?;
+
+            if let Some(remaining) = MIN_FRAME_TIME.checked_sub(frame_start.elapsed()) {
+                std::thread::sleep(remaining);
+            }
         }
-        self.player.stop();
+        self.app_state.stop();
 
         Ok(())
     }
+
+    /// Режим простоя: воспроизведение не идет, а терминал не в фокусе - кадры нужны редко
+    fn is_idle(&self) -> bool {
+        self.app_state.player_state() != PlayerState::Playing && !self.app_state.terminal_focused()
+    }
 }
 
 impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        let layout = self.resolve_layout(area);
+        if layout == LayoutPreset::Mini {
+            self.render_mini_player(area, buf);
+            return;
+        }
+        self.mini_player.hide();
+
         let block = self.create_main_block();
         let inner = block.inner(area);
         block.render(area, buf);
 
-        let [upper_area, progress_bar_area, controls_area] = self.create_layout(inner);
-        self.render_upper_section(upper_area, buf);
+        let [upper_area, visualizer_area, progress_bar_area, controls_area] =
+            self.create_layout(inner, layout);
+        // Картинка протоколов терминала выводится поверх кадра и закрыла бы модальное окно
+        self.art_output.set_enabled(self.event_handler.overlays().is_empty());
+        self.render_upper_section(upper_area, layout, buf);
+        render_or_hide(&mut self.visualizer, visualizer_area, buf);
         self.render_progress_bar(progress_bar_area, buf);
         self.render_controls(controls_area, buf);
+        self.render_overlays(area, buf);
+        self.hide_closed_modals();
+        self.render_toasts(upper_area, buf);
+        self.keep_focus_visible(&self.playlist.clone());
     }
 }
 
 impl App {
-    fn create_main_block(&self) -> Block {
+    fn create_main_block(&self) -> Block<'_> {
+        let theme = &self.theme;
+        let status = self.app_state.status_message().map(|message| format!(" {} ", message));
+        let key = |key: &str| Span::styled(key.to_string(), theme.key());
+        let separator = || Span::from(theme.borders.main.horizontal_bottom.repeat(3));
+
         Block::bordered()
-            .title(Line::from(" 𝄞 TMP 𝄞 ".bold()).centered())
-            .title_bottom(Line::from(vec![
-                " Quit ".into(), "<Esc> ".blue(),
-                "━━━".into(),
-                " Play/Pause ".into(), "<Space> ".blue()
-            ]).left_aligned())
+            .title(Line::from(theme.glyphs.title.bold()).centered())
+            .title(Line::from(status.unwrap_or_default().fg(theme.palette.accent)).right_aligned())
+            // Остальные клавиши перечислены в справке, которая строится по привязкам виджетов
+            .title_bottom(
+                Line::from(vec![
+                    " Quit ".into(),
+                    key("<Esc> "),
+                    separator(),
+                    " Help ".into(),
+                    key("<?> "),
+                    separator(),
+                    " Play/Pause ".into(),
+                    key("<Space> "),
+                ])
+                .left_aligned(),
+            )
             .padding(Padding::new(1, 1, 0, 0))
-            .border_set(border::THICK)
+            .border_set(theme.borders.main)
     }
 
-    fn create_layout(&self, area: Rect) -> [Rect; 3] {
+    /// Раскладка для терминала размером `area`: `auto` выбирает мини-плеер, если панели не помещаются
+    fn resolve_layout(&self, area: Rect) -> LayoutPreset {
+        let fits = area.width >= MIN_FULL_LAYOUT_WIDTH && area.height >= MIN_FULL_LAYOUT_HEIGHT;
+        match self.layout {
+            LayoutPreset::Auto if fits => LayoutPreset::Full,
+            LayoutPreset::Auto => LayoutPreset::Mini,
+            layout => layout,
+        }
+    }
+
+    /// Визуализация получает место, только если списки при этом не становятся слишком низкими
+    fn create_layout(&self, area: Rect, layout: LayoutPreset) -> [Rect; 4] {
+        let min_height = MIN_UPPER_HEIGHT + VISUALIZER_HEIGHT + PROGRESS_BAR_HEIGHT + CONTROLS_HEIGHT;
+        let visualizer_visible = layout == LayoutPreset::Full && area.height >= min_height;
+        let visualizer_height = if visualizer_visible { VISUALIZER_HEIGHT } else { 0 };
+
         Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(10), Constraint::Length(3), Constraint::Length(3)])
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Length(visualizer_height),
+                Constraint::Length(PROGRESS_BAR_HEIGHT),
+                Constraint::Length(CONTROLS_HEIGHT),
+            ])
             .areas(area)
     }
 
-    fn render_upper_section(&mut self, area: Rect, buf: &mut Buffer) {
-        self.playlist.render(area, buf);
+    /// Мини-плеер вместо всех панелей
+    fn render_mini_player(&mut self, area: Rect, buf: &mut Buffer) {
+        for widget in [
+            &mut self.playlist,
+            &mut self.playlist_tabs,
+            &mut self.queue,
+            &mut self.file_browser,
+            &mut self.library_browser,
+            &mut self.now_playing,
+            &mut self.lyrics_panel,
+            &mut self.visualizer,
+            &mut self.progress_bar,
+            &mut self.play_button,
+            &mut self.last_track_button,
+            &mut self.next_track_button,
+            &mut self.stop_button,
+            &mut self.volume_control,
+            &mut self.shuffle_toggle,
+            &mut self.repeat_toggle,
+            &mut self.toasts,
+        ] {
+            widget.hide();
+        }
+        self.art_output.set_enabled(false);
+
+        self.mini_player.render(area, buf);
+        self.render_overlays(area, buf);
+        self.hide_closed_modals();
+        self.keep_focus_visible(&self.mini_player.clone());
+    }
+
+    /// Фокус скрытого виджета переходит к `fallback`, иначе клавиши уходили бы невидимому виджету
+    fn keep_focus_visible(&mut self, fallback: &InteractiveWidget) {
+        if self.event_handler.focused().is_some_and(|focused| !focused.is_visible()) {
+            self.event_handler.focus(fallback);
+        }
+    }
+
+    fn render_upper_section(&mut self, area: Rect, layout: LayoutPreset, buf: &mut Buffer) {
+        let [tabs_area, lists_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+        // На узком экране первой пропадает колонка текущего трека, затем обозреватель
+        let (browser_visible, side_visible) = match layout {
+            LayoutPreset::Compact => (false, false),
+            _ => (lists_area.width >= TWO_COLUMNS_MIN_WIDTH, lists_area.width >= THREE_COLUMNS_MIN_WIDTH),
+        };
+        // Медиатеке из трех колонок нужно больше места, чем обозревателю файлов
+        let library_visible = self.app_state.library_visible();
+        let browser_width = match (browser_visible, library_visible) {
+            (false, _) => 0,
+            (true, true) => 40,
+            (true, false) => 25,
+        };
+        let side_width = if side_visible { 25 } else { 0 };
+        let [browser_area, playlist_area, queue_area] = Layout::horizontal([
+            Constraint::Percentage(browser_width),
+            Constraint::Fill(1),
+            Constraint::Percentage(side_width),
+        ])
+        .areas(lists_area);
+        // Ячейка терминала примерно вдвое выше своей ширины, так что квадратной обложке нужно
+        // вдвое меньше строк, чем колонок
+        let now_playing_height = (queue_area.width.saturating_sub(2) / 2 + 2 + NOW_PLAYING_INFO_HEIGHT)
+            .min(queue_area.height * 2 / 3);
+        let [now_playing_area, queue_area] =
+            Layout::vertical([Constraint::Length(now_playing_height), Constraint::Fill(1)]).areas(queue_area);
+
+        // Текст песни и очередь делят одно место
+        let (shown_list, hidden_list) = match self.app_state.lyrics_visible() {
+            true => (&mut self.lyrics_panel, &mut self.queue),
+            false => (&mut self.queue, &mut self.lyrics_panel),
+        };
+        hidden_list.hide();
+        if hidden_list.is_focused() {
+            self.event_handler.focus(shown_list);
+        }
+        render_or_hide(shown_list, queue_area, buf);
+
+        let (shown, hidden) = match library_visible {
+            true => (&mut self.library_browser, &mut self.file_browser),
+            false => (&mut self.file_browser, &mut self.library_browser),
+        };
+        // Фокус остается на левой панели, когда она меняет содержимое
+        hidden.hide();
+        if hidden.is_focused() {
+            self.event_handler.focus(shown);
+        }
+        render_or_hide(shown, browser_area, buf);
+        self.playlist_tabs.render(tabs_area, buf);
+        self.playlist.render(playlist_area, buf);
+        render_or_hide(&mut self.now_playing, now_playing_area, buf);
+    }
+
+    /// Окно, закрытое во время отрисовки кадра, могло снова получить область
+    /// и перехватывало бы мышь
+    fn hide_closed_modals(&mut self) {
+        let overlays = self.event_handler.overlays();
+        for modal in [&mut self.tag_editor, &mut self.organiser, &mut self.help_overlay] {
+            if !overlays.iter().any(|(overlay, _)| overlay.ptr_eq(modal)) {
+                modal.hide();
+            }
+        }
+    }
+
+    /// Стопка модальных окон поверх интерфейса, каждое по центру экрана
+    fn render_overlays(&mut self, area: Rect, buf: &mut Buffer) {
+        for (overlay, (width, height)) in self.event_handler.overlays() {
+            let [overlay_area] =
+                Layout::horizontal([Constraint::Length(width)]).flex(Flex::Center).areas(area);
+            let [overlay_area] =
+                Layout::vertical([Constraint::Length(height)]).flex(Flex::Center).areas(overlay_area);
+            overlay.render(overlay_area, buf);
+        }
+    }
+
+    /// Уведомления в правом нижнем углу `area`, поверх панелей и модальных окон
+    fn render_toasts(&mut self, area: Rect, buf: &mut Buffer) {
+        let height = (TOAST_HEIGHT * self.app_state.toasts().len() as u16).min(area.height);
+        let [toasts_area] =
+            Layout::horizontal([Constraint::Length(TOASTS_WIDTH)]).flex(Flex::End).areas(area);
+        let [toasts_area] = Layout::vertical([Constraint::Length(height)]).flex(Flex::End).areas(toasts_area);
+        render_or_hide(&mut self.toasts, toasts_area, buf);
     }
 
     fn render_progress_bar(&mut self, area: Rect, buf: &mut Buffer) {
         self.progress_bar.render(area, buf);
     }
 
+    /// На узком экране первыми пропадают переключатели, а кнопки сужаются
     fn render_controls(&mut self, area: Rect, buf: &mut Buffer) {
+        let toggles_visible = area.width >= 4 * BUTTON_WIDTH + VOLUME_CONTROL_WIDTH + 2 * TOGGLE_WIDTH;
+        let right_width = match toggles_visible {
+            true => VOLUME_CONTROL_WIDTH + 2 * TOGGLE_WIDTH,
+            false => VOLUME_CONTROL_WIDTH,
+        };
         let [left_controls, right_controls] =
-            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area);
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(right_width)]).areas(area);
 
         self.render_left_controls(left_controls, buf);
-        self.render_right_controls(right_controls, buf);
+        self.render_right_controls(right_controls, toggles_visible, buf);
     }
 
     fn render_left_controls(&mut self, area: Rect, buf: &mut Buffer) {
-        let areas = Layout::horizontal([
-            Constraint::Length(9),
-            Constraint::Length(9),
-            Constraint::Length(9),
-            Constraint::Length(9),
-        ])
-        .flex(Flex::Start)
-        .areas(area);
+        let width = (area.width / 4).min(BUTTON_WIDTH);
+        let areas = Layout::horizontal([Constraint::Length(width); 4]).flex(Flex::Start).areas(area);
 
         let [play, last, next, stop] = areas;
         self.play_button.render(play, buf);
@@ -198,36 +524,68 @@ impl App {
         self.stop_button.render(stop, buf);
     }
 
-    fn render_right_controls(&mut self, area: Rect, buf: &mut Buffer) {
-        let areas =
-            Layout::horizontal([Constraint::Length(12), Constraint::Length(6), Constraint::Length(6)])
-                .flex(Flex::End)
-                .areas(area);
+    fn render_right_controls(&mut self, area: Rect, toggles_visible: bool, buf: &mut Buffer) {
+        let toggle_width = if toggles_visible { TOGGLE_WIDTH } else { 0 };
+        let areas = Layout::horizontal([
+            Constraint::Length(VOLUME_CONTROL_WIDTH),
+            Constraint::Length(toggle_width),
+            Constraint::Length(toggle_width),
+        ])
+        .flex(Flex::End)
+        .areas(area);
 
         let [volume, shuffle, repeat] = areas;
         self.volume_control.render(volume, buf);
-        self.shuffle_toggle.render(shuffle, buf);
-        self.repeat_toggle.render(repeat, buf);
+        render_or_hide(&mut self.shuffle_toggle, shuffle, buf);
+        render_or_hide(&mut self.repeat_toggle, repeat, buf);
     }
 }
 
-//TODO перейти на symphonia + cpal + rubato и убрать - это костыль
-fn launch_track_progression_handler(app_state: &AppState) {
-    let app_state = app_state.clone();
-    thread::spawn(move || {
-        loop {
-            if let Some(info) = app_state.current_track_info() {
-                if app_state.player_state() == PlayerState::Playing {
-                    app_state.set_current_track_info(Some(CurrentTrackInfo::new(
-                        info.title, 
-                        info.artist, 
-                        info.album, 
-                        info.duration, 
-                        Duration::from_secs(info.played_duration.as_secs() + 1)
-                    )));
+/// Открывает и закрывает модальные окна приложения сразу при изменении состояния, а не при
+/// отрисовке: клавиша, нажатая до следующего кадра, должна достаться уже окну
+///
+/// # Args:
+/// * `modals` - Редактор тегов, окно раскладки и справка с их размерами
+fn track_modals(
+    app_state: &AppState,
+    event_handler: &EventHandler,
+    modals: [(InteractiveWidget, (u16, u16)); 3],
+) {
+    let event_handler = event_handler.clone();
+    app_state.subscribe(move |app_state, changes| {
+        let modal_changes = [StateChange::TagEditor, StateChange::Organiser, StateChange::Help];
+        if !modal_changes.iter().any(|change| changes.contains(change)) {
+            return;
+        }
+
+        let open = [app_state.tag_editor_open(), app_state.organiser_open(), app_state.help_open()];
+        for ((modal, size), open) in modals.iter().zip(open) {
+            match open {
+                true => event_handler.open_overlay(modal, *size),
+                false => {
+                    event_handler.close_overlay(modal);
+                    modal.clone().hide();
                 }
             }
-            thread::sleep(Duration::from_secs(1));
         }
     });
 }
+
+/// Привязки самого приложения, не относящиеся ни к одному виджету
+fn app_bindings(app_state: &AppState) -> InteractiveWidget {
+    InteractiveWidget::default()
+        .help_section("Application")
+        .on_global_key_down(KeyCode::Esc, {
+            let app_state = app_state.clone();
+            move |_, _| app_state.set_exit(true)
+        })
+        .describe("Quit")
+}
+
+/// Рисует виджет, если для него нашлось место, иначе убирает его с экрана
+fn render_or_hide(widget: &mut InteractiveWidget, area: Rect, buf: &mut Buffer) {
+    match area.is_empty() {
+        true => widget.hide(),
+        false => widget.render(area, buf),
+    }
+}
@@ -0,0 +1,125 @@
+use std::{
+    sync::{
+        atomic::{AtomicU32, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use rodio::{source::SeekError, Source};
+
+/// Сколько последних выборок хранит отвод
+const CAPACITY: usize = 8192;
+
+/// Кольцевой буфер последних выборок
+///
+/// # Fields
+///
+/// * `samples` - Выборки (моно) в виде битов `f32`
+/// * `written` - Сколько выборок записано за все время; по нему читатель находит последние
+/// * `sample_rate` - Частота дискретизации записываемого потока
+struct TapBuffer {
+    samples: Box<[AtomicU32]>,
+    written: AtomicUsize,
+    sample_rate: AtomicU32,
+}
+
+/// Отвод выборок, которые проигрыватель отдает на звуковой вывод, для визуализации
+///
+/// Звуковой поток только записывает выборки в кольцевой буфер атомарных ячеек и никогда
+/// не ждет читателя. Читатель копирует последние выборки и изредка может получить часть
+/// из них вперемешку с более новыми, что для визуализации не заметно.
+#[derive(Clone)]
+pub struct AudioTap {
+    buffer: Arc<TapBuffer>,
+}
+
+impl Default for AudioTap {
+    fn default() -> Self {
+        let buffer = TapBuffer {
+            samples: (0..CAPACITY).map(|_| AtomicU32::new(0)).collect(),
+            written: AtomicUsize::new(0),
+            sample_rate: AtomicU32::new(0),
+        };
+        Self { buffer: Arc::new(buffer) }
+    }
+}
+
+impl AudioTap {
+    fn push(&self, sample: f32) {
+        let written = self.buffer.written.load(Ordering::Relaxed);
+        self.buffer.samples[written % CAPACITY].store(sample.to_bits(), Ordering::Relaxed);
+        self.buffer.written.store(written + 1, Ordering::Release);
+    }
+
+    /// Последние `count` выборок (не больше емкости буфера), от старых к новым
+    pub fn latest(&self, count: usize) -> Vec<f32> {
+        let written = self.buffer.written.load(Ordering::Acquire);
+        let count = count.min(CAPACITY).min(written);
+        (written - count..written)
+            .map(|index| f32::from_bits(self.buffer.samples[index % CAPACITY].load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.buffer.sample_rate.load(Ordering::Relaxed)
+    }
+}
+
+/// Источник звука, который по пути на вывод сводит каналы каждого кадра в моно
+/// и пишет результат в [`AudioTap`]
+pub struct Tapped<S> {
+    source: S,
+    tap: AudioTap,
+    frame_sum: f32,
+    channel: u16,
+}
+
+impl<S: Source<Item = f32>> Tapped<S> {
+    pub fn new(source: S, tap: AudioTap) -> Self {
+        tap.buffer.sample_rate.store(source.sample_rate(), Ordering::Relaxed);
+        Self { source, tap, frame_sum: 0.0, channel: 0 }
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for Tapped<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.source.next()?;
+
+        let channels = self.source.channels().max(1);
+        self.frame_sum += sample;
+        self.channel += 1;
+        if self.channel >= channels {
+            self.tap.push(self.frame_sum / channels as f32);
+            self.frame_sum = 0.0;
+            self.channel = 0;
+        }
+        Some(sample)
+    }
+}
+
+impl<S: Source<Item = f32>> Source for Tapped<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.frame_sum = 0.0;
+        self.channel = 0;
+        self.source.try_seek(position)
+    }
+}
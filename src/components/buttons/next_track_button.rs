@@ -1,41 +1,44 @@
 use ratatui::{
     buffer::Buffer,
-    layout::{Position, Rect},
+    crossterm::event::KeyCode,
+    layout::Rect,
     text::Line,
-    widgets::{Block, BorderType, Paragraph, Widget},
+    widgets::{Paragraph, Widget},
 };
 
 use crate::{
     app::AppState,
     interaction::{InteractionState, InteractiveWidget},
+    theme::Theme,
 };
 
-const NEXT_TRACK_BUTTON_LABEL: &str = "▷▷|";
-const NEXT_TRACK_BUTTON_LABEL_HOVERED: &str = "▶▶|";
-const NEXT_TRACK_BUTTON_LABEL_PRESSED: &str = "▸▸|";
-
 /// Создает кнопку перехода к следующему треку
-pub fn next_track_button(app_state: &AppState) -> InteractiveWidget {
-    let app_state = app_state.clone();
-
+pub fn next_track_button(app_state: &AppState, theme: &Theme) -> InteractiveWidget {
     InteractiveWidget::default()
-        .on_mouse_down(move |_: &mut InteractiveWidget, _: Position| on_click(&app_state))
-        .draw(draw_next_track_button)
+        .focusable()
+        .help_section("Playback controls")
+        .on_mouse_down({
+            let app_state = app_state.clone();
+            move |_, _| on_click(&app_state)
+        })
+        .describe("Next track")
+        .on_key_down(KeyCode::Enter, {
+            let app_state = app_state.clone();
+            move |_, _| on_click(&app_state)
+        })
+        .describe("Next track")
+        .draw({
+            let theme = *theme;
+            move |widget_state, area, buf| draw_next_track_button(widget_state, &theme, area, buf)
+        })
 }
 
-fn on_click(_: &AppState) {
-
+fn on_click(app_state: &AppState) {
+    app_state.update(|state| state.skip_forward());
 }
 
-fn draw_next_track_button(widget_state: InteractionState, area: Rect, buf: &mut Buffer) {
-    let view = match widget_state {
-        InteractionState::Hovered => Paragraph::new(Line::from(NEXT_TRACK_BUTTON_LABEL_HOVERED).centered())
-            .block(Block::bordered().border_type(BorderType::Thick)),
-        InteractionState::Pressed => {
-            Paragraph::new(Line::from(NEXT_TRACK_BUTTON_LABEL_PRESSED).centered()).block(Block::bordered())
-        }
-        _ => Paragraph::new(Line::from(NEXT_TRACK_BUTTON_LABEL).centered()).block(Block::bordered()),
-    };
-
-    view.render(area, buf);
+fn draw_next_track_button(widget_state: InteractionState, theme: &Theme, area: Rect, buf: &mut Buffer) {
+    Paragraph::new(Line::from(theme.glyphs.next.label(&widget_state)).centered())
+        .block(theme.block(&widget_state))
+        .render(area, buf);
 }
@@ -1,89 +1,64 @@
 use ratatui::{
-    buffer::Buffer, crossterm::event::KeyCode, layout::Rect, text::Line, widgets::{Block, BorderType, Paragraph, Widget}
+    buffer::Buffer,
+    crossterm::event::KeyCode,
+    layout::Rect,
+    text::Line,
+    widgets::{Paragraph, Widget},
 };
 
 use crate::{
     app::{AppState, PlayerState},
     interaction::{InteractionState, InteractiveWidget},
-    player::Player,
+    theme::Theme,
 };
 
-const PLAY_BUTTON_LABEL: &str = "▷";
-const PLAY_BUTTON_LABEL_HOVERED: &str = "▶";
-const PLAY_BUTTON_LABEL_PRESSED: &str = "▸";
-
-const PLAY_BUTTON_LABEL_PAUSE: &str = "‖";
-const PLAY_BUTTON_LABEL_PAUSE_HOVERED: &str = "‖";
-const PLAY_BUTTON_LABEL_PAUSE_PRESSED: &str = "∥";
-
-pub fn play_button(app_state: &AppState, player: &Player) -> InteractiveWidget {
+pub fn play_button(app_state: &AppState, theme: &Theme) -> InteractiveWidget {
     InteractiveWidget::default()
+        .focusable()
+        .help_section("Playback controls")
         .on_mouse_down({
             let app_state = app_state.clone();
-            let player = player.clone();
-
-            move |_, _| on_click(&app_state, &player)
+            move |_, _| on_click(&app_state)
         })
-        .on_key_down(KeyCode::Char(' '), {
+        .describe("Play/pause")
+        .on_key_down(KeyCode::Enter, {
             let app_state = app_state.clone();
-            let player = player.clone();
-
-            move |_, _| on_click(&app_state, &player)
+            move |_, _| on_click(&app_state)
         })
+        .describe("Play/pause")
+        .on_global_key_down(KeyCode::Char(' '), {
+            let app_state = app_state.clone();
+            move |_, _| on_click(&app_state)
+        })
+        .describe("Play/pause")
         .draw({
             let app_state = app_state.clone();
-            move |widget_state, area, buf| draw_play_button(widget_state, &app_state, area, buf)
+            let theme = *theme;
+            move |widget_state, area, buf| draw_play_button(widget_state, &app_state, &theme, area, buf)
         })
 }
 
-fn on_click(app_state: &AppState, player: &Player) {
-
-    match app_state.player_state() {
-        PlayerState::Playing => {
-            player.pause();
-            app_state.set_player_state(PlayerState::Paused);
-        }
-        PlayerState::Paused => {
-            player.resume();
-            app_state.set_player_state(PlayerState::Playing);
-        }
-        PlayerState::Stopped => {
-            if let Some(path) = app_state.playlist().get(0) {
-                player.play(path.to_string());
-                app_state.set_player_state(PlayerState::Playing);
-            }
-        }
-    }
+fn on_click(app_state: &AppState) {
+    app_state.update(|state| match state.player_state() {
+        PlayerState::Playing => state.set_player_state(PlayerState::Paused),
+        PlayerState::Paused => state.set_player_state(PlayerState::Playing),
+        PlayerState::Stopped => state.play_track(state.current_track().unwrap_or(0)),
+    });
 }
 
-fn draw_play_button(widget_state: InteractionState, app_state: &AppState, area: Rect, buf: &mut Buffer) {
-    let view = match widget_state {
-        InteractionState::Hovered => {
-            if app_state.player_state() == PlayerState::Playing {
-                Paragraph::new(Line::from(PLAY_BUTTON_LABEL_PAUSE_HOVERED).centered())
-                    .block(Block::bordered().border_type(BorderType::Thick))
-            } else {
-                Paragraph::new(Line::from(PLAY_BUTTON_LABEL_HOVERED).centered())
-                    .block(Block::bordered().border_type(BorderType::Thick))
-            }
-        }
-        InteractionState::Pressed => {
-            //Меняем местами,чтобы лого кнопки менялось после отжатия, а не сразу
-            if app_state.player_state() == PlayerState::Playing {
-                Paragraph::new(Line::from(PLAY_BUTTON_LABEL_PRESSED).centered()).block(Block::bordered())
-            } else {
-                Paragraph::new(Line::from(PLAY_BUTTON_LABEL_PAUSE_PRESSED).centered())
-                    .block(Block::bordered())
-            }
-        }
-        _ => {
-            if app_state.player_state() == PlayerState::Playing {
-                Paragraph::new(Line::from(PLAY_BUTTON_LABEL_PAUSE).centered()).block(Block::bordered())
-            } else {
-                Paragraph::new(Line::from(PLAY_BUTTON_LABEL).centered()).block(Block::bordered())
-            }
-        }
-    };
+fn draw_play_button(
+    widget_state: InteractionState,
+    app_state: &AppState,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let playing = app_state.player_state() == PlayerState::Playing;
+    //Меняем местами,чтобы лого кнопки менялось после отжатия, а не сразу
+    let pressed = widget_state == InteractionState::Pressed;
+    let glyphs = if playing != pressed { theme.glyphs.pause } else { theme.glyphs.play };
 
-    view.render(area, buf);
+    Paragraph::new(Line::from(glyphs.label(&widget_state)).centered())
+        .block(theme.block(&widget_state))
+        .render(area, buf);
 }
@@ -1,44 +1,43 @@
 use ratatui::{
     buffer::Buffer,
+    crossterm::event::KeyCode,
     layout::Rect,
     text::Line,
-    widgets::{Block, BorderType, Paragraph, Widget},
+    widgets::{Paragraph, Widget},
 };
 
 use crate::{
-    app::{AppState, PlayerState},
+    app::AppState,
     interaction::{InteractionState, InteractiveWidget},
-    player::Player,
+    theme::Theme,
 };
 
-const STOP_BUTTON_LABEL: &str = "▢";
-const STOP_BUTTON_LABEL_HOVERED: &str = "■";
-const STOP_BUTTON_LABEL_PRESSED: &str = "▪";
-
-pub fn stop_button(app_state: &AppState, player: &Player) -> InteractiveWidget {
-    let app_state = app_state.clone();
-    let player = player.clone();
-
+pub fn stop_button(app_state: &AppState, theme: &Theme) -> InteractiveWidget {
     InteractiveWidget::default()
-        .on_mouse_down(move |_, _| on_click(&app_state, &player))
-        .draw(draw_stop_button)
+        .focusable()
+        .help_section("Playback controls")
+        .on_mouse_down({
+            let app_state = app_state.clone();
+            move |_, _| on_click(&app_state)
+        })
+        .describe("Stop")
+        .on_key_down(KeyCode::Enter, {
+            let app_state = app_state.clone();
+            move |_, _| on_click(&app_state)
+        })
+        .describe("Stop")
+        .draw({
+            let theme = *theme;
+            move |widget_state, area, buf| draw_stop_button(widget_state, &theme, area, buf)
+        })
 }
 
-fn on_click(app_state: &AppState, player: &Player) {
-    app_state.set_player_state(PlayerState::Stopped);
-    app_state.set_current_track_info(None);
-    player.stop();
+fn on_click(app_state: &AppState) {
+    app_state.stop();
 }
 
-fn draw_stop_button(widget_state: InteractionState, area: Rect, buf: &mut Buffer) {
-    let view = match widget_state {
-        InteractionState::Hovered => Paragraph::new(Line::from(STOP_BUTTON_LABEL_HOVERED).centered())
-            .block(Block::bordered().border_type(BorderType::Thick)),
-        InteractionState::Pressed => {
-            Paragraph::new(Line::from(STOP_BUTTON_LABEL_PRESSED).centered()).block(Block::bordered())
-        }
-        _ => Paragraph::new(Line::from(STOP_BUTTON_LABEL).centered()).block(Block::bordered()),
-    };
-
-    view.render(area, buf);
+fn draw_stop_button(widget_state: InteractionState, theme: &Theme, area: Rect, buf: &mut Buffer) {
+    Paragraph::new(Line::from(theme.glyphs.stop.label(&widget_state)).centered())
+        .block(theme.block(&widget_state))
+        .render(area, buf);
 }
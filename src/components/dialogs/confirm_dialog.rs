@@ -0,0 +1,204 @@
+use std::sync::{Arc, Mutex};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::{Constraint, Layout, Position, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Clear, Padding, Paragraph, Widget, Wrap},
+};
+
+use crate::{
+    interaction::{EventHandler, InteractionState, InteractiveWidget},
+    theme::Theme,
+};
+
+/// Границы ширины окна вместе с рамкой
+const MIN_WIDTH: u16 = 30;
+const MAX_WIDTH: u16 = 70;
+/// Рамка, отступы по бокам и пустая строка с кнопками под сообщением
+const HORIZONTAL_CHROME: u16 = 4;
+const VERTICAL_CHROME: u16 = 4;
+const CANCEL_LABEL: &str = "Cancel";
+
+/// Обработчик подтверждения
+type ConfirmHandler = dyn Fn() + Send + Sync;
+
+/// Состояние окна подтверждения
+///
+/// # Fields
+///
+/// * `title` - Заголовок окна
+/// * `message` - Вопрос
+/// * `confirm_label` - Надпись на кнопке подтверждения
+/// * `confirm_selected` - Выбрана кнопка подтверждения, а не отмены
+/// * `button_bounds` - Горизонтальные границы кнопок (относительно окна) на последней отрисовке
+struct ConfirmView {
+    title: String,
+    message: String,
+    confirm_label: &'static str,
+    confirm_selected: bool,
+    button_bounds: [(u16, u16); 2],
+}
+
+/// Открывает окно подтверждения поверх интерфейса
+///
+/// Enter нажимает выбранную кнопку, стрелки и Tab переключают кнопки, `y` и `n` нажимают их сразу,
+/// Esc отменяет. `on_confirm` вызывается уже после закрытия окна, поэтому может открыть следующее.
+///
+/// # Args:
+/// * `title` - Заголовок окна
+/// * `message` - Вопрос; длинный переносится по словам
+/// * `confirm_label` - Надпись на кнопке подтверждения, например, `Delete`
+pub fn confirm_dialog<F>(
+    event_handler: &EventHandler,
+    theme: &Theme,
+    title: &str,
+    message: &str,
+    confirm_label: &'static str,
+    on_confirm: F,
+) -> InteractiveWidget
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    let view = Arc::new(Mutex::new(ConfirmView {
+        title: format!(" {} ", title),
+        message: message.to_string(),
+        confirm_label,
+        confirm_selected: true,
+        button_bounds: [(0, 0); 2],
+    }));
+    let on_confirm: Arc<ConfirmHandler> = Arc::new(on_confirm);
+
+    let widget = InteractiveWidget::default()
+        .focusable()
+        .on_key_input({
+            let event_handler = event_handler.clone();
+            let view = view.clone();
+            let on_confirm = on_confirm.clone();
+            move |widget, key_event| on_input(widget, &event_handler, &view, &on_confirm, key_event)
+        })
+        .on_mouse_down({
+            let event_handler = event_handler.clone();
+            let view = view.clone();
+            move |widget, mouse_position| on_click(widget, &event_handler, &view, &on_confirm, mouse_position)
+        })
+        .draw({
+            let theme = *theme;
+            move |widget_state, area, buf| draw_confirm_dialog(widget_state, &view, &theme, area, buf)
+        });
+
+    event_handler.open_overlay(&widget, dialog_size(title, message));
+    widget
+}
+
+/// Размер окна: сообщение по возможности в одну строку, иначе с переносами
+fn dialog_size(title: &str, message: &str) -> (u16, u16) {
+    let message_width = message.chars().count() as u16;
+    let width =
+        (message_width.max(title.chars().count() as u16 + 2) + HORIZONTAL_CHROME).clamp(MIN_WIDTH, MAX_WIDTH);
+    let lines = message_width.div_ceil(width - HORIZONTAL_CHROME).max(1);
+    (width, lines + VERTICAL_CHROME)
+}
+
+fn on_input(
+    widget: &InteractiveWidget,
+    event_handler: &EventHandler,
+    view: &Mutex<ConfirmView>,
+    on_confirm: &Arc<ConfirmHandler>,
+    key_event: KeyEvent,
+) -> bool {
+    let confirmed = match key_event.code {
+        KeyCode::Enter => Some(view.lock().unwrap().confirm_selected),
+        KeyCode::Char('y') => Some(true),
+        KeyCode::Char('n') | KeyCode::Esc => Some(false),
+        KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab => {
+            let mut view = view.lock().unwrap();
+            view.confirm_selected = !view.confirm_selected;
+            None
+        }
+        _ => None,
+    };
+
+    if let Some(confirmed) = confirmed {
+        close(widget, event_handler, confirmed, on_confirm);
+    }
+    true
+}
+
+fn on_click(
+    widget: &InteractiveWidget,
+    event_handler: &EventHandler,
+    view: &Mutex<ConfirmView>,
+    on_confirm: &Arc<ConfirmHandler>,
+    mouse_position: Position,
+) {
+    let button = {
+        let view = view.lock().unwrap();
+        let buttons_row = widget.area().height.saturating_sub(2);
+        match mouse_position.y == buttons_row {
+            true => {
+                view.button_bounds.iter().position(|&(x, width)| (x..x + width).contains(&mouse_position.x))
+            }
+            false => None,
+        }
+    };
+
+    if let Some(button) = button {
+        close(widget, event_handler, button == 0, on_confirm);
+    }
+}
+
+fn close(
+    widget: &InteractiveWidget,
+    event_handler: &EventHandler,
+    confirmed: bool,
+    on_confirm: &Arc<ConfirmHandler>,
+) {
+    event_handler.close_overlay(widget);
+    if confirmed {
+        on_confirm();
+    }
+}
+
+fn draw_confirm_dialog(
+    widget_state: InteractionState,
+    view: &Mutex<ConfirmView>,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let mut view = view.lock().unwrap();
+    let block = theme.panel(&widget_state).title(view.title.clone()).padding(Padding::horizontal(1));
+
+    Clear.render(area, buf);
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    let [message_area, _, buttons_area] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(1), Constraint::Length(1)]).areas(inner);
+    Paragraph::new(view.message.clone()).wrap(Wrap { trim: true }).render(message_area, buf);
+
+    let button = |label: &str, selected: bool| {
+        let style = match selected {
+            true => Style::new().fg(theme.palette.accent_text).bg(theme.palette.accent),
+            false => Style::new().fg(theme.palette.primary),
+        };
+        Span::styled(format!(" {} ", label), style)
+    };
+    let buttons = Line::from(vec![
+        button(view.confirm_label, view.confirm_selected),
+        "  ".into(),
+        button(CANCEL_LABEL, !view.confirm_selected),
+    ])
+    .centered();
+
+    // Кнопки выводятся по центру строки
+    let confirm_width = view.confirm_label.chars().count() as u16 + 2;
+    let cancel_width = CANCEL_LABEL.chars().count() as u16 + 2;
+    let left = buttons_area.x - area.x + buttons_area.width.saturating_sub(buttons.width() as u16) / 2;
+    view.button_bounds = [(left, confirm_width), (left + confirm_width + 2, cancel_width)];
+
+    buttons.render(buttons_area, buf);
+}
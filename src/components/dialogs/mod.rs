@@ -0,0 +1,8 @@
+mod confirm_dialog;
+pub use confirm_dialog::confirm_dialog;
+
+mod prompt_dialog;
+pub use prompt_dialog::prompt_dialog;
+
+mod select_dialog;
+pub use select_dialog::select_dialog;
@@ -0,0 +1,121 @@
+use std::sync::{Arc, Mutex};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Clear, Widget},
+};
+
+use crate::{
+    interaction::{EventHandler, InteractionState, InteractiveWidget},
+    theme::Theme,
+};
+
+/// Размер окна ввода вместе с рамкой
+const PROMPT_SIZE: (u16, u16) = (50, 3);
+
+/// Обработчик введенной строки
+type SubmitHandler = dyn Fn(String) + Send + Sync;
+
+/// Создает и открывает окно ввода строки поверх интерфейса
+///
+/// Enter подтверждает ввод, Esc отменяет, Ctrl-U очищает строку; вставка из буфера обмена
+/// дописывается в конец. `on_submit` вызывается уже после закрытия окна.
+///
+/// # Args:
+/// * `title` - Заголовок окна, он же подсказка, что нужно ввести
+/// * `initial` - Начальное значение строки
+pub fn prompt_dialog<F>(
+    event_handler: &EventHandler,
+    theme: &Theme,
+    title: &str,
+    initial: &str,
+    on_submit: F,
+) -> InteractiveWidget
+where
+    F: Fn(String) + Send + Sync + 'static,
+{
+    let value = Arc::new(Mutex::new(initial.to_string()));
+    let on_submit: Arc<SubmitHandler> = Arc::new(on_submit);
+
+    let widget = InteractiveWidget::default()
+        .focusable()
+        .on_key_input({
+            let event_handler = event_handler.clone();
+            let value = value.clone();
+            move |widget, key_event| on_input(widget, &event_handler, &value, &on_submit, key_event)
+        })
+        .on_paste({
+            let value = value.clone();
+            move |_, paste_event| {
+                value.lock().unwrap().extend(paste_event.chars().filter(|c| !c.is_control()))
+            }
+        })
+        .draw({
+            let theme = *theme;
+            let title = format!(" {} ", title);
+            move |widget_state, area, buf| draw_prompt_dialog(widget_state, &value, &theme, &title, area, buf)
+        });
+
+    event_handler.open_overlay(&widget, PROMPT_SIZE);
+    widget
+}
+
+fn on_input(
+    widget: &InteractiveWidget,
+    event_handler: &EventHandler,
+    value: &Mutex<String>,
+    on_submit: &Arc<SubmitHandler>,
+    key_event: KeyEvent,
+) -> bool {
+    let control = key_event.modifiers.contains(KeyModifiers::CONTROL);
+    match key_event.code {
+        KeyCode::Enter => {
+            event_handler.close_overlay(widget);
+            let value = value.lock().unwrap().clone();
+            on_submit(value);
+        }
+        KeyCode::Esc => event_handler.close_overlay(widget),
+        KeyCode::Backspace => {
+            value.lock().unwrap().pop();
+        }
+        KeyCode::Char('u') if control => value.lock().unwrap().clear(),
+        KeyCode::Char(c) if !key_event.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            value.lock().unwrap().push(c)
+        }
+        _ => {}
+    }
+    true
+}
+
+fn draw_prompt_dialog(
+    widget_state: InteractionState,
+    value: &Mutex<String>,
+    theme: &Theme,
+    title: &str,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let block = theme.panel(&widget_state).title(title.to_string()).title_bottom(Line::from(vec![
+        " OK ".into(),
+        Span::styled("<Enter> ", theme.key()),
+        " Cancel ".into(),
+        Span::styled("<Esc> ", theme.key()),
+    ]));
+
+    Clear.render(area, buf);
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    // Длинная строка прокручивается так, чтобы был виден ее конец и курсор
+    let value = value.lock().unwrap();
+    let cursor_width = theme.glyphs.cursor.chars().count();
+    let visible = (inner.width as usize).saturating_sub(cursor_width);
+    let skipped = value.chars().count().saturating_sub(visible);
+    let text: String = value.chars().skip(skipped).collect();
+
+    Line::from(vec![text.into(), theme.glyphs.cursor.fg(theme.palette.accent)]).render(inner, buf);
+}
@@ -0,0 +1,223 @@
+use std::sync::{Arc, Mutex};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent},
+    layout::Rect,
+    style::{Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Clear, List, ListState, StatefulWidget, Widget},
+};
+
+use crate::{
+    interaction::{EventHandler, InteractionState, InteractiveWidget},
+    theme::Theme,
+};
+
+/// Границы ширины окна вместе с рамкой
+const MIN_WIDTH: u16 = 24;
+const MAX_WIDTH: u16 = 70;
+/// Сколько пунктов видно без прокрутки
+const MAX_VISIBLE_ITEMS: u16 = 12;
+/// Рамка и номер пункта перед названием
+const HORIZONTAL_CHROME: u16 = 4;
+/// Высота рамки, на которую смещены пункты
+const BORDER_WIDTH: u16 = 1;
+
+/// Обработчик выбранного пункта
+type SelectHandler = dyn Fn(usize) + Send + Sync;
+
+/// Состояние списка выбора
+///
+/// # Fields
+///
+/// * `items` - Названия пунктов
+/// * `list_state` - Выделенный пункт и прокрутка
+struct SelectView {
+    items: Vec<String>,
+    list_state: ListState,
+}
+
+impl SelectView {
+    fn move_selection(&mut self, offset: isize) {
+        let last = self.items.len().saturating_sub(1) as isize;
+        let selected = self.list_state.selected().unwrap_or(0) as isize;
+        self.list_state.select(Some((selected + offset).clamp(0, last) as usize));
+    }
+
+    /// Пункт в строке `y` (относительно окна)
+    fn item_at(&self, y: u16) -> Option<usize> {
+        let row = y.checked_sub(BORDER_WIDTH)? as usize;
+        Some(self.list_state.offset() + row).filter(|&index| index < self.items.len())
+    }
+}
+
+/// Создает и открывает список выбора поверх интерфейса
+///
+/// Стрелки, колесо и наведение мыши выделяют пункт, Enter и щелчок выбирают его, цифры 1-9 выбирают
+/// пункт с этим номером сразу, Esc отменяет. `on_select` получает номер пункта уже после закрытия окна.
+///
+/// # Args:
+/// * `title` - Заголовок окна
+/// * `items` - Названия пунктов
+/// * `selected` - Пункт, выделенный при открытии
+pub fn select_dialog<F>(
+    event_handler: &EventHandler,
+    theme: &Theme,
+    title: &str,
+    items: Vec<String>,
+    selected: usize,
+    on_select: F,
+) -> InteractiveWidget
+where
+    F: Fn(usize) + Send + Sync + 'static,
+{
+    let size = dialog_size(title, &items);
+    let list_state = ListState::default().with_selected(Some(selected.min(items.len().saturating_sub(1))));
+    let view = Arc::new(Mutex::new(SelectView { items, list_state }));
+    let on_select: Arc<SelectHandler> = Arc::new(on_select);
+
+    let widget = InteractiveWidget::default()
+        .focusable()
+        .on_key_input({
+            let event_handler = event_handler.clone();
+            let view = view.clone();
+            let on_select = on_select.clone();
+            move |widget, key_event| on_input(widget, &event_handler, &view, &on_select, key_event)
+        })
+        .on_mouse_down({
+            let event_handler = event_handler.clone();
+            let view = view.clone();
+            move |widget, mouse_position| {
+                let item = view.lock().unwrap().item_at(mouse_position.y);
+                if let Some(item) = item {
+                    select(widget, &event_handler, &on_select, item);
+                }
+            }
+        })
+        .on_mouse_move({
+            let view = view.clone();
+            move |_, mouse_position| {
+                let mut view = view.lock().unwrap();
+                if let Some(item) = view.item_at(mouse_position.y) {
+                    view.list_state.select(Some(item));
+                }
+            }
+        })
+        .on_mouse_scroll_up({
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().move_selection(-1)
+        })
+        .on_mouse_scroll_down({
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().move_selection(1)
+        })
+        .draw({
+            let theme = *theme;
+            let title = format!(" {} ", title);
+            move |widget_state, area, buf| draw_select_dialog(widget_state, &view, &theme, &title, area, buf)
+        });
+
+    event_handler.open_overlay(&widget, size);
+    widget
+}
+
+/// Размер окна: по самому длинному пункту, но не больше [`MAX_VISIBLE_ITEMS`] строк
+fn dialog_size(title: &str, items: &[String]) -> (u16, u16) {
+    let longest = items.iter().map(|item| item.chars().count()).max().unwrap_or_default() as u16;
+    let width =
+        (longest + HORIZONTAL_CHROME).max(title.chars().count() as u16 + 4).clamp(MIN_WIDTH, MAX_WIDTH);
+    let height = (items.len() as u16).clamp(1, MAX_VISIBLE_ITEMS) + 2 * BORDER_WIDTH;
+    (width, height)
+}
+
+fn on_input(
+    widget: &InteractiveWidget,
+    event_handler: &EventHandler,
+    view: &Mutex<SelectView>,
+    on_select: &Arc<SelectHandler>,
+    key_event: KeyEvent,
+) -> bool {
+    let mut locked = view.lock().unwrap();
+    let item = match key_event.code {
+        KeyCode::Enter => locked.list_state.selected(),
+        KeyCode::Char(c) => c.to_digit(10).and_then(|digit| (digit as usize).checked_sub(1)),
+        KeyCode::Esc => {
+            drop(locked);
+            event_handler.close_overlay(widget);
+            return true;
+        }
+        KeyCode::Up => {
+            locked.move_selection(-1);
+            None
+        }
+        KeyCode::Down => {
+            locked.move_selection(1);
+            None
+        }
+        KeyCode::PageUp => {
+            locked.move_selection(-(MAX_VISIBLE_ITEMS as isize));
+            None
+        }
+        KeyCode::PageDown => {
+            locked.move_selection(MAX_VISIBLE_ITEMS as isize);
+            None
+        }
+        KeyCode::Home => {
+            locked.list_state.select_first();
+            None
+        }
+        KeyCode::End => {
+            let last = locked.items.len().saturating_sub(1);
+            locked.list_state.select(Some(last));
+            None
+        }
+        _ => None,
+    };
+
+    let item = item.filter(|&item| item < locked.items.len());
+    drop(locked);
+    if let Some(item) = item {
+        select(widget, event_handler, on_select, item);
+    }
+    true
+}
+
+fn select(
+    widget: &InteractiveWidget,
+    event_handler: &EventHandler,
+    on_select: &Arc<SelectHandler>,
+    item: usize,
+) {
+    event_handler.close_overlay(widget);
+    on_select(item);
+}
+
+fn draw_select_dialog(
+    widget_state: InteractionState,
+    view: &Mutex<SelectView>,
+    theme: &Theme,
+    title: &str,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let mut view = view.lock().unwrap();
+    let block = theme.panel(&widget_state).title(title.to_string());
+
+    let items: Vec<Line> = view
+        .items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let number = match index < 9 {
+                true => format!("{} ", index + 1),
+                false => "  ".to_string(),
+            };
+            Line::from(vec![number.fg(theme.palette.muted), Span::from(item.clone())])
+        })
+        .collect();
+    let list = List::new(items).block(block).highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+    Clear.render(area, buf);
+    StatefulWidget::render(list, area, buf, &mut view.list_state);
+}
@@ -0,0 +1,434 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::KeyCode,
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, StatefulWidget, Widget},
+};
+
+use crate::{
+    app::AppState,
+    components::playback::format_duration,
+    config::LibraryConfig,
+    files::{is_playlist_file, is_track_file, walk_path},
+    interaction::{InteractionState, InteractiveWidget},
+    metadata::{file_name, MetadataCache},
+    theme::Theme,
+};
+
+/// Высота рамки, на которую смещены строки списка
+const BORDER_WIDTH: u16 = 1;
+/// Высота панели предпросмотра тегов вместе с разделителем
+const PREVIEW_HEIGHT: u16 = 5;
+/// На сколько строк перемещается выделение при прокрутке страницы
+const PAGE_SIZE: usize = 10;
+const PARENT_LABEL: &str = "..";
+
+#[derive(Clone, Copy, PartialEq)]
+enum EntryKind {
+    Parent,
+    Directory,
+    Track,
+    Playlist,
+}
+
+struct Entry {
+    path: PathBuf,
+    kind: EntryKind,
+}
+
+/// Состояние обозревателя файлов
+///
+/// # Fields
+///
+/// * `roots` - Каталоги медиатеки; выше них подняться нельзя
+/// * `dir` - Открытый каталог; `None` - список каталогов медиатеки
+/// * `entries` - Содержимое открытого каталога: подкаталоги, треки и плейлисты
+/// * `list_state` - Выделение и прокрутка списка
+/// * `list_height` - Высота списка на последней отрисовке
+/// * `previewed` - Трек, теги которого уже запрошены для предпросмотра
+struct BrowserView {
+    roots: Vec<PathBuf>,
+    dir: Option<PathBuf>,
+    entries: Vec<Entry>,
+    list_state: ListState,
+    list_height: u16,
+    previewed: Option<PathBuf>,
+}
+
+impl BrowserView {
+    /// Единственный каталог медиатеки открывается сразу, из нескольких предлагается выбрать
+    fn new(roots: Vec<PathBuf>) -> Self {
+        let dir = match roots.as_slice() {
+            [root] => Some(root.clone()),
+            _ => None,
+        };
+
+        let mut view = Self {
+            roots,
+            dir: None,
+            entries: vec![],
+            list_state: ListState::default(),
+            list_height: 0,
+            previewed: None,
+        };
+        view.open(dir, None);
+        view
+    }
+
+    /// Открывает каталог и выделяет в нем запись `select` или первую запись
+    fn open(&mut self, dir: Option<PathBuf>, select: Option<&Path>) {
+        self.dir = dir;
+        self.entries = match &self.dir {
+            Some(dir) => self.read_entries(dir),
+            None => self
+                .roots
+                .iter()
+                .map(|root| Entry { path: root.clone(), kind: EntryKind::Directory })
+                .collect(),
+        };
+
+        let selected = select.and_then(|path| self.entries.iter().position(|entry| entry.path == path));
+        self.list_state =
+            ListState::default().with_selected((!self.entries.is_empty()).then_some(selected.unwrap_or(0)));
+    }
+
+    /// Подкаталоги, затем треки и плейлисты, каждые по имени. Скрытые файлы пропускаются.
+    fn read_entries(&self, dir: &Path) -> Vec<Entry> {
+        let mut directories = vec![];
+        let mut files = vec![];
+
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+
+            if path.is_dir() {
+                directories.push(Entry { path, kind: EntryKind::Directory });
+            } else if is_track_file(&path) {
+                files.push(Entry { path, kind: EntryKind::Track });
+            } else if is_playlist_file(&path) {
+                files.push(Entry { path, kind: EntryKind::Playlist });
+            }
+        }
+
+        let by_name =
+            |entry: &Entry| entry.path.file_name().map(|name| name.to_string_lossy().to_lowercase());
+        directories.sort_by_key(by_name);
+        files.sort_by_key(by_name);
+
+        let parent =
+            self.parent_dir().map(|_| Entry { path: PathBuf::from(PARENT_LABEL), kind: EntryKind::Parent });
+        parent.into_iter().chain(directories).chain(files).collect()
+    }
+
+    /// Куда ведет переход на уровень выше: `Some(None)` - к списку каталогов медиатеки,
+    /// `None` - выше подняться нельзя
+    fn parent_dir(&self) -> Option<Option<PathBuf>> {
+        let dir = self.dir.as_ref()?;
+        if self.roots.contains(dir) {
+            return (self.roots.len() > 1).then_some(None);
+        }
+        Some(dir.parent().map(Path::to_path_buf))
+    }
+
+    /// Поднимается на уровень выше и выделяет каталог, из которого вышли
+    fn go_up(&mut self) {
+        if let Some(parent) = self.parent_dir() {
+            let from = self.dir.clone();
+            self.open(parent, from.as_deref());
+        }
+    }
+
+    fn selected(&self) -> Option<(EntryKind, PathBuf)> {
+        let entry = self.entries.get(self.list_state.selected()?)?;
+        Some((entry.kind, entry.path.clone()))
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let Some(last) = self.entries.len().checked_sub(1) else { return };
+        let selected = self.list_state.selected().unwrap_or(0) as isize + offset;
+        self.list_state.select(Some(selected.clamp(0, last as isize) as usize));
+    }
+
+    /// Выделяет первую или последнюю (`last`) запись
+    fn select_edge(&mut self, last: bool) {
+        let index = if last { self.entries.len().checked_sub(1) } else { Some(0) };
+        self.list_state.select(index.filter(|&index| index < self.entries.len()));
+    }
+}
+
+/// Создает обозреватель файлов для добавления музыки в плейлист
+///
+/// Enter открывает каталог или добавляет файл, Backspace поднимается на уровень выше,
+/// `a` добавляет выделенный каталог со всеми подкаталогами. Щелчок по выделенной записи
+/// действует как Enter.
+pub fn file_browser(
+    app_state: &AppState,
+    metadata_cache: &MetadataCache,
+    config: &LibraryConfig,
+    theme: &Theme,
+) -> InteractiveWidget {
+    let view = Arc::new(Mutex::new(BrowserView::new(config.root_dirs())));
+
+    InteractiveWidget::default()
+        .focusable()
+        .help_section("File browser")
+        .on_key_down(KeyCode::Up, {
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().move_selection(-1)
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::Down, {
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().move_selection(1)
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::PageUp, {
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().move_selection(-(PAGE_SIZE as isize))
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::PageDown, {
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().move_selection(PAGE_SIZE as isize)
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::Home, {
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().select_edge(false)
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::End, {
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().select_edge(true)
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::Enter, {
+            let app_state = app_state.clone();
+            let view = view.clone();
+            let theme = *theme;
+            move |_, _| activate(&app_state, &view, &theme)
+        })
+        .describe("Open folder, add file")
+        .on_key_down(KeyCode::Right, {
+            let view = view.clone();
+            move |_, _| open_selected(&view)
+        })
+        .describe("Open folder")
+        .on_key_down(KeyCode::Left, {
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().go_up()
+        })
+        .describe("Parent folder")
+        .on_key_down(KeyCode::Backspace, {
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().go_up()
+        })
+        .describe("Parent folder")
+        .on_key_down(KeyCode::Char('a'), {
+            let app_state = app_state.clone();
+            let view = view.clone();
+            let theme = *theme;
+            move |_, _| add_selected(&app_state, &view, &theme)
+        })
+        .describe("Add folder")
+        .on_mouse_down({
+            let app_state = app_state.clone();
+            let view = view.clone();
+            let theme = *theme;
+            move |_, mouse_position| on_click(&app_state, &view, &theme, mouse_position)
+        })
+        .describe("Select, click again to open")
+        .on_mouse_scroll_up({
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().move_selection(-1)
+        })
+        .describe("Move cursor")
+        .on_mouse_scroll_down({
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().move_selection(1)
+        })
+        .describe("Move cursor")
+        .draw({
+            let app_state = app_state.clone();
+            let metadata_cache = metadata_cache.clone();
+            let theme = *theme;
+            move |widget_state, area, buf| {
+                draw_file_browser(widget_state, &app_state, &metadata_cache, &view, &theme, area, buf)
+            }
+        })
+}
+
+/// Открывает выделенный каталог или добавляет выделенный файл в плейлист
+fn activate(app_state: &AppState, view: &Mutex<BrowserView>, theme: &Theme) {
+    let selected = view.lock().unwrap().selected();
+    match selected {
+        Some((EntryKind::Track | EntryKind::Playlist, path)) => add_to_playlist(app_state, &path, theme),
+        Some(_) => open_selected(view),
+        None => {}
+    }
+}
+
+fn open_selected(view: &Mutex<BrowserView>) {
+    let mut view = view.lock().unwrap();
+    match view.selected() {
+        Some((EntryKind::Parent, _)) => view.go_up(),
+        Some((EntryKind::Directory, path)) => view.open(Some(path), None),
+        _ => {}
+    }
+}
+
+fn add_selected(app_state: &AppState, view: &Mutex<BrowserView>, theme: &Theme) {
+    let selected = view.lock().unwrap().selected();
+    if let Some((kind, path)) = selected {
+        if kind != EntryKind::Parent {
+            add_to_playlist(app_state, &path, theme);
+        }
+    }
+}
+
+/// Добавляет в открытый плейлист трек, треки плейлиста или все треки каталога
+fn add_to_playlist(app_state: &AppState, path: &Path, theme: &Theme) {
+    let tracks = walk_path(path);
+    if tracks.is_empty() {
+        app_state.show_status(format!("No tracks in \"{}\"", file_name(&path.to_string_lossy())));
+        return;
+    }
+
+    app_state.add_tracks(tracks, theme.glyphs.separator);
+}
+
+fn on_click(app_state: &AppState, view: &Mutex<BrowserView>, theme: &Theme, mouse_position: Position) {
+    {
+        let mut view = view.lock().unwrap();
+        if !(BORDER_WIDTH..BORDER_WIDTH + view.list_height).contains(&mouse_position.y) {
+            return;
+        }
+
+        let row = view.list_state.offset() + (mouse_position.y - BORDER_WIDTH) as usize;
+        if row >= view.entries.len() {
+            return;
+        }
+        if view.list_state.selected() != Some(row) {
+            view.list_state.select(Some(row));
+            return;
+        }
+    }
+
+    // Повторный щелчок по выделенной записи
+    activate(app_state, view, theme);
+}
+
+fn draw_file_browser(
+    widget_state: InteractionState,
+    app_state: &AppState,
+    metadata_cache: &MetadataCache,
+    view: &Mutex<BrowserView>,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let mut view = view.lock().unwrap();
+
+    let title = match &view.dir {
+        Some(dir) => format!(" Files: {} ", file_name(&dir.to_string_lossy())),
+        None => " Library ".to_string(),
+    };
+    let block = theme.panel(&widget_state).title(title);
+
+    let inner = block.inner(area);
+    block.render(area, buf);
+    let [list_area, preview_area] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(PREVIEW_HEIGHT)]).areas(inner);
+
+    let roots = view.dir.is_none();
+    let items: Vec<ListItem> =
+        view.entries.iter().map(|entry| ListItem::new(entry_line(entry, roots, theme))).collect();
+    let list = List::new(items).highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    view.list_height = list_area.height;
+    StatefulWidget::render(list, list_area, buf, &mut view.list_state);
+
+    let preview = match view.selected() {
+        Some((EntryKind::Track, path)) => {
+            if view.previewed.as_ref() != Some(&path) {
+                let app_state = app_state.clone();
+                metadata_cache
+                    .prefetch(vec![path.to_string_lossy().to_string()], move || app_state.request_redraw());
+                view.previewed = Some(path.clone());
+            }
+            track_preview(metadata_cache, &path, theme)
+        }
+        Some((EntryKind::Directory, _)) => {
+            vec![
+                Line::from("Directory").fg(theme.palette.muted),
+                Line::from("Enter to open, a to add all tracks").fg(theme.palette.muted),
+            ]
+        }
+        Some((EntryKind::Playlist, _)) => {
+            vec![
+                Line::from("Playlist").fg(theme.palette.muted),
+                Line::from("Enter to add its tracks").fg(theme.palette.muted),
+            ]
+        }
+        _ => vec![],
+    };
+    let separator = Block::new().borders(Borders::TOP).border_set(theme.borders.normal);
+    Paragraph::new(preview).block(separator).render(preview_area, buf);
+}
+
+/// Строка записи; каталоги медиатеки (`full_path`) показываются полным путем
+fn entry_line(entry: &Entry, full_path: bool, theme: &Theme) -> Line<'static> {
+    let name = match entry.kind {
+        EntryKind::Parent => PARENT_LABEL.to_string(),
+        _ if full_path => entry.path.to_string_lossy().to_string(),
+        _ => file_name(&entry.path.to_string_lossy()),
+    };
+
+    match entry.kind {
+        EntryKind::Parent => Line::from(name).fg(theme.palette.muted),
+        EntryKind::Directory => Line::from(format!("{}/", name)).fg(theme.palette.primary).bold(),
+        EntryKind::Track => Line::from(name),
+        EntryKind::Playlist => Line::from(name).fg(theme.palette.accent),
+    }
+}
+
+/// Теги трека для предпросмотра; пока они не прочитаны, показывается только имя файла
+fn track_preview(metadata_cache: &MetadataCache, path: &Path, theme: &Theme) -> Vec<Line<'static>> {
+    let path = path.to_string_lossy();
+    let Some(metadata) = metadata_cache.get(&path) else {
+        return vec![
+            Line::from(file_name(&path)),
+            Line::from(format!("Reading tags{}", theme.glyphs.ellipsis)).fg(theme.palette.muted),
+        ];
+    };
+
+    let mut lines = vec![Line::from(metadata.title.unwrap_or_else(|| file_name(&path))).bold()];
+    if let Some(artist) = metadata.artist {
+        lines.push(Line::from(artist));
+    }
+    if let Some(album) = metadata.album {
+        let year = metadata.year.map(|year| format!(" ({})", year)).unwrap_or_default();
+        lines.push(Line::from(format!("{}{}", album, year)).fg(theme.palette.muted));
+    }
+
+    let mut details = vec![];
+    if let Some(duration) = metadata.duration {
+        details.push(format_duration(duration));
+    }
+    if let Some(bitrate) = metadata.bitrate {
+        details.push(format!("{} kbps", bitrate));
+    }
+    if !details.is_empty() {
+        lines.push(Line::from(Span::from(details.join(theme.glyphs.separator)).fg(theme.palette.muted)));
+    }
+    lines
+}
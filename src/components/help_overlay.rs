@@ -0,0 +1,237 @@
+use std::sync::{Arc, Mutex};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Clear, Paragraph, Widget},
+};
+
+use crate::{
+    app::{AppState, StateChange},
+    interaction::{EventHandler, Gesture, HelpSection, InteractionState, InteractiveWidget},
+    theme::Theme,
+};
+
+/// Строк, на которые прокручивает колесо мыши
+const SCROLL_STEP: usize = 3;
+
+/// Состояние справки
+///
+/// # Fields
+///
+/// * `lines` - Строки справки, собранные при открытии
+/// * `scroll` - Первая видимая строка
+/// * `page` - Сколько строк помещалось в окно на последней отрисовке
+#[derive(Default)]
+struct HelpView {
+    lines: Vec<Line<'static>>,
+    scroll: usize,
+    page: usize,
+}
+
+impl HelpView {
+    fn scroll_by(&mut self, offset: isize) {
+        let max_scroll = self.lines.len().saturating_sub(self.page);
+        self.scroll = self.scroll.saturating_add_signed(offset).min(max_scroll);
+    }
+}
+
+/// Создает окно справки по клавишам и жестам мыши
+///
+/// Справка собирается из описанных привязок всех виджетов в момент открытия, поэтому всегда
+/// совпадает с действующими клавишами. Открывается по `?` откуда угодно, закрывается по Esc.
+pub fn help_overlay(app_state: &AppState, event_handler: &EventHandler, theme: &Theme) -> InteractiveWidget {
+    let view = Arc::new(Mutex::new(HelpView::default()));
+
+    app_state.subscribe({
+        let view = view.clone();
+        let event_handler = event_handler.clone();
+        let theme = *theme;
+        move |app_state, changes| {
+            if changes.contains(&StateChange::Help) {
+                let lines = match app_state.read(|state| state.help_open()) {
+                    true => help_lines(&event_handler.help(), &theme),
+                    false => vec![],
+                };
+                *view.lock().unwrap() = HelpView { lines, ..HelpView::default() };
+            }
+        }
+    });
+
+    InteractiveWidget::default()
+        .focusable()
+        .help_section("Help")
+        .on_global_key_down(KeyCode::Char('?'), {
+            let app_state = app_state.clone();
+            move |_, _| app_state.update(|state| state.set_help_open(true))
+        })
+        .describe("Show this help")
+        .on_key_input({
+            let app_state = app_state.clone();
+            let view = view.clone();
+            move |_, key_event| on_input(&app_state, &view, key_event)
+        })
+        .help_key(KeyCode::Esc, KeyModifiers::NONE, "Close help")
+        .help_key(KeyCode::Up, KeyModifiers::NONE, "Scroll help")
+        .help_key(KeyCode::Down, KeyModifiers::NONE, "Scroll help")
+        .help_key(KeyCode::PageUp, KeyModifiers::NONE, "Scroll help")
+        .help_key(KeyCode::PageDown, KeyModifiers::NONE, "Scroll help")
+        .on_mouse_scroll_up({
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().scroll_by(-(SCROLL_STEP as isize))
+        })
+        .describe("Scroll help")
+        .on_mouse_scroll_down({
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().scroll_by(SCROLL_STEP as isize)
+        })
+        .describe("Scroll help")
+        .draw({
+            let theme = *theme;
+            move |widget_state, area, buf| draw_help(widget_state, &view, &theme, area, buf)
+        })
+}
+
+/// Справка забирает себе все клавиши, пока открыта
+fn on_input(app_state: &AppState, view: &Mutex<HelpView>, key_event: KeyEvent) -> bool {
+    if !app_state.help_open() {
+        return false;
+    }
+
+    let mut view = view.lock().unwrap();
+    let page = view.page.max(1) as isize;
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('?') | KeyCode::Char('q') => {
+            // Подписчик справки блокирует её состояние
+            drop(view);
+            app_state.update(|state| state.set_help_open(false));
+        }
+        KeyCode::Up | KeyCode::Char('k') => view.scroll_by(-1),
+        KeyCode::Down | KeyCode::Char('j') => view.scroll_by(1),
+        KeyCode::PageUp => view.scroll_by(-page),
+        KeyCode::PageDown => view.scroll_by(page),
+        KeyCode::Home => view.scroll = 0,
+        KeyCode::End => view.scroll_by(isize::MAX),
+        _ => {}
+    }
+    true
+}
+
+/// Строки справки: заголовок раздела, затем по строке на действие со всеми его клавишами
+/// и жестами мыши
+fn help_lines(sections: &[HelpSection], theme: &Theme) -> Vec<Line<'static>> {
+    let action_width = sections
+        .iter()
+        .flat_map(|section| &section.entries)
+        .map(|entry| entry.action.chars().count())
+        .max()
+        .unwrap_or_default();
+
+    let mut lines = vec![];
+    for section in sections {
+        if !lines.is_empty() {
+            lines.push(Line::default());
+        }
+        lines.push(Line::from(section.title.bold().fg(theme.palette.accent)));
+
+        // Действия в порядке первого упоминания, с жестами из всех привязок
+        let mut actions: Vec<(&str, Vec<Gesture>)> = vec![];
+        for entry in &section.entries {
+            match actions.iter_mut().find(|(action, _)| *action == entry.action) {
+                Some((_, gestures)) => gestures.push(entry.gesture),
+                None => actions.push((entry.action, vec![entry.gesture])),
+            }
+        }
+
+        for (action, gestures) in actions {
+            let (mouse, keys): (Vec<Gesture>, Vec<Gesture>) =
+                gestures.into_iter().partition(Gesture::is_mouse);
+            let mouse = mouse_labels(&mouse);
+
+            let mut spans = vec![Span::from(format!("  {:width$}  ", action, width = action_width))];
+            spans.push(key_labels(&keys).join("/").fg(theme.palette.primary));
+            if !mouse.is_empty() {
+                let separator = if keys.is_empty() { "" } else { "  " };
+                spans.push(format!("{}{}", separator, mouse.join(", ")).fg(theme.palette.muted));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+    lines
+}
+
+/// Подписи жестов мыши без повторов; прокрутка в обе стороны называется просто колесом
+fn mouse_labels(gestures: &[Gesture]) -> Vec<String> {
+    let wheel = gestures.contains(&Gesture::ScrollUp) && gestures.contains(&Gesture::ScrollDown);
+    let mut labels: Vec<String> = vec![];
+    for gesture in gestures {
+        let label = match gesture {
+            Gesture::ScrollUp | Gesture::ScrollDown if wheel => "Wheel".to_string(),
+            gesture => gesture.label(),
+        };
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    labels
+}
+
+/// Подписи клавиш; подряд идущие цифры с одинаковыми модификаторами сворачиваются в диапазон
+fn key_labels(keys: &[Gesture]) -> Vec<String> {
+    let digit = |gesture: &Gesture| match gesture {
+        Gesture::Key(KeyCode::Char(c), modifiers) | Gesture::GlobalKey(KeyCode::Char(c), modifiers) => {
+            c.to_digit(10).map(|digit| (digit, *modifiers))
+        }
+        _ => None,
+    };
+
+    let mut labels = vec![];
+    let mut index = 0;
+    while index < keys.len() {
+        let mut end = index + 1;
+        if let Some((first, modifiers)) = digit(&keys[index]) {
+            while end < keys.len() && digit(&keys[end]) == Some((first + (end - index) as u32, modifiers)) {
+                end += 1;
+            }
+        }
+
+        if end - index > 2 {
+            let first = keys[index].label();
+            let last = keys[end - 1].label();
+            labels.push(format!("{}-{}", first.trim_end_matches('>'), last.trim_start_matches('<')));
+        } else {
+            labels.push(keys[index].label());
+            end = index + 1;
+        }
+        index = end;
+    }
+    labels
+}
+
+fn draw_help(
+    widget_state: InteractionState,
+    view: &Mutex<HelpView>,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let mut view = view.lock().unwrap();
+    let block = theme.panel(&widget_state).title(" Help ").title_bottom(Line::from(vec![
+        " Close ".into(),
+        Span::styled("<Esc> ", theme.key()),
+        " Scroll ".into(),
+        Span::styled(format!("<{}> ", theme.glyphs.up_down), theme.key()),
+    ]));
+
+    Clear.render(area, buf);
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    view.page = inner.height as usize;
+    // Окно могло вырасти с последней прокрутки
+    view.scroll_by(0);
+    Paragraph::new(view.lines.clone()).scroll((view.scroll as u16, 0)).render(inner, buf);
+}
@@ -0,0 +1,410 @@
+use std::sync::{Arc, Mutex};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::KeyCode,
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::{
+    app::AppState,
+    components::playback::format_duration,
+    interaction::{InteractionState, InteractiveWidget},
+    library::{Library, LibraryField, LibraryTrack},
+    metadata::file_name,
+    theme::Theme,
+};
+
+/// Высота рамки, на которую смещены строки списков
+const BORDER_WIDTH: u16 = 1;
+/// Высота строки заголовков колонок
+const HEADER_HEIGHT: u16 = 1;
+/// Отступ между колонками
+const COLUMN_SPACING: u16 = 1;
+/// На сколько строк перемещается выделение при прокрутке страницы
+const PAGE_SIZE: usize = 10;
+
+const GROUPS_COLUMN: usize = 0;
+const ALBUMS_COLUMN: usize = 1;
+const TRACKS_COLUMN: usize = 2;
+
+/// Поля первой колонки в порядке переключения
+const GROUP_FIELDS: [LibraryField; 3] = [LibraryField::Artist, LibraryField::Genre, LibraryField::Year];
+const UNKNOWN_VALUE: &str = "Unknown";
+
+/// Состояние браузера медиатеки
+///
+/// # Fields
+///
+/// * `group_field` - Поле, по которому сгруппирована первая колонка: исполнитель, жанр или год
+/// * `column` - Колонка, в которой перемещается выделение
+/// * `lists` - Выделение и прокрутка каждой колонки
+/// * `groups` - Исполнители, жанры или годы
+/// * `albums` - Альбомы выделенной группы
+/// * `tracks` - Треки выделенного альбома
+/// * `lists_key` - Версия индекса, группировка и выделение, для которых прочитаны списки
+/// * `column_bounds` - Горизонтальные границы колонок на последней отрисовке
+/// * `list_height` - Высота списков на последней отрисовке
+#[derive(Default)]
+struct LibraryView {
+    group_field: usize,
+    column: usize,
+    lists: [ListState; 3],
+    groups: Vec<String>,
+    albums: Vec<String>,
+    tracks: Vec<LibraryTrack>,
+    lists_key: Option<(u64, usize, Option<usize>, Option<usize>)>,
+    column_bounds: Vec<(u16, u16)>,
+    list_height: u16,
+}
+
+impl LibraryView {
+    /// Перечитывает списки из индекса, если изменились индекс, группировка или выделение
+    fn refresh(&mut self, library: &Library) {
+        let key = |view: &Self| {
+            let selected = |column: usize| view.lists[column].selected();
+            (library.version(), view.group_field, selected(GROUPS_COLUMN), selected(ALBUMS_COLUMN))
+        };
+        if self.lists_key == Some(key(self)) {
+            return;
+        }
+
+        self.groups = library.values(GROUP_FIELDS[self.group_field], &[]);
+        clamp_selection(&mut self.lists[GROUPS_COLUMN], self.groups.len());
+
+        self.albums = self
+            .filters(ALBUMS_COLUMN)
+            .map_or(vec![], |filters| library.values(LibraryField::Album, &filters));
+        clamp_selection(&mut self.lists[ALBUMS_COLUMN], self.albums.len());
+
+        self.tracks = self.filters(TRACKS_COLUMN).map_or(vec![], |filters| library.tracks(&filters));
+        clamp_selection(&mut self.lists[TRACKS_COLUMN], self.tracks.len());
+
+        self.lists_key = Some(key(self));
+    }
+
+    /// Фильтры треков колонки: выделенная группа и, для треков, выделенный альбом
+    fn filters(&self, column: usize) -> Option<Vec<(LibraryField, String)>> {
+        let group = self.groups.get(self.lists[GROUPS_COLUMN].selected()?)?;
+        let mut filters = vec![(GROUP_FIELDS[self.group_field], group.clone())];
+
+        if column == TRACKS_COLUMN {
+            let album = self.albums.get(self.lists[ALBUMS_COLUMN].selected()?)?;
+            filters.push((LibraryField::Album, album.clone()));
+        }
+        Some(filters)
+    }
+
+    fn column_len(&self, column: usize) -> usize {
+        match column {
+            GROUPS_COLUMN => self.groups.len(),
+            ALBUMS_COLUMN => self.albums.len(),
+            _ => self.tracks.len(),
+        }
+    }
+
+    /// Выделяет строку колонки; колонки правее начинаются заново с первой строки
+    fn select(&mut self, column: usize, row: usize) {
+        let Some(last) = self.column_len(column).checked_sub(1) else { return };
+        let row = row.min(last);
+        if self.lists[column].selected() == Some(row) {
+            return;
+        }
+
+        self.lists[column].select(Some(row));
+        for list in &mut self.lists[column + 1..] {
+            *list = ListState::default().with_selected(Some(0));
+        }
+    }
+
+    fn move_selection(&mut self, column: usize, offset: isize) {
+        let selected = self.lists[column].selected().unwrap_or(0) as isize;
+        self.select(column, (selected + offset).max(0) as usize);
+    }
+
+    fn move_column(&mut self, offset: isize) {
+        self.column =
+            (self.column as isize + offset).clamp(GROUPS_COLUMN as isize, TRACKS_COLUMN as isize) as usize;
+    }
+
+    fn next_group_field(&mut self) {
+        self.group_field = (self.group_field + 1) % GROUP_FIELDS.len();
+        self.lists = Default::default();
+        self.column = GROUPS_COLUMN;
+    }
+
+    /// Пути треков выделенной строки колонки: всей группы, альбома или одного трека
+    fn selected_tracks(&self, library: &Library) -> Vec<String> {
+        match self.column {
+            TRACKS_COLUMN => {
+                let track = self.lists[TRACKS_COLUMN].selected().and_then(|row| self.tracks.get(row));
+                track.map(|track| vec![track.path.clone()]).unwrap_or_default()
+            }
+            column => self.filters(column + 1).map_or(vec![], |filters| {
+                library.tracks(&filters).into_iter().map(|track| track.path).collect()
+            }),
+        }
+    }
+
+    fn column_at(&self, x: u16) -> Option<usize> {
+        self.column_bounds.iter().position(|&(left, width)| (left..left + width).contains(&x))
+    }
+}
+
+/// Создает браузер медиатеки из трех колонок: исполнители (жанры, годы), альбомы и треки
+///
+/// Стрелки влево и вправо переходят между колонками, Enter добавляет в плейлист выделенную
+/// группу, альбом или трек, `v` меняет группировку первой колонки, `r` запускает повторное
+/// сканирование. F3 переключает левую панель между медиатекой и обозревателем файлов.
+pub fn library_browser(app_state: &AppState, library: &Library, theme: &Theme) -> InteractiveWidget {
+    let view = Arc::new(Mutex::new(LibraryView::default()));
+
+    InteractiveWidget::default()
+        .focusable()
+        .help_section("Library")
+        .on_key_down(KeyCode::Up, {
+            let view = view.clone();
+            move |_, _| move_selection(&view, -1)
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::Down, {
+            let view = view.clone();
+            move |_, _| move_selection(&view, 1)
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::PageUp, {
+            let view = view.clone();
+            move |_, _| move_selection(&view, -(PAGE_SIZE as isize))
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::PageDown, {
+            let view = view.clone();
+            move |_, _| move_selection(&view, PAGE_SIZE as isize)
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::Home, {
+            let view = view.clone();
+            move |_, _| {
+                let mut view = view.lock().unwrap();
+                let column = view.column;
+                view.select(column, 0);
+            }
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::End, {
+            let view = view.clone();
+            move |_, _| {
+                let mut view = view.lock().unwrap();
+                let column = view.column;
+                view.select(column, usize::MAX);
+            }
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::Left, {
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().move_column(-1)
+        })
+        .describe("Switch column")
+        .on_key_down(KeyCode::Right, {
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().move_column(1)
+        })
+        .describe("Switch column")
+        .on_key_down(KeyCode::Enter, {
+            let app_state = app_state.clone();
+            let library = library.clone();
+            let view = view.clone();
+            let theme = *theme;
+            move |_, _| add_selected(&app_state, &library, &view, &theme)
+        })
+        .describe("Add selection to playlist")
+        .on_key_down(KeyCode::Char('v'), {
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().next_group_field()
+        })
+        .describe("Change grouping")
+        .on_key_down(KeyCode::Char('r'), {
+            let app_state = app_state.clone();
+            let library = library.clone();
+            move |_, _| library.rescan(&app_state)
+        })
+        .describe("Rescan library")
+        .on_global_key_down(KeyCode::F(3), {
+            let app_state = app_state.clone();
+            move |_, _| app_state.update(|state| state.set_library_visible(!state.library_visible()))
+        })
+        .describe("Toggle files/library")
+        .on_mouse_down({
+            let app_state = app_state.clone();
+            let library = library.clone();
+            let view = view.clone();
+            let theme = *theme;
+            move |_, mouse_position| on_click(&app_state, &library, &view, &theme, mouse_position)
+        })
+        .describe("Select, click again to add")
+        .on_mouse_scroll_up({
+            let view = view.clone();
+            move |_, mouse_position| scroll(&view, mouse_position, -1)
+        })
+        .describe("Move cursor")
+        .on_mouse_scroll_down({
+            let view = view.clone();
+            move |_, mouse_position| scroll(&view, mouse_position, 1)
+        })
+        .describe("Move cursor")
+        .draw({
+            let library = library.clone();
+            let theme = *theme;
+            move |widget_state, area, buf| {
+                draw_library_browser(widget_state, &library, &view, &theme, area, buf)
+            }
+        })
+}
+
+fn move_selection(view: &Mutex<LibraryView>, offset: isize) {
+    let mut view = view.lock().unwrap();
+    let column = view.column;
+    view.move_selection(column, offset);
+}
+
+fn scroll(view: &Mutex<LibraryView>, mouse_position: Position, offset: isize) {
+    let mut view = view.lock().unwrap();
+    if let Some(column) = view.column_at(mouse_position.x) {
+        view.move_selection(column, offset);
+    }
+}
+
+fn add_selected(app_state: &AppState, library: &Library, view: &Mutex<LibraryView>, theme: &Theme) {
+    let tracks = view.lock().unwrap().selected_tracks(library);
+    if !tracks.is_empty() {
+        app_state.add_tracks(tracks, theme.glyphs.separator);
+    }
+}
+
+/// Щелчок выделяет строку колонки, повторный щелчок добавляет ее в плейлист.
+/// Щелчок по заголовку первой колонки меняет группировку.
+fn on_click(
+    app_state: &AppState,
+    library: &Library,
+    view: &Mutex<LibraryView>,
+    theme: &Theme,
+    mouse_position: Position,
+) {
+    {
+        let mut view = view.lock().unwrap();
+        let Some(column) = view.column_at(mouse_position.x) else { return };
+        view.column = column;
+
+        let header_row = BORDER_WIDTH;
+        if mouse_position.y == header_row {
+            if column == GROUPS_COLUMN {
+                view.next_group_field();
+            }
+            return;
+        }
+
+        let first_row = BORDER_WIDTH + HEADER_HEIGHT;
+        if !(first_row..first_row + view.list_height).contains(&mouse_position.y) {
+            return;
+        }
+
+        let row = view.lists[column].offset() + (mouse_position.y - first_row) as usize;
+        if row >= view.column_len(column) {
+            return;
+        }
+        if view.lists[column].selected() != Some(row) {
+            view.select(column, row);
+            return;
+        }
+    }
+
+    // Повторный щелчок по выделенной строке
+    add_selected(app_state, library, view, theme);
+}
+
+fn draw_library_browser(
+    widget_state: InteractionState,
+    library: &Library,
+    view: &Mutex<LibraryView>,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let mut view = view.lock().unwrap();
+    view.refresh(library);
+
+    let title = match library.is_scanning() {
+        true => format!(" Library (scanning{}) ", theme.glyphs.ellipsis),
+        false => " Library ".to_string(),
+    };
+    let focused = widget_state == InteractionState::Focused;
+    let block = theme.panel(&widget_state).title(title);
+
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    let columns: [Rect; 3] =
+        Layout::horizontal([Constraint::Fill(1); 3]).spacing(COLUMN_SPACING).areas(inner);
+    view.column_bounds = columns.iter().map(|column| (column.x - area.x, column.width)).collect();
+    view.list_height = inner.height.saturating_sub(HEADER_HEIGHT);
+
+    let group_title = match GROUP_FIELDS[view.group_field] {
+        LibraryField::Genre => "Genres",
+        LibraryField::Year => "Years",
+        _ => "Artists",
+    };
+    let titles = [group_title, "Albums", "Tracks"];
+
+    let items: [Vec<ListItem>; 3] = [
+        view.groups.iter().map(|group| ListItem::new(value_line(group, theme))).collect(),
+        view.albums.iter().map(|album| ListItem::new(value_line(album, theme))).collect(),
+        view.tracks.iter().map(|track| ListItem::new(track_line(track, theme))).collect(),
+    ];
+
+    for (column, (items, column_area)) in items.into_iter().zip(columns).enumerate() {
+        let [header_area, list_area] =
+            Layout::vertical([Constraint::Length(HEADER_HEIGHT), Constraint::Fill(1)]).areas(column_area);
+
+        let header = Span::from(titles[column]).bold();
+        let header = if focused && column == view.column { header.reversed() } else { header };
+        Line::from(header).render(header_area, buf);
+
+        // Выделение ярче в колонке, где оно перемещается
+        let highlight_style = match column == view.column {
+            true => Style::new().add_modifier(Modifier::REVERSED),
+            false => Style::new().add_modifier(Modifier::BOLD),
+        };
+        let list = List::new(items).highlight_style(highlight_style);
+        StatefulWidget::render(list, list_area, buf, &mut view.lists[column]);
+    }
+}
+
+/// Значение группы или альбома; пустое значение означает, что тег не заполнен
+fn value_line(value: &str, theme: &Theme) -> Line<'static> {
+    match value.is_empty() {
+        true => Line::from(UNKNOWN_VALUE).fg(theme.palette.muted),
+        false => Line::from(value.to_string()),
+    }
+}
+
+fn track_line(track: &LibraryTrack, theme: &Theme) -> Line<'static> {
+    let number = track.track_number.map(|number| format!("{:02} ", number)).unwrap_or_default();
+    let title = track.title.clone().unwrap_or_else(|| file_name(&track.path));
+    let duration =
+        track.duration.map(|duration| format!(" {}", format_duration(duration))).unwrap_or_default();
+
+    Line::from(vec![
+        Span::from(number).fg(theme.palette.muted),
+        Span::from(title),
+        Span::from(duration).fg(theme.palette.muted),
+    ])
+}
+
+/// Оставляет выделение в пределах списка: пустой список выделения не имеет
+fn clamp_selection(list: &mut ListState, len: usize) {
+    let selected = len.checked_sub(1).map(|last| list.selected().unwrap_or(0).min(last));
+    list.select(selected);
+}
@@ -0,0 +1,284 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::KeyCode,
+    layout::{Constraint, Flex, Layout, Position, Rect},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+
+use crate::{
+    app::{AppState, StateChange},
+    components::playback::seek_to,
+    interaction::{InteractionState, InteractiveWidget},
+    lyrics::Lyrics,
+    player::Player,
+    theme::Theme,
+};
+
+/// Высота рамки, на которую смещены строки текста
+const BORDER_WIDTH: u16 = 1;
+/// Шаг сдвига текста относительно звука в миллисекундах
+const OFFSET_STEP: i64 = 500;
+
+/// Текст песни текущего трека
+///
+/// # Fields
+///
+/// * `path` - Трек, для которого загружается текст
+/// * `loaded` - Загрузка закончилась; если `lyrics` пуст, текста у трека нет
+/// * `lyrics` - Текст песни
+/// * `offset` - Сдвиг текста в миллисекундах; положительный показывает строки раньше
+/// * `top` - Первая видимая строка
+/// * `manual_scroll` - Текст прокручен вручную и не следует за текущей строкой,
+///   пока она не сменится
+/// * `current` - Текущая строка на момент последней отрисовки
+#[derive(Default)]
+struct LyricsView {
+    path: Option<String>,
+    loaded: bool,
+    lyrics: Option<Lyrics>,
+    offset: i64,
+    top: usize,
+    manual_scroll: bool,
+    current: Option<usize>,
+}
+
+impl LyricsView {
+    fn scroll(&mut self, offset: isize) {
+        let last = self.lyrics.as_ref().map_or(0, |lyrics| lyrics.len().saturating_sub(1));
+        self.top = (self.top as isize + offset).clamp(0, last as isize) as usize;
+        self.manual_scroll = true;
+    }
+
+    /// Позиция в тексте песни с учетом сдвига
+    fn lyrics_position(&self, played_duration: Duration) -> Duration {
+        shift(played_duration, self.offset)
+    }
+}
+
+/// Создает панель текста песни
+///
+/// Синхронизированный текст прокручивается вслед за воспроизведением, а текущая строка
+/// закрашивается по мере звучания. Щелчок по строке перематывает трек к ней, `+`/`-`
+/// сдвигают текст, если он расходится со звуком.
+pub fn lyrics_panel(app_state: &AppState, player: &Player, theme: &Theme) -> InteractiveWidget {
+    let view = Arc::new(Mutex::new(LyricsView::default()));
+
+    app_state.subscribe({
+        let view = view.clone();
+        move |app_state, changes| {
+            if changes.contains(&StateChange::CurrentTrack) {
+                load_current_lyrics(app_state, &view);
+            }
+        }
+    });
+
+    InteractiveWidget::default()
+        .focusable()
+        .help_section("Lyrics")
+        .on_key_down(KeyCode::Char('+'), {
+            let app_state = app_state.clone();
+            let view = view.clone();
+            move |_, _| shift_lyrics(&app_state, &view, OFFSET_STEP)
+        })
+        .describe("Show lyrics earlier")
+        .on_key_down(KeyCode::Char('-'), {
+            let app_state = app_state.clone();
+            let view = view.clone();
+            move |_, _| shift_lyrics(&app_state, &view, -OFFSET_STEP)
+        })
+        .describe("Show lyrics later")
+        .on_key_down(KeyCode::Char('0'), {
+            let app_state = app_state.clone();
+            let view = view.clone();
+            move |_, _| {
+                let offset = view.lock().unwrap().offset;
+                shift_lyrics(&app_state, &view, -offset);
+            }
+        })
+        .describe("Reset lyrics offset")
+        .on_key_down(KeyCode::Up, {
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().scroll(-1)
+        })
+        .describe("Scroll lyrics")
+        .on_key_down(KeyCode::Down, {
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().scroll(1)
+        })
+        .describe("Scroll lyrics")
+        .on_mouse_scroll_up({
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().scroll(-1)
+        })
+        .describe("Scroll lyrics")
+        .on_mouse_scroll_down({
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().scroll(1)
+        })
+        .describe("Scroll lyrics")
+        .on_mouse_down({
+            let app_state = app_state.clone();
+            let player = player.clone();
+            let view = view.clone();
+            move |_, mouse_position| on_click(&app_state, &player, &view, mouse_position)
+        })
+        .describe("Seek to line")
+        .on_global_key_down(KeyCode::F(4), {
+            let app_state = app_state.clone();
+            move |_, _| app_state.update(|state| state.set_lyrics_visible(!state.lyrics_visible()))
+        })
+        .describe("Toggle queue/lyrics")
+        .draw({
+            let app_state = app_state.clone();
+            let theme = *theme;
+            move |widget_state, area, buf| draw_lyrics(widget_state, &app_state, &view, &theme, area, buf)
+        })
+}
+
+fn load_current_lyrics(app_state: &AppState, view: &Arc<Mutex<LyricsView>>) {
+    let path = app_state.read(|state| state.current_track_path().cloned());
+    if view.lock().unwrap().path == path {
+        return;
+    }
+    *view.lock().unwrap() = LyricsView { path: path.clone(), ..LyricsView::default() };
+
+    let Some(path) = path else { return };
+    let app_state = app_state.clone();
+    let view = view.clone();
+    thread::spawn(move || {
+        let lyrics = Lyrics::load(&path).filter(|lyrics| !lyrics.is_empty());
+        let mut view = view.lock().unwrap();
+        // Пока текст читался, мог начаться другой трек
+        if view.path.as_ref() == Some(&path) {
+            view.loaded = true;
+            view.lyrics = lyrics;
+            drop(view);
+            app_state.request_redraw();
+        }
+    });
+}
+
+fn shift_lyrics(app_state: &AppState, view: &Mutex<LyricsView>, step: i64) {
+    let offset = {
+        let mut view = view.lock().unwrap();
+        view.offset += step;
+        view.manual_scroll = false;
+        view.offset
+    };
+    app_state.show_status(format!("Lyrics offset {:+.1}s", offset as f64 / 1000.0));
+}
+
+/// Перематывает трек к началу строки под курсором
+fn on_click(app_state: &AppState, player: &Player, view: &Mutex<LyricsView>, mouse_position: Position) {
+    if mouse_position.y < BORDER_WIDTH {
+        return;
+    }
+    let Some(info) = app_state.current_track_info() else { return };
+
+    let position = {
+        let mut view = view.lock().unwrap();
+        let index = view.top + (mouse_position.y - BORDER_WIDTH) as usize;
+        let Some(time) = view.lyrics.as_ref().and_then(|lyrics| lyrics.time(index)) else { return };
+        view.manual_scroll = false;
+        shift(time, -view.offset).min(info.duration)
+    };
+
+    seek_to(position, info, app_state, player);
+}
+
+fn draw_lyrics(
+    widget_state: InteractionState,
+    app_state: &AppState,
+    view: &Mutex<LyricsView>,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let mut view = view.lock().unwrap();
+
+    let mut block = theme.panel(&widget_state).title(" Lyrics ");
+    if view.offset != 0 {
+        block =
+            block.title_bottom(Line::from(format!(" {:+.1}s ", view.offset as f64 / 1000.0)).right_aligned());
+    }
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    let info = app_state.current_track_info();
+    let (Some(lyrics), Some(info)) = (view.lyrics.as_ref(), info) else {
+        let message = match (&view.path, view.loaded) {
+            (None, _) => "Nothing playing",
+            (Some(_), false) => "",
+            (Some(_), true) => "No lyrics",
+        };
+        let [message_area] = Layout::vertical([Constraint::Length(1)]).flex(Flex::Center).areas(inner);
+        Paragraph::new(Line::from(message.fg(theme.palette.muted)).centered()).render(message_area, buf);
+        return;
+    };
+
+    let position = view.lyrics_position(info.played_duration);
+    let current = lyrics.current_line(position);
+    let len = lyrics.len();
+    let top = match view.manual_scroll && view.current == current {
+        true => view.top,
+        // Текущая строка держится в верхней трети панели
+        false => current.map_or(0, |current| current.saturating_sub(inner.height as usize / 3)),
+    };
+
+    let lines: Vec<Line> = (top..len.min(top + inner.height as usize))
+        .map(|index| match current {
+            Some(current) if index == current => {
+                let end = lyrics.time(index + 1).unwrap_or(view.lyrics_position(info.duration));
+                karaoke_line(lyrics.text(index), lyrics.time(index).unwrap_or_default(), end, position, theme)
+            }
+            Some(current) if index < current => {
+                Line::from(lyrics.text(index).to_string().fg(theme.palette.muted))
+            }
+            _ => Line::from(lyrics.text(index).to_string()),
+        })
+        .map(Line::centered)
+        .collect();
+    Paragraph::new(lines).render(inner, buf);
+
+    view.manual_scroll = view.manual_scroll && view.current == current;
+    view.top = top;
+    view.current = current;
+}
+
+/// Текущая строка, пропетая часть которой закрашена пропорционально прошедшему времени
+fn karaoke_line(
+    text: &str,
+    start: Duration,
+    end: Duration,
+    position: Duration,
+    theme: &Theme,
+) -> Line<'static> {
+    let length = end.saturating_sub(start).as_secs_f64();
+    let progress = match length > 0.0 {
+        true => (position.saturating_sub(start).as_secs_f64() / length).clamp(0.0, 1.0),
+        false => 1.0,
+    };
+
+    let sung = (text.chars().count() as f64 * progress).round() as usize;
+    let split = text.char_indices().nth(sung).map_or(text.len(), |(index, _)| index);
+    Line::from(vec![
+        Span::from(text[..split].to_string()).fg(theme.palette.accent).bold(),
+        Span::from(text[split..].to_string()).bold(),
+    ])
+}
+
+/// Сдвигает время на `offset` миллисекунд, не уходя в отрицательные значения
+fn shift(time: Duration, offset: i64) -> Duration {
+    match offset >= 0 {
+        true => time + Duration::from_millis(offset as u64),
+        false => time.saturating_sub(Duration::from_millis(offset.unsigned_abs())),
+    }
+}
@@ -0,0 +1,148 @@
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::KeyCode,
+    layout::{Constraint, Flex, Layout, Position, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::Widget,
+};
+
+use crate::{
+    app::{AppState, PlayerState},
+    components::playback::{format_duration, seek_by, seek_to},
+    interaction::{InteractionState, InteractiveWidget},
+    player::Player,
+    theme::Theme,
+};
+
+/// Шаг перемотки колесом мыши и стрелками в секундах
+const SEEK_STEP: f64 = 5.0;
+/// Строка полосы прогресса, если плееру досталось больше одной строки
+const PROGRESS_ROW: u16 = 1;
+
+/// Создает мини-плеер: одна-две строки без рамок для маленьких окон
+///
+/// В первой строке состояние, трек и время, во второй - полоса прогресса. В одну строку
+/// прогресс показывается подсветкой самой строки. Щелчок по строке трека ставит на паузу,
+/// по полосе прогресса - перематывает; колесо мыши и стрелки тоже перематывают.
+pub fn mini_player(app_state: &AppState, player: &Player, theme: &Theme) -> InteractiveWidget {
+    InteractiveWidget::default()
+        .focusable()
+        .help_section("Mini player")
+        .on_mouse_down({
+            let app_state = app_state.clone();
+            let player = player.clone();
+            move |widget, mouse_position| on_click(widget, mouse_position, &app_state, &player)
+        })
+        .describe("Play/pause, seek on progress line")
+        .on_mouse_scroll_up({
+            let app_state = app_state.clone();
+            let player = player.clone();
+            move |_, _| seek_by(SEEK_STEP, &app_state, &player)
+        })
+        .describe("Seek forward")
+        .on_mouse_scroll_down({
+            let app_state = app_state.clone();
+            let player = player.clone();
+            move |_, _| seek_by(-SEEK_STEP, &app_state, &player)
+        })
+        .describe("Seek back")
+        .on_key_down(KeyCode::Right, {
+            let app_state = app_state.clone();
+            let player = player.clone();
+            move |_, _| seek_by(SEEK_STEP, &app_state, &player)
+        })
+        .describe("Seek forward")
+        .on_key_down(KeyCode::Left, {
+            let app_state = app_state.clone();
+            let player = player.clone();
+            move |_, _| seek_by(-SEEK_STEP, &app_state, &player)
+        })
+        .describe("Seek back")
+        .draw({
+            let app_state = app_state.clone();
+            let theme = *theme;
+            move |widget_state, area, buf| draw_mini_player(widget_state, &app_state, &theme, area, buf)
+        })
+}
+
+fn on_click(widget: &mut InteractiveWidget, mouse_position: Position, app_state: &AppState, player: &Player) {
+    let area = widget.area();
+    if area.height > 1 && mouse_position.y == PROGRESS_ROW {
+        if let Some(info) = app_state.current_track_info() {
+            let ratio = mouse_position.x as f64 / area.width.saturating_sub(1).max(1) as f64;
+            seek_to(info.duration.mul_f64(ratio.min(1.0)), info, app_state, player);
+        }
+        return;
+    }
+
+    app_state.update(|state| match state.player_state() {
+        PlayerState::Playing => state.set_player_state(PlayerState::Paused),
+        PlayerState::Paused => state.set_player_state(PlayerState::Playing),
+        PlayerState::Stopped => state.play_track(state.current_track().unwrap_or(0)),
+    });
+}
+
+fn draw_mini_player(
+    widget_state: InteractionState,
+    app_state: &AppState,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let glyphs = &theme.glyphs;
+    let status = match app_state.player_state() {
+        PlayerState::Playing => glyphs.play.hovered,
+        PlayerState::Paused => glyphs.pause.normal,
+        PlayerState::Stopped => glyphs.stop.normal,
+    };
+
+    let [track_area, progress_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Length(1)]).flex(Flex::Start).areas(area);
+
+    let Some(info) = app_state.current_track_info() else {
+        let line = Line::from(vec![format!("{} ", status).into(), "Nothing playing".fg(theme.palette.muted)]);
+        line.render(track_area, buf);
+        return;
+    };
+
+    let time = format!(" {} / {}", format_duration(info.played_duration), format_duration(info.duration));
+    let title = match info.artist.is_empty() {
+        true => info.title.clone(),
+        false => format!("{} - {}", info.title, info.artist),
+    };
+    let [title_area, time_area] =
+        Layout::horizontal([Constraint::Fill(1), Constraint::Length(time.chars().count() as u16)])
+            .areas(track_area);
+
+    let title_style = match widget_state {
+        InteractionState::Hovered => Style::new().bold(),
+        _ => Style::new(),
+    };
+    Line::from(vec![format!("{} ", status).into(), Span::styled(title, title_style)]).render(title_area, buf);
+    Line::from(time.fg(theme.palette.muted)).render(time_area, buf);
+
+    let progress = match info.duration.as_secs_f64() {
+        total if total > 0.0 => (info.played_duration.as_secs_f64() / total).clamp(0.0, 1.0),
+        _ => 0.0,
+    };
+
+    // В одну строку сыгранная часть трека подсвечивается прямо в строке трека
+    if progress_area.is_empty() {
+        let played = (track_area.width as f64 * progress).round() as u16;
+        let played_area = Rect { width: played, ..track_area };
+        buf.set_style(played_area, Style::new().fg(theme.palette.accent_text).bg(theme.palette.accent));
+        return;
+    }
+
+    let played = (progress_area.width as f64 * progress).round() as usize;
+    let line = Line::from(vec![
+        glyphs.progress_line.to_string().repeat(played).fg(theme.palette.accent),
+        glyphs
+            .progress_line
+            .to_string()
+            .repeat(progress_area.width as usize - played)
+            .fg(theme.palette.muted),
+    ]);
+    line.render(progress_area, buf);
+}
@@ -4,16 +4,59 @@ pub use buttons::next_track_button;
 pub use buttons::play_button;
 pub use buttons::stop_button;
 
+mod dialogs;
+pub use dialogs::confirm_dialog;
+pub use dialogs::prompt_dialog;
+pub use dialogs::select_dialog;
+
+mod file_browser;
+pub use file_browser::file_browser;
+
+mod help_overlay;
+pub use help_overlay::help_overlay;
+
+mod library_browser;
+pub use library_browser::library_browser;
+
+mod lyrics_panel;
+pub use lyrics_panel::lyrics_panel;
+
+mod mini_player;
+pub use mini_player::mini_player;
+
+mod now_playing;
+pub use now_playing::now_playing;
+
+mod organiser;
+pub use organiser::organiser;
+
+mod playback;
+
 mod playlist;
 pub use playlist::playlist_widget;
 
+mod playlist_tabs;
+pub use playlist_tabs::playlist_tabs;
+
 mod progress_bar;
 pub use progress_bar::progress_bar;
 
+mod queue;
+pub use queue::queue_widget;
+
+mod tag_editor;
+pub use tag_editor::tag_editor;
+
+mod toasts;
+pub use toasts::toasts;
+pub use toasts::TOAST_HEIGHT;
+
 mod toggles;
 pub use toggles::repeat_toggle;
 pub use toggles::shuffle_toggle;
 
+mod visualizer;
+pub use visualizer::visualizer;
+
 mod volume_control;
 pub use volume_control::volume_control;
-
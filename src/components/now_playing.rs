@@ -0,0 +1,219 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use image::DynamicImage;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Paragraph, Widget},
+};
+
+use crate::{
+    album_art::{cell_size, encode, fit_area, load_cover, render_half_blocks, ArtOutput, EncodedArt},
+    app::{AppState, CurrentTrackInfo, StateChange},
+    interaction::{InteractionState, InteractiveWidget},
+    metadata::{file_name, MetadataCache, TrackMetadata},
+    theme::Theme,
+};
+
+/// Обложка текущего трека
+///
+/// # Fields
+///
+/// * `path` - Трек, для которого загружается обложка
+/// * `loaded` - Загрузка закончилась; если `image` пуст, обложки у трека нет
+/// * `image` - Обложка в исходном размере
+/// * `encoded` - Обложка, подготовленная для последнего размера панели (в ячейках)
+#[derive(Default)]
+struct CoverView {
+    path: Option<String>,
+    loaded: bool,
+    image: Option<Arc<DynamicImage>>,
+    encoded: Option<((u16, u16), Option<EncodedArt>)>,
+}
+
+/// Создает панель текущего трека: обложка альбома, теги и параметры файла и следующий трек
+///
+/// Обложка загружается в фоне при смене трека и масштабируется под размер панели.
+pub fn now_playing(
+    app_state: &AppState,
+    metadata_cache: &MetadataCache,
+    art_output: &ArtOutput,
+    theme: &Theme,
+) -> InteractiveWidget {
+    let view = Arc::new(Mutex::new(CoverView::default()));
+
+    app_state.subscribe({
+        let view = view.clone();
+        move |app_state, changes| {
+            if changes.contains(&StateChange::CurrentTrack) {
+                load_current_cover(app_state, &view);
+            }
+        }
+    });
+
+    InteractiveWidget::default().draw({
+        let app_state = app_state.clone();
+        let metadata_cache = metadata_cache.clone();
+        let art_output = art_output.clone();
+        let theme = *theme;
+        move |_, area, buf| {
+            draw_now_playing(&app_state, &metadata_cache, &view, &art_output, &theme, area, buf)
+        }
+    })
+}
+
+fn load_current_cover(app_state: &AppState, view: &Arc<Mutex<CoverView>>) {
+    let path = app_state.read(|state| state.current_track_path().cloned());
+    if view.lock().unwrap().path == path {
+        return;
+    }
+    *view.lock().unwrap() = CoverView { path: path.clone(), ..CoverView::default() };
+
+    let Some(path) = path else { return };
+    let app_state = app_state.clone();
+    let view = view.clone();
+    thread::spawn(move || {
+        let image = load_cover(&path);
+        let mut view = view.lock().unwrap();
+        // Пока обложка читалась, мог начаться другой трек
+        if view.path.as_ref() == Some(&path) {
+            view.loaded = true;
+            view.image = image.map(Arc::new);
+            drop(view);
+            app_state.request_redraw();
+        }
+    });
+}
+
+fn draw_now_playing(
+    app_state: &AppState,
+    metadata_cache: &MetadataCache,
+    view: &Mutex<CoverView>,
+    art_output: &ArtOutput,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let block = theme.panel(&InteractionState::Default).title(" Now playing ");
+    let inner = block.inner(area);
+    block.render(area, buf);
+    if inner.is_empty() {
+        return;
+    }
+
+    let (info, next) =
+        app_state.read(|state| (state.current_track_info().cloned(), state.next_track_path().cloned()));
+    let lines = match &info {
+        Some(info) => {
+            info_lines(info, next.map(|path| track_name(metadata_cache, &path)), theme, inner.width)
+        }
+        None => vec![],
+    };
+    let [cover_area, info_area] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(lines.len() as u16)]).areas(inner);
+
+    draw_cover(view, art_output, theme, cover_area, buf);
+    Paragraph::new(lines).render(info_area, buf);
+}
+
+fn draw_cover(view: &Mutex<CoverView>, art_output: &ArtOutput, theme: &Theme, area: Rect, buf: &mut Buffer) {
+    if area.is_empty() {
+        return;
+    }
+
+    let mut view = view.lock().unwrap();
+    let Some(image) = view.image.clone() else {
+        let message = match (&view.path, view.loaded) {
+            (None, _) => "Nothing playing",
+            (Some(_), false) => "",
+            (Some(_), true) => "No cover",
+        };
+        let [message_area] = Layout::vertical([Constraint::Length(1)]).flex(Flex::Center).areas(area);
+        Paragraph::new(Line::from(message.fg(theme.palette.muted)).centered()).render(message_area, buf);
+        return;
+    };
+
+    let cell_size = cell_size();
+    let cover_area = fit_area(&image, area, cell_size);
+    let size = (cover_area.width, cover_area.height);
+    if view.encoded.as_ref().map(|(encoded_size, _)| *encoded_size) != Some(size) {
+        view.encoded = Some((size, encode(&image, art_output.protocol(), size, cell_size)));
+    }
+
+    match view.encoded.as_ref().and_then(|(_, encoded)| encoded.as_ref()) {
+        Some(EncodedArt::HalfBlocks(cells)) => render_half_blocks(cells, cover_area, buf),
+        Some(EncodedArt::Escape(escape)) => art_output.place(escape, cover_area, buf),
+        None => {}
+    }
+}
+
+/// Теги и параметры файла текущего трека; пустые поля пропускаются
+fn info_lines(
+    info: &CurrentTrackInfo,
+    next: Option<String>,
+    theme: &Theme,
+    width: u16,
+) -> Vec<Line<'static>> {
+    let joined = |parts: Vec<Option<String>>| {
+        parts.into_iter().flatten().collect::<Vec<_>>().join(theme.glyphs.separator)
+    };
+
+    let album = match info.year {
+        Some(year) => format!("{} ({})", info.album, year),
+        None => info.album.clone(),
+    };
+    let position = joined(vec![
+        info.disc_number.map(|disc| format!("Disc {}", disc)),
+        info.track_number.map(|track| format!("Track {}", track)),
+        info.genre.clone(),
+    ]);
+    let format = joined(vec![
+        info.stream.codec.clone(),
+        info.bitrate.map(|bitrate| format!("{} kbps", bitrate)),
+        info.stream.sample_rate.map(|rate| format!("{} kHz", rate as f64 / 1000.0)),
+        info.stream.bit_depth.map(|depth| format!("{} bit", depth)),
+        info.stream.channels.map(|channels| match channels {
+            1 => "Mono".to_string(),
+            2 => "Stereo".to_string(),
+            channels => format!("{} ch", channels),
+        }),
+    ]);
+
+    vec![
+        Line::from(info.title.clone().bold()),
+        Line::from(info.artist.clone()),
+        Line::from(album),
+        Line::from(position.fg(theme.palette.muted)),
+        Line::from(format.fg(theme.palette.muted)),
+        Line::from(truncate_start(&info.path, width as usize, theme.glyphs.ellipsis).fg(theme.palette.muted)),
+        Line::from(vec!["Next ".fg(theme.palette.muted), next.unwrap_or_else(|| "-".to_string()).into()]),
+    ]
+}
+
+/// Название трека с исполнителем, если теги уже прочитаны, иначе имя файла
+fn track_name(metadata_cache: &MetadataCache, path: &str) -> String {
+    match metadata_cache.get(path) {
+        Some(TrackMetadata { title: Some(title), artist: Some(artist), .. }) => {
+            format!("{} - {}", title, artist)
+        }
+        Some(TrackMetadata { title: Some(title), .. }) => title,
+        _ => file_name(path),
+    }
+}
+
+/// Обрезает текст слева, чтобы в пути осталось видно имя файла
+fn truncate_start(text: &str, width: usize, ellipsis: &str) -> String {
+    let length = text.chars().count();
+    match length > width {
+        true => {
+            let skipped = (length + ellipsis.chars().count()).saturating_sub(width.max(1));
+            format!("{}{}", ellipsis, text.chars().skip(skipped).collect::<String>())
+        }
+        false => text.to_string(),
+    }
+}
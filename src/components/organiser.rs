@@ -0,0 +1,282 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::{
+    app::{AppState, StateChange, ToastLevel},
+    config::LibraryConfig,
+    interaction::{InteractionState, InteractiveWidget},
+    metadata::{file_name, MetadataCache, TrackMetadata},
+    organise::{move_file, plan_renames, Rename},
+    theme::Theme,
+};
+
+/// Высота строк шаблона и сводки над списком перемещений
+const HEADER_HEIGHT: u16 = 2;
+/// На сколько строк прокручивается список при прокрутке страницы
+const PAGE_SIZE: usize = 10;
+
+/// Состояние окна раскладки файлов
+///
+/// # Fields
+///
+/// * `tracks` - Треки и их теги
+/// * `template` - Шаблон пути
+/// * `renames` - Перемещения по текущему шаблону (предварительный просмотр)
+/// * `offset` - Прокрутка списка перемещений
+#[derive(Default)]
+struct OrganiserView {
+    tracks: Vec<(String, TrackMetadata)>,
+    template: String,
+    renames: Vec<Rename>,
+    offset: usize,
+}
+
+impl OrganiserView {
+    fn replan(&mut self, roots: &[PathBuf]) {
+        self.renames = plan_renames(&self.template, &self.tracks, roots);
+        self.offset = self.offset.min(self.renames.len().saturating_sub(1));
+    }
+
+    fn scroll(&mut self, offset: isize) {
+        let last = self.renames.len().saturating_sub(1) as isize;
+        self.offset = (self.offset as isize + offset).clamp(0, last.max(0)) as usize;
+    }
+}
+
+/// Создает модальное окно, раскладывающее выбранные в плейлисте файлы по каталогам
+/// и именам из тегов
+///
+/// Пока вводится шаблон, под ним видно, куда переедет каждый файл; файлы перемещаются
+/// только по Enter, после чего пути в плейлистах обновляются.
+pub fn organiser(
+    app_state: &AppState,
+    metadata_cache: &MetadataCache,
+    config: &LibraryConfig,
+    theme: &Theme,
+) -> InteractiveWidget {
+    let view = Arc::new(Mutex::new(OrganiserView::default()));
+    let roots = config.root_dirs();
+
+    app_state.subscribe({
+        let view = view.clone();
+        let metadata_cache = metadata_cache.clone();
+        let roots = roots.clone();
+        let template = config.organise_template.clone();
+        move |app_state, changes| {
+            if !changes.contains(&StateChange::Organiser) {
+                return;
+            }
+
+            let tracks = app_state.read(|state| state.organiser_tracks().to_vec());
+            let metadata = metadata_cache.load_many(&tracks);
+            let mut view = view.lock().unwrap();
+            *view = OrganiserView {
+                tracks: tracks.into_iter().zip(metadata).collect(),
+                template: template.clone(),
+                ..OrganiserView::default()
+            };
+            view.replan(&roots);
+        }
+    });
+
+    InteractiveWidget::default()
+        .focusable()
+        .help_section("Organiser")
+        .on_key_input({
+            let app_state = app_state.clone();
+            let view = view.clone();
+            move |_, key_event| on_input(&app_state, &view, &roots, key_event)
+        })
+        .help_key(KeyCode::Enter, KeyModifiers::NONE, "Move files")
+        .help_key(KeyCode::Esc, KeyModifiers::NONE, "Cancel")
+        .help_key(KeyCode::Char('u'), KeyModifiers::CONTROL, "Clear template")
+        .help_key(KeyCode::Up, KeyModifiers::NONE, "Scroll preview")
+        .help_key(KeyCode::Down, KeyModifiers::NONE, "Scroll preview")
+        .help_key(KeyCode::PageUp, KeyModifiers::NONE, "Scroll preview")
+        .help_key(KeyCode::PageDown, KeyModifiers::NONE, "Scroll preview")
+        .on_mouse_scroll_up({
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().scroll(-1)
+        })
+        .describe("Scroll preview")
+        .on_mouse_scroll_down({
+            let view = view.clone();
+            move |_, _| view.lock().unwrap().scroll(1)
+        })
+        .describe("Scroll preview")
+        .draw({
+            let theme = *theme;
+            move |widget_state, area, buf| draw_organiser(widget_state, &view, &theme, area, buf)
+        })
+}
+
+/// Окно забирает себе все клавиши, пока открыто
+fn on_input(
+    app_state: &AppState,
+    view: &Mutex<OrganiserView>,
+    roots: &[PathBuf],
+    key_event: KeyEvent,
+) -> bool {
+    if view.lock().unwrap().tracks.is_empty() {
+        return false;
+    }
+
+    // Закрытие окна уведомляет подписчика, которому нужно состояние окна,
+    // поэтому оно не должно быть заблокировано
+    match key_event.code {
+        KeyCode::Esc => close(app_state),
+        KeyCode::Enter => {
+            let renames = view.lock().unwrap().renames.clone();
+            close(app_state);
+            apply_renames(app_state, renames);
+        }
+        _ => edit_template(&mut view.lock().unwrap(), roots, key_event),
+    }
+    true
+}
+
+fn edit_template(view: &mut OrganiserView, roots: &[PathBuf], key_event: KeyEvent) {
+    let control = key_event.modifiers.contains(KeyModifiers::CONTROL);
+    match key_event.code {
+        KeyCode::Up => view.scroll(-1),
+        KeyCode::Down => view.scroll(1),
+        KeyCode::PageUp => view.scroll(-(PAGE_SIZE as isize)),
+        KeyCode::PageDown => view.scroll(PAGE_SIZE as isize),
+        KeyCode::Backspace => {
+            view.template.pop();
+            view.replan(roots);
+        }
+        KeyCode::Char('u') if control => {
+            view.template.clear();
+            view.replan(roots);
+        }
+        KeyCode::Char(c) if !control => {
+            view.template.push(c);
+            view.replan(roots);
+        }
+        _ => {}
+    }
+}
+
+fn close(app_state: &AppState) {
+    app_state.update(|state| state.set_organiser_tracks(vec![]));
+}
+
+/// Перемещает файлы без конфликтов в фоне и обновляет пути в плейлистах
+fn apply_renames(app_state: &AppState, renames: Vec<Rename>) {
+    let renames: Vec<Rename> = renames.into_iter().filter(|rename| rename.conflict.is_none()).collect();
+    if renames.is_empty() {
+        app_state.show_status("Nothing to move");
+        return;
+    }
+
+    let app_state = app_state.clone();
+    thread::spawn(move || {
+        let mut moves = HashMap::new();
+        let mut errors = vec![];
+        for rename in renames {
+            match move_file(Path::new(&rename.from), Path::new(&rename.to)) {
+                Ok(()) => {
+                    moves.insert(rename.from, rename.to);
+                }
+                Err(error) => errors.push(format!("\"{}\": {}", file_name(&rename.from), error)),
+            }
+        }
+
+        app_state.update(|state| state.rename_tracks(&moves));
+        match errors.first() {
+            Some(error) => {
+                let message = format!("Moved {} files, cannot move {}", moves.len(), error);
+                app_state.notify(ToastLevel::Error, message)
+            }
+            None => app_state.notify(ToastLevel::Success, format!("Moved {} files", moves.len())),
+        }
+    });
+}
+
+fn draw_organiser(
+    widget_state: InteractionState,
+    view: &Mutex<OrganiserView>,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let view = view.lock().unwrap();
+    if view.tracks.is_empty() {
+        return;
+    }
+
+    let block = theme
+        .panel(&widget_state)
+        .title(format!(" Organise {} tracks ", view.tracks.len()))
+        .title_bottom(Line::from(vec![
+            " Move ".into(),
+            "<Enter> ".fg(theme.palette.primary),
+            " Cancel ".into(),
+            "<Esc> ".fg(theme.palette.primary),
+        ]));
+
+    Clear.render(area, buf);
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    let [header_area, list_area] =
+        Layout::vertical([Constraint::Length(HEADER_HEIGHT), Constraint::Fill(1)]).areas(inner);
+
+    let conflicts = view.renames.iter().filter(|rename| rename.conflict.is_some()).count();
+    let unchanged = view.tracks.len() - view.renames.len();
+    let header = vec![
+        Line::from(vec![
+            "Template ".fg(theme.palette.muted),
+            view.template.clone().into(),
+            theme.glyphs.cursor.into(),
+        ]),
+        Line::from(
+            [
+                format!("{} to move", view.renames.len() - conflicts),
+                format!("{} conflicts", conflicts),
+                format!("{} already in place", unchanged),
+            ]
+            .join(theme.glyphs.separator)
+            .fg(theme.palette.muted),
+        ),
+    ];
+    Paragraph::new(header).render(header_area, buf);
+
+    let lines: Vec<Line> =
+        view.renames.iter().skip(view.offset).map(|rename| rename_line(rename, theme)).collect();
+    Paragraph::new(lines)
+        .block(Block::new().borders(Borders::TOP).border_set(theme.borders.normal))
+        .render(list_area, buf);
+}
+
+/// Перемещение относительно общего каталога старого и нового путей
+fn rename_line(rename: &Rename, theme: &Theme) -> Line<'static> {
+    let from = Path::new(&rename.from);
+    let to = Path::new(&rename.to);
+    let common = from.ancestors().find(|ancestor| to.starts_with(ancestor)).unwrap_or(Path::new(""));
+    let relative = |path: &Path| path.strip_prefix(common).unwrap_or(path).to_string_lossy().to_string();
+
+    let mut spans = vec![Span::from(relative(from)).fg(theme.palette.muted), theme.glyphs.arrow.into()];
+    match rename.conflict {
+        Some(conflict) => {
+            spans.push(Span::from(relative(to)).fg(theme.palette.error));
+            spans.push(Span::from(format!(" ({})", conflict)).fg(theme.palette.error));
+        }
+        None => spans.push(Span::from(relative(to)).fg(theme.palette.success)),
+    }
+    Line::from(spans)
+}
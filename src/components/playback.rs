@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use crate::{
+    app::{AppState, CurrentTrackInfo},
+    player::Player,
+};
+
+/// Время трека в виде `м:сс`, а для часовых записей - `ч:мм:сс`
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds >= 3600 {
+        true => format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60),
+        false => format!("{}:{:02}", seconds / 60, seconds % 60),
+    }
+}
+
+/// Перематывает текущий трек на `offset` секунд относительно текущей позиции
+pub fn seek_by(offset: f64, app_state: &AppState, player: &Player) {
+    if let Some(info) = app_state.current_track_info() {
+        let position = (info.played_duration.as_secs_f64() + offset).clamp(0.0, info.duration.as_secs_f64());
+        seek_to(Duration::from_secs_f64(position), info, app_state, player);
+    }
+}
+
+/// Перематывает текущий трек на позицию `position` от начала
+///
+/// Позиция сразу попадает в состояние, чтобы интерфейс не ждал, пока ее опубликует проигрыватель.
+///
+/// # Args:
+/// * `info` - Сведения о текущем треке, из которых берется его длительность
+pub fn seek_to(position: Duration, info: CurrentTrackInfo, app_state: &AppState, player: &Player) {
+    let total = info.duration.as_secs_f64();
+    if total <= 0.0 {
+        return;
+    }
+
+    let position = position.min(info.duration);
+    app_state.set_current_track_info(Some(CurrentTrackInfo { played_duration: position, ..info }));
+    player.seek(position.as_secs_f64() / total);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_duration_shows_minutes_and_seconds() {
+        assert_eq!(format_duration(Duration::from_secs(0)), "0:00");
+        assert_eq!(format_duration(Duration::from_secs(65)), "1:05");
+        assert_eq!(format_duration(Duration::from_secs(3599)), "59:59");
+    }
+
+    #[test]
+    fn format_duration_shows_hours() {
+        assert_eq!(format_duration(Duration::from_secs(3600)), "1:00:00");
+        assert_eq!(format_duration(Duration::from_secs(2 * 3600 + 5 * 60 + 9)), "2:05:09");
+    }
+}
@@ -0,0 +1,139 @@
+use std::cmp::Ordering;
+
+use ratatui::layout::Constraint;
+
+use crate::{
+    components::playback::format_duration,
+    config::PlaylistColumn,
+    metadata::{file_name, TrackMetadata},
+    theme::Glyphs,
+};
+
+/// Направление сортировки
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    pub fn reversed(self) -> Self {
+        match self {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        }
+    }
+
+    pub fn indicator(self, glyphs: &Glyphs) -> &'static str {
+        match self {
+            SortDirection::Ascending => glyphs.sort_ascending,
+            SortDirection::Descending => glyphs.sort_descending,
+        }
+    }
+}
+
+/// Данные трека, нужные для отображения и сортировки строки
+pub struct TrackRow<'a> {
+    pub index: usize,
+    pub path: &'a str,
+    pub metadata: TrackMetadata,
+    pub play_count: u32,
+}
+
+impl TrackRow<'_> {
+    /// Название трека, а при его отсутствии - имя файла
+    pub fn title(&self) -> String {
+        self.metadata.title.clone().unwrap_or_else(|| file_name(self.path))
+    }
+}
+
+pub fn column_title(column: PlaylistColumn) -> &'static str {
+    match column {
+        PlaylistColumn::Number => "#",
+        PlaylistColumn::Title => "Title",
+        PlaylistColumn::Artist => "Artist",
+        PlaylistColumn::Album => "Album",
+        PlaylistColumn::AlbumArtist => "Album artist",
+        PlaylistColumn::Year => "Year",
+        PlaylistColumn::Genre => "Genre",
+        PlaylistColumn::Disc => "Disc",
+        PlaylistColumn::Track => "Track",
+        PlaylistColumn::Duration => "Time",
+        PlaylistColumn::Bitrate => "Kbps",
+        PlaylistColumn::PlayCount => "Plays",
+    }
+}
+
+pub fn column_width(column: PlaylistColumn) -> Constraint {
+    match column {
+        PlaylistColumn::Number => Constraint::Length(5),
+        PlaylistColumn::Title => Constraint::Fill(3),
+        PlaylistColumn::Artist | PlaylistColumn::Album | PlaylistColumn::AlbumArtist => Constraint::Fill(2),
+        PlaylistColumn::Genre => Constraint::Fill(1),
+        PlaylistColumn::Year | PlaylistColumn::Disc => Constraint::Length(4),
+        PlaylistColumn::Track | PlaylistColumn::Bitrate | PlaylistColumn::PlayCount => Constraint::Length(5),
+        PlaylistColumn::Duration => Constraint::Length(8),
+    }
+}
+
+/// Текст ячейки без подсветки
+pub fn cell_text(column: PlaylistColumn, row: &TrackRow) -> String {
+    let metadata = &row.metadata;
+    match column {
+        PlaylistColumn::Number => (row.index + 1).to_string(),
+        PlaylistColumn::Title => row.title(),
+        PlaylistColumn::Artist => metadata.artist.clone().unwrap_or_default(),
+        PlaylistColumn::Album => metadata.album.clone().unwrap_or_default(),
+        PlaylistColumn::AlbumArtist => metadata.album_artist.clone().unwrap_or_default(),
+        PlaylistColumn::Year => optional_text(metadata.year),
+        PlaylistColumn::Genre => metadata.genre.clone().unwrap_or_default(),
+        PlaylistColumn::Disc => optional_text(metadata.disc_number),
+        PlaylistColumn::Track => optional_text(metadata.track_number),
+        PlaylistColumn::Duration => metadata.duration.map(format_duration).unwrap_or_default(),
+        PlaylistColumn::Bitrate => optional_text(metadata.bitrate),
+        PlaylistColumn::PlayCount => row.play_count.to_string(),
+    }
+}
+
+/// Сравнивает строки по колонке. Пустые значения оказываются в конце при любом направлении.
+pub fn compare(column: PlaylistColumn, direction: SortDirection, a: &TrackRow, b: &TrackRow) -> Ordering {
+    let (x, y) = (&a.metadata, &b.metadata);
+    let d = direction;
+    match column {
+        PlaylistColumn::Number => compare_optional(Some(a.index), Some(b.index), d),
+        PlaylistColumn::Title => compare_text(Some(&a.title()), Some(&b.title()), d),
+        PlaylistColumn::Artist => compare_text(x.artist.as_ref(), y.artist.as_ref(), d),
+        PlaylistColumn::Album => compare_text(x.album.as_ref(), y.album.as_ref(), d),
+        PlaylistColumn::AlbumArtist => compare_text(album_artist(x), album_artist(y), d),
+        PlaylistColumn::Year => compare_optional(x.year, y.year, d),
+        PlaylistColumn::Genre => compare_text(x.genre.as_ref(), y.genre.as_ref(), d),
+        PlaylistColumn::Disc => compare_optional(x.disc_number, y.disc_number, d),
+        PlaylistColumn::Track => compare_optional(x.track_number, y.track_number, d),
+        PlaylistColumn::Duration => compare_optional(x.duration, y.duration, d),
+        PlaylistColumn::Bitrate => compare_optional(x.bitrate, y.bitrate, d),
+        PlaylistColumn::PlayCount => compare_optional(Some(a.play_count), Some(b.play_count), d),
+    }
+}
+
+/// Исполнитель альбома, а если он не указан - исполнитель трека
+fn album_artist(metadata: &TrackMetadata) -> Option<&String> {
+    metadata.album_artist.as_ref().or(metadata.artist.as_ref())
+}
+
+fn compare_text(a: Option<&String>, b: Option<&String>, direction: SortDirection) -> Ordering {
+    compare_optional(a.map(|a| a.to_lowercase()), b.map(|b| b.to_lowercase()), direction)
+}
+
+fn compare_optional<T: Ord>(a: Option<T>, b: Option<T>, direction: SortDirection) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if direction == SortDirection::Descending => b.cmp(&a),
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn optional_text<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
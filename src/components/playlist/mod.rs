@@ -0,0 +1,1041 @@
+mod columns;
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    layout::{Flex, Layout, Position, Rect},
+    style::{Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Cell, HighlightSpacing, Row, StatefulWidget, Table, TableState},
+};
+
+use crate::{
+    app::{AppState, State, StateChange, ToastLevel},
+    components::select_dialog,
+    config::{PlaylistColumn, PlaylistConfig},
+    files::{get_initial_playlist, walk_path},
+    fuzzy::fuzzy_match,
+    interaction::{EventHandler, InteractionState, InteractiveWidget},
+    library::Library,
+    metadata::{file_name, MetadataCache},
+    play_counts::PlayCounts,
+    playlist_store::PlaylistStore,
+    theme::Theme,
+};
+
+use columns::{cell_text, column_title, column_width, compare, SortDirection, TrackRow};
+
+/// Высота рамки, на которую смещены строки таблицы
+const BORDER_WIDTH: u16 = 1;
+/// Высота строки заголовков
+const HEADER_HEIGHT: u16 = 1;
+/// Отступ между колонками таблицы
+const COLUMN_SPACING: u16 = 1;
+/// На сколько строк перемещается выделение при прокрутке страницы
+const PAGE_SIZE: usize = 10;
+
+/// Состояние отображения плейлиста
+///
+/// # Fields
+///
+/// * `playlist_id` - Номер плейлиста, к которому относится это состояние
+/// * `selected` - Индекс выделенного трека в плейлисте (а не в отфильтрованном списке)
+/// * `anchor` - Трек, от которого выделен диапазон строк до `selected`
+/// * `table_state` - Прокрутка таблицы
+/// * `filter` - Строка фильтра
+/// * `filter_input` - Идет ли ввод фильтра
+/// * `rows` - Индексы треков плейлиста, прошедших фильтр
+/// * `rows_key` - Фильтр, версия кэша тегов и длина плейлиста, для которых посчитаны `rows`
+/// * `sort` - Колонка и направление последней сортировки по одной колонке
+/// * `column_bounds` - Горизонтальные границы колонок (относительно виджета) на последней отрисовке
+/// * `dragging` - Идет перетаскивание, начало которого уже запомнено в истории правок
+#[derive(Default)]
+struct PlaylistView {
+    playlist_id: u64,
+    selected: Option<usize>,
+    anchor: Option<usize>,
+    table_state: TableState,
+    filter: String,
+    filter_input: bool,
+    rows: Vec<usize>,
+    rows_key: Option<(String, u64, usize)>,
+    sort: Option<(PlaylistColumn, SortDirection)>,
+    column_bounds: Vec<(u16, u16)>,
+    dragging: bool,
+}
+
+impl PlaylistView {
+    /// Устарел ли отфильтрованный список для плейлиста длиной `len`
+    fn rows_stale(&self, len: usize, cache: &MetadataCache) -> bool {
+        self.rows_key.as_ref() != Some(&(self.filter.clone(), cache.version(), len))
+    }
+
+    /// Пересчитывает отфильтрованный список, если изменились фильтр, плейлист или теги.
+    ///
+    /// Длина плейлиста входит в ключ: кадр может быть нарисован после правки плейлиста,
+    /// но до того, как подписчик сбросит список, и прежние индексы вышли бы за его конец.
+    fn refresh_rows(&mut self, playlist: &[String], cache: &MetadataCache) {
+        if !self.rows_stale(playlist.len(), cache) {
+            return;
+        }
+
+        let terms: Vec<&str> = self.filter.split_whitespace().collect();
+        self.rows = (0..playlist.len())
+            .filter(|&index| terms.is_empty() || matches_filter(&terms, &playlist[index], cache))
+            .collect();
+        self.rows_key = Some((self.filter.clone(), cache.version(), playlist.len()));
+
+        if self.selected.is_some_and(|selected| selected >= playlist.len()) {
+            self.selected = playlist.len().checked_sub(1);
+        }
+    }
+
+    /// Сбрасывает закэшированный отфильтрованный список
+    fn invalidate(&mut self) {
+        self.rows_key = None;
+    }
+
+    /// Позиция выделенного трека в отфильтрованном списке
+    fn selected_row(&self) -> Option<usize> {
+        let selected = self.selected?;
+        self.rows.iter().position(|&index| index == selected)
+    }
+
+    /// Сдвигает выделение на `offset` строк отфильтрованного списка
+    fn move_selection(&mut self, offset: isize) {
+        self.anchor = None;
+        self.move_cursor(offset);
+    }
+
+    /// Расширяет выделенный диапазон на `offset` строк
+    fn extend_selection(&mut self, offset: isize) {
+        if self.anchor.is_none() {
+            self.anchor = self.selected_visible();
+        }
+        self.move_cursor(offset);
+    }
+
+    fn move_cursor(&mut self, offset: isize) {
+        if self.rows.is_empty() {
+            return;
+        }
+
+        let row = match self.selected_row() {
+            Some(row) => (row as isize + offset).clamp(0, self.rows.len() as isize - 1) as usize,
+            None if offset < 0 => self.rows.len() - 1,
+            None => 0,
+        };
+        self.selected = Some(self.rows[row]);
+    }
+
+    fn select_row(&mut self, row: usize) {
+        if let Some(&index) = self.rows.get(row) {
+            self.selected = Some(index);
+            self.anchor = None;
+        }
+    }
+
+    fn select_all(&mut self) {
+        if let (Some(&first), Some(&last)) = (self.rows.first(), self.rows.last()) {
+            self.anchor = Some(first);
+            self.selected = Some(last);
+        }
+    }
+
+    /// Строка отфильтрованного списка под курсором мыши. Выше первой видимой строки
+    /// (на заголовке) - предыдущая строка, чтобы при перетаскивании список прокручивался.
+    fn row_at(&self, y: u16) -> usize {
+        match y.checked_sub(BORDER_WIDTH + HEADER_HEIGHT) {
+            Some(offset) => self.table_state.offset() + offset as usize,
+            None => self.table_state.offset().saturating_sub(1),
+        }
+    }
+
+    /// Выделенный трек, если он виден при текущем фильтре
+    fn selected_visible(&self) -> Option<usize> {
+        self.selected_row().map(|row| self.rows[row])
+    }
+
+    /// Первая и последняя строки отфильтрованного списка, входящие в выделение
+    fn selected_range(&self) -> Option<(usize, usize)> {
+        let cursor = self.selected_row()?;
+        match self.anchor.and_then(|anchor| self.rows.iter().position(|&index| index == anchor)) {
+            Some(anchor) => Some((anchor.min(cursor), anchor.max(cursor))),
+            None => Some((cursor, cursor)),
+        }
+    }
+
+    /// Индексы выделенных треков в плейлисте по возрастанию
+    fn selected_indices(&self) -> Vec<usize> {
+        let Some((first, last)) = self.selected_range() else { return vec![] };
+
+        let mut indices = self.rows[first..=last].to_vec();
+        indices.sort_unstable();
+        indices
+    }
+
+    /// Переносит выделение на новые места треков после правки плейлиста
+    ///
+    /// Если выделенный трек удален, выделяется следующий за ним уцелевший трек.
+    fn remap(&mut self, order: &[usize]) {
+        let position = |index: usize| order.iter().position(|&old| old == index);
+
+        self.anchor = self.anchor.and_then(position);
+        self.selected = self.selected.map(|selected| {
+            position(selected)
+                .or_else(|| order.iter().position(|&old| old > selected))
+                .unwrap_or(order.len().saturating_sub(1))
+        });
+        if order.is_empty() {
+            self.selected = None;
+        }
+        self.invalidate();
+    }
+
+    fn clear_filter(&mut self) {
+        self.filter.clear();
+        self.filter_input = false;
+        self.invalidate();
+    }
+}
+
+/// Создает таблицу открытого плейлиста
+///
+/// Загружает сохраненные плейлисты; путь из командной строки открывается плейлистом
+/// с именем файла или каталога, заменяя одноименный.
+#[allow(clippy::too_many_arguments)]
+pub fn playlist_widget(
+    app_state: &AppState,
+    event_handler: &EventHandler,
+    metadata_cache: &MetadataCache,
+    play_counts: &PlayCounts,
+    playlist_store: &PlaylistStore,
+    library: &Library,
+    config: &PlaylistConfig,
+    theme: &Theme,
+) -> InteractiveWidget {
+    let view = Arc::new(Mutex::new(PlaylistView::default()));
+    let playlist = Playlist {
+        app_state: app_state.clone(),
+        event_handler: event_handler.clone(),
+        metadata_cache: metadata_cache.clone(),
+        play_counts: play_counts.clone(),
+        library: library.clone(),
+        config: config.clone(),
+        theme: *theme,
+        view,
+    };
+
+    // Путь из командной строки открывается новым плейлистом: сохраненный плейлист с тем же
+    // названием остается как есть, а новый получает номер (см. `State::set_playlists`)
+    let mut playlists = playlist_store.load();
+    let mut active = 0;
+    if let Some(initial) = get_initial_playlist() {
+        playlists.push(initial);
+        active = playlists.len() - 1;
+    }
+
+    app_state.update(|state| state.set_playlists(playlists, active));
+    playlist_store.track(app_state);
+
+    let active_tracks = app_state.playlist();
+    metadata_cache.prefetch(active_tracks, {
+        let app_state = app_state.clone();
+        move || app_state.request_redraw()
+    });
+    playlist.view.lock().unwrap().playlist_id = active_playlist_id(app_state);
+
+    // Подгружаем теги новых треков, чтобы по ним можно было искать и сортировать
+    app_state.subscribe({
+        let metadata_cache = metadata_cache.clone();
+        let view = playlist.view.clone();
+        move |app_state, changes| {
+            if changes.contains(&StateChange::Playlist) {
+                view.lock().unwrap().invalidate();
+
+                let app_state = app_state.clone();
+                metadata_cache.prefetch(app_state.playlist(), move || app_state.request_redraw());
+            }
+        }
+    });
+
+    // У каждого плейлиста свои выделение, прокрутка и фильтр: при смене вкладки
+    // состояние прежнего плейлиста откладывается до возвращения к нему
+    app_state.subscribe({
+        let view = playlist.view.clone();
+        let stashed = Mutex::new(HashMap::<u64, PlaylistView>::new());
+        move |app_state, changes| {
+            if !changes.contains(&StateChange::Playlists) {
+                return;
+            }
+
+            let id = active_playlist_id(app_state);
+            let mut view = view.lock().unwrap();
+            if view.playlist_id != id {
+                let mut stashed = stashed.lock().unwrap();
+                let restored = stashed
+                    .remove(&id)
+                    .unwrap_or_else(|| PlaylistView { playlist_id: id, ..Default::default() });
+                let previous = std::mem::replace(&mut *view, restored);
+                stashed.insert(previous.playlist_id, previous);
+                view.invalidate();
+            }
+        }
+    });
+
+    let mut widget = InteractiveWidget::default()
+        .focusable()
+        .help_section("Playlist")
+        .on_paste({
+            let playlist = playlist.clone();
+            move |_, paste_event| playlist.add_tracks(walk_path(Path::new(&paste_event)))
+        })
+        .describe("Add pasted path")
+        .on_key_input({
+            let playlist = playlist.clone();
+            move |_, key_event| on_filter_input(&playlist.view, key_event)
+        })
+        .help_key(KeyCode::Esc, KeyModifiers::NONE, "Clear filter")
+        .on_key_down(KeyCode::Char('s'), {
+            let playlist = playlist.clone();
+            move |_, _| playlist.send_selection()
+        })
+        .describe("Send selection to playlist")
+        .on_key_down(KeyCode::Char('/'), {
+            let view = playlist.view.clone();
+            move |_, _| view.lock().unwrap().filter_input = true
+        })
+        .describe("Filter")
+        .on_key_down(KeyCode::Up, {
+            let view = playlist.view.clone();
+            move |_, _| view.lock().unwrap().move_selection(-1)
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::Down, {
+            let view = playlist.view.clone();
+            move |_, _| view.lock().unwrap().move_selection(1)
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::PageUp, {
+            let view = playlist.view.clone();
+            move |_, _| view.lock().unwrap().move_selection(-(PAGE_SIZE as isize))
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::PageDown, {
+            let view = playlist.view.clone();
+            move |_, _| view.lock().unwrap().move_selection(PAGE_SIZE as isize)
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::Home, {
+            let view = playlist.view.clone();
+            move |_, _| view.lock().unwrap().select_row(0)
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::End, {
+            let view = playlist.view.clone();
+            move |_, _| {
+                let mut view = view.lock().unwrap();
+                let last = view.rows.len().saturating_sub(1);
+                view.select_row(last);
+            }
+        })
+        .describe("Move cursor")
+        .on_key_down_with_modifiers(KeyCode::Up, KeyModifiers::SHIFT, {
+            let view = playlist.view.clone();
+            move |_, _| view.lock().unwrap().extend_selection(-1)
+        })
+        .describe("Extend selection")
+        .on_key_down_with_modifiers(KeyCode::Down, KeyModifiers::SHIFT, {
+            let view = playlist.view.clone();
+            move |_, _| view.lock().unwrap().extend_selection(1)
+        })
+        .describe("Extend selection")
+        .on_key_down_with_modifiers(KeyCode::Char('a'), KeyModifiers::CONTROL, {
+            let view = playlist.view.clone();
+            move |_, _| view.lock().unwrap().select_all()
+        })
+        .describe("Select all")
+        .on_key_down(KeyCode::Enter, {
+            let playlist = playlist.clone();
+            move |_, _| playlist.play_selected()
+        })
+        .describe("Play")
+        .on_key_down(KeyCode::Char('q'), {
+            let playlist = playlist.clone();
+            move |_, _| playlist.enqueue_selected(false)
+        })
+        .describe("Queue selection")
+        .on_key_down(KeyCode::Char('n'), {
+            let playlist = playlist.clone();
+            move |_, _| playlist.enqueue_selected(true)
+        })
+        .describe("Play selection next")
+        .on_key_down(KeyCode::Delete, {
+            let playlist = playlist.clone();
+            move |_, _| playlist.remove_selected()
+        })
+        .describe("Remove selection")
+        .on_key_down_with_modifiers(KeyCode::Up, KeyModifiers::ALT, {
+            let playlist = playlist.clone();
+            move |_, _| playlist.move_selected(-1)
+        })
+        .describe("Move selection")
+        .on_key_down_with_modifiers(KeyCode::Down, KeyModifiers::ALT, {
+            let playlist = playlist.clone();
+            move |_, _| playlist.move_selected(1)
+        })
+        .describe("Move selection")
+        .on_key_down(KeyCode::Char('C'), {
+            let playlist = playlist.clone();
+            move |_, _| {
+                playlist.edit("Clear playlist", |state| {
+                    state.clear_playlist();
+                    vec![]
+                })
+            }
+        })
+        .describe("Clear playlist")
+        .on_key_down(KeyCode::Char('D'), {
+            let playlist = playlist.clone();
+            move |_, _| playlist.edit("Remove duplicates", |state| state.remove_duplicates())
+        })
+        .describe("Remove duplicates")
+        .on_key_down(KeyCode::Char('M'), {
+            let playlist = playlist.clone();
+            move |_, _| playlist.remove_missing()
+        })
+        .describe("Remove missing tracks")
+        .on_key_down(KeyCode::Char('e'), {
+            let playlist = playlist.clone();
+            move |_, _| playlist.edit_selected_tags()
+        })
+        .describe("Edit tags")
+        .on_key_down(KeyCode::Char('o'), {
+            let playlist = playlist.clone();
+            move |_, _| playlist.organise_selected()
+        })
+        .describe("Organise files")
+        .on_key_down(KeyCode::Char('R'), {
+            let playlist = playlist.clone();
+            move |_, _| playlist.relocate_missing()
+        })
+        .describe("Relocate missing tracks")
+        .on_global_key_down_with_modifiers(KeyCode::Char('z'), KeyModifiers::CONTROL, {
+            let playlist = playlist.clone();
+            move |_, _| playlist.undo()
+        })
+        .describe("Undo")
+        .on_global_key_down_with_modifiers(KeyCode::Char('y'), KeyModifiers::CONTROL, {
+            let playlist = playlist.clone();
+            move |_, _| playlist.redo()
+        })
+        .describe("Redo")
+        .on_key_down(KeyCode::Char('0'), {
+            let playlist = playlist.clone();
+            move |_, _| playlist.sort_by_preset()
+        })
+        .describe("Sort by preset")
+        .on_mouse_down({
+            let playlist = playlist.clone();
+            move |_, mouse_position| playlist.on_click(mouse_position)
+        })
+        .describe("Select, sort by column header")
+        .on_mouse_drag({
+            let playlist = playlist.clone();
+            move |_, mouse_position| playlist.drag_to(mouse_position)
+        })
+        .describe("Drag selection")
+        .on_mouse_scroll_up({
+            let view = playlist.view.clone();
+            move |_, _| view.lock().unwrap().move_selection(-1)
+        })
+        .describe("Move cursor")
+        .on_mouse_scroll_down({
+            let view = playlist.view.clone();
+            move |_, _| view.lock().unwrap().move_selection(1)
+        })
+        .describe("Move cursor");
+
+    // Клавиши 1-9 сортируют по соответствующей колонке
+    for (number, &column) in (1..=9).zip(&config.columns) {
+        widget = widget
+            .on_key_down(KeyCode::Char(char::from_digit(number, 10).unwrap()), {
+                let playlist = playlist.clone();
+                move |_, _| playlist.sort_by_column(column)
+            })
+            .describe("Sort by column");
+    }
+
+    widget.draw(move |widget_state, area, buf| playlist.draw(widget_state, area, buf))
+}
+
+/// Все, что нужно обработчикам плейлиста
+#[derive(Clone)]
+struct Playlist {
+    app_state: AppState,
+    event_handler: EventHandler,
+    metadata_cache: MetadataCache,
+    play_counts: PlayCounts,
+    library: Library,
+    config: PlaylistConfig,
+    theme: Theme,
+    view: Arc<Mutex<PlaylistView>>,
+}
+
+impl Playlist {
+    fn play_selected(&self) {
+        let selected = self.view.lock().unwrap().selected_visible();
+        if let Some(index) = selected {
+            self.app_state.update(|state| state.play_track(index));
+        }
+    }
+
+    fn on_click(&self, mouse_position: Position) {
+        let header_row = BORDER_WIDTH;
+        if mouse_position.y == header_row {
+            let column = {
+                let view = self.view.lock().unwrap();
+                view.column_bounds
+                    .iter()
+                    .position(|&(x, width)| (x..x + width).contains(&mouse_position.x))
+                    .and_then(|position| self.config.columns.get(position).copied())
+            };
+
+            if let Some(column) = column {
+                self.sort_by_column(column);
+            }
+            return;
+        }
+
+        if mouse_position.y < header_row {
+            return;
+        }
+
+        let mut view = self.view.lock().unwrap();
+        let row = view.row_at(mouse_position.y);
+        view.dragging = false;
+
+        // Щелчок внутри выделенного диапазона сохраняет его, чтобы диапазон можно было перетащить
+        if view.selected_range().is_some_and(|(first, last)| (first..=last).contains(&row)) {
+            return;
+        }
+        view.select_row(row);
+    }
+
+    /// Перетаскивание выделенных треков мышью на строку под курсором
+    fn drag_to(&self, mouse_position: Position) {
+        let (indices, before) = {
+            let view = self.view.lock().unwrap();
+            let Some((first, last)) = view.selected_range() else { return };
+            let row = view.row_at(mouse_position.y).min(view.rows.len().saturating_sub(1));
+
+            let before = match row {
+                row if row < first => view.rows[row],
+                row if row > last => view.rows[row] + 1,
+                _ => return,
+            };
+            (view.selected_indices(), before)
+        };
+
+        // Все перетаскивание отменяется одним действием
+        let dragging = std::mem::replace(&mut self.view.lock().unwrap().dragging, true);
+        if dragging {
+            self.apply(|state| state.move_tracks(&indices, before));
+        } else {
+            self.edit("Move tracks", |state| state.move_tracks(&indices, before));
+        }
+    }
+
+    /// Сдвигает выделенные треки за соседнюю видимую строку сверху (`offset < 0`) или снизу
+    fn move_selected(&self, offset: isize) {
+        let (indices, before) = {
+            let view = self.view.lock().unwrap();
+            let Some((first, last)) = view.selected_range() else { return };
+
+            let before = if offset < 0 {
+                let Some(row) = first.checked_sub(1) else { return };
+                view.rows[row]
+            } else {
+                let Some(&index) = view.rows.get(last + 1) else { return };
+                index + 1
+            };
+            (view.selected_indices(), before)
+        };
+
+        self.edit("Move tracks", |state| state.move_tracks(&indices, before));
+    }
+
+    fn remove_selected(&self) {
+        let indices = self.view.lock().unwrap().selected_indices();
+        match indices.len() {
+            0 => {}
+            1 => self.edit("Remove track", |state| state.remove_tracks(&indices)),
+            count => self.edit(&format!("Remove {} tracks", count), |state| state.remove_tracks(&indices)),
+        }
+    }
+
+    /// Ставит выделенные треки в очередь: в начало (`next`), чтобы они заиграли после текущего,
+    /// или в конец
+    fn enqueue_selected(&self, next: bool) {
+        let indices = self.view.lock().unwrap().selected_indices();
+        if indices.is_empty() {
+            return;
+        }
+
+        self.app_state.update(|state| match next {
+            true => state.enqueue_next(&indices),
+            false => state.enqueue(&indices),
+        });
+
+        let tracks = tracks_label(indices.len());
+        match next {
+            true => self.app_state.show_status(format!("Playing {} next", tracks)),
+            false => self.app_state.show_status(format!("Queued {}", tracks)),
+        }
+    }
+
+    /// Спрашивает, в какой из остальных плейлистов отправить выделенные треки
+    fn send_selection(&self) {
+        let indices = self.view.lock().unwrap().selected_indices();
+        let (targets, names): (Vec<usize>, Vec<String>) = self.app_state.read(|state| {
+            let active = state.active_playlist();
+            state
+                .playlists()
+                .iter()
+                .enumerate()
+                .filter(|&(index, _)| index != active)
+                .map(|(index, playlist)| (index, playlist.name.clone()))
+                .unzip()
+        });
+        if indices.is_empty() {
+            return;
+        }
+        if targets.is_empty() {
+            self.app_state.notify(ToastLevel::Warning, "There is no other playlist to send tracks to");
+            return;
+        }
+
+        let title = format!("Send {} to", tracks_label(indices.len()));
+        let app_state = self.app_state.clone();
+        let separator = self.theme.glyphs.separator;
+        select_dialog(&self.event_handler, &self.theme, &title, names, 0, move |item| {
+            let target = targets[item];
+            let label = app_state.update(|state| {
+                let name = &state.playlists()[target].name;
+                let label = format!("Send {} to {}", tracks_label(indices.len()), name);
+                state.send_tracks(&indices, target, &label);
+                label
+            });
+            app_state.notify(ToastLevel::Success, format!("{}{}Ctrl-Z to undo", label, separator));
+        });
+    }
+
+    fn add_tracks(&self, tracks: Vec<String>) {
+        if tracks.is_empty() {
+            return;
+        }
+
+        let label = format!("Add {} tracks", tracks.len());
+        self.edit(&label, |state| {
+            let order = (0..state.playlist().len()).collect();
+            state.add_tracks(tracks);
+            order
+        });
+    }
+
+    /// Удаляет треки, файлов которых больше нет на диске
+    fn remove_missing(&self) {
+        let missing: HashSet<String> =
+            self.app_state.playlist().into_iter().filter(|path| !Path::new(path).exists()).collect();
+        if missing.is_empty() {
+            return;
+        }
+
+        self.edit("Remove missing files", |state| {
+            let indices: Vec<usize> = (0..state.playlist().len())
+                .filter(|&index| missing.contains(&state.playlist()[index]))
+                .collect();
+            state.remove_tracks(&indices)
+        });
+    }
+
+    /// Открывает редактор тегов выделенных треков, кроме отсутствующих на диске
+    fn edit_selected_tags(&self) {
+        let indices = self.view.lock().unwrap().selected_indices();
+        self.app_state.update(|state| {
+            let tracks = self.existing_tracks(state, &indices);
+            state.set_tag_editor_tracks(tracks);
+        });
+    }
+
+    /// Пути треков плейлиста по индексам, кроме отсутствующих на диске
+    fn existing_tracks(&self, state: &State, indices: &[usize]) -> Vec<String> {
+        indices
+            .iter()
+            .filter_map(|&index| state.playlist().get(index))
+            .filter(|path| !state.is_track_missing(path))
+            .cloned()
+            .collect()
+    }
+
+    /// Открывает окно раскладки выделенных треков по шаблону, кроме отсутствующих на диске
+    fn organise_selected(&self) {
+        let indices = self.view.lock().unwrap().selected_indices();
+        self.app_state.update(|state| {
+            let tracks = self.existing_tracks(state, &indices);
+            state.set_organiser_tracks(tracks);
+        });
+    }
+
+    /// Заменяет пути пропавших треков, которые нашлись в медиатеке на новом месте
+    fn relocate_missing(&self) {
+        let missing: Vec<String> = self.app_state.read(|state| state.missing_tracks().cloned().collect());
+        let moves: HashMap<String, String> = missing
+            .into_iter()
+            .filter_map(|path| self.library.relocation(&path).map(|new_path| (path, new_path)))
+            .collect();
+        if moves.is_empty() {
+            self.app_state.show_status("No moved tracks found");
+            return;
+        }
+
+        let label = format!("Relocate {}", tracks_label(moves.len()));
+        let relocated = self.app_state.update(|state| state.relocate_tracks(&moves, &label));
+        if relocated > 0 {
+            self.view.lock().unwrap().invalidate();
+            self.app_state.show_status(format!("{}{}Ctrl-Z to undo", label, self.theme.glyphs.separator));
+        }
+    }
+
+    /// Запоминает плейлист в истории правок и применяет правку
+    fn edit(&self, label: &str, edit: impl FnOnce(&mut State) -> Vec<usize>) {
+        self.apply(|state| {
+            state.checkpoint_playlist(label);
+            edit(state)
+        });
+        self.app_state.show_status(format!("{}{}Ctrl-Z to undo", label, self.theme.glyphs.separator));
+    }
+
+    /// Применяет правку плейлиста, возвращающую порядок треков, и переносит выделение вслед за ними
+    fn apply(&self, edit: impl FnOnce(&mut State) -> Vec<usize>) {
+        let order = self.app_state.update(edit);
+
+        let mut view = self.view.lock().unwrap();
+        view.remap(&order);
+        // Ручная правка нарушает порядок сортировки
+        view.sort = None;
+    }
+
+    fn undo(&self) {
+        match self.app_state.update(|state| state.undo_playlist()) {
+            Some(label) => self.app_state.show_status(format!("Undo: {}", label)),
+            None => self.app_state.show_status("Nothing to undo"),
+        }
+        self.forget_selection_range();
+    }
+
+    fn redo(&self) {
+        match self.app_state.update(|state| state.redo_playlist()) {
+            Some(label) => self.app_state.show_status(format!("Redo: {}", label)),
+            None => self.app_state.show_status("Nothing to redo"),
+        }
+        self.forget_selection_range();
+    }
+
+    /// После отмены индексы выделенного диапазона указывают на другие треки
+    fn forget_selection_range(&self) {
+        let mut view = self.view.lock().unwrap();
+        view.anchor = None;
+        view.sort = None;
+    }
+
+    /// Сортирует по колонке; повторная сортировка по той же колонке меняет направление
+    fn sort_by_column(&self, column: PlaylistColumn) {
+        let direction = match self.view.lock().unwrap().sort {
+            Some((sorted_column, direction)) if sorted_column == column => direction.reversed(),
+            _ => SortDirection::Ascending,
+        };
+
+        self.sort(&[(column, direction)]);
+        self.view.lock().unwrap().sort = Some((column, direction));
+    }
+
+    /// Многоуровневая сортировка по ключам из настроек
+    fn sort_by_preset(&self) {
+        let keys: Vec<_> =
+            self.config.sort_preset.iter().map(|&column| (column, SortDirection::Ascending)).collect();
+
+        self.sort(&keys);
+        self.view.lock().unwrap().sort = None;
+    }
+
+    /// Переставляет треки плейлиста по ключам сортировки, сохраняя выделение
+    fn sort(&self, keys: &[(PlaylistColumn, SortDirection)]) {
+        let playlist = self.app_state.playlist();
+        let rows: Vec<TrackRow> =
+            playlist.iter().enumerate().map(|(index, path)| self.track_row(index, path)).collect();
+
+        let mut order: Vec<usize> = (0..rows.len()).collect();
+        order.sort_by(|&a, &b| {
+            keys.iter()
+                .map(|&(column, direction)| compare(column, direction, &rows[a], &rows[b]))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let sorted = self.app_state.update(|state| {
+            // Плейлист мог измениться, пока собирались данные для сортировки
+            if state.playlist() != playlist.as_slice() {
+                return false;
+            }
+            state.checkpoint_playlist("Sort");
+            state.rearrange_playlist(&order);
+            true
+        });
+
+        if sorted {
+            self.view.lock().unwrap().remap(&order);
+        }
+    }
+
+    fn track_row<'a>(&self, index: usize, path: &'a str) -> TrackRow<'a> {
+        TrackRow {
+            index,
+            path,
+            metadata: self.metadata_cache.get(path).unwrap_or_default(),
+            play_count: self.play_counts.get(path),
+        }
+    }
+
+    fn draw(&self, widget_state: InteractionState, area: Rect, buf: &mut Buffer) {
+        let theme = &self.theme;
+        let (total, current_track) =
+            self.app_state.read(|state| (state.playlist().len(), state.current_track()));
+
+        let mut view = self.view.lock().unwrap();
+        // Весь плейлист копируется, только когда нужно заново отфильтровать его
+        if view.rows_stale(total, &self.metadata_cache) {
+            view.refresh_rows(&self.app_state.playlist(), &self.metadata_cache);
+        }
+
+        let block = theme.panel(&widget_state).title(playlist_title(&view, total, theme));
+        let columns = &self.config.columns;
+        let widths: Vec<_> = columns.iter().map(|&column| column_width(column)).collect();
+        let inner = block.inner(area);
+        view.column_bounds = Layout::horizontal(widths.clone())
+            .flex(Flex::Start)
+            .spacing(COLUMN_SPACING)
+            .split(inner)
+            .iter()
+            .map(|rect| (rect.x - area.x, rect.width))
+            .collect();
+
+        // Строки строятся только для видимой части таблицы
+        let height = inner.height.saturating_sub(HEADER_HEIGHT) as usize;
+        let selected_row = view.selected_row();
+        let offset = visible_offset(view.table_state.offset(), selected_row, height, view.rows.len());
+        *view.table_state.offset_mut() = offset;
+        view.table_state.select(selected_row);
+
+        let window: Vec<usize> = view.rows.iter().skip(offset).take(height).copied().collect();
+        let tracks: Vec<Option<(String, bool)>> = self.app_state.read(|state| {
+            let playlist = state.playlist();
+            window
+                .iter()
+                .map(|&index| playlist.get(index).map(|path| (path.clone(), state.is_track_missing(path))))
+                .collect()
+        });
+
+        let terms: Vec<String> = view.filter.split_whitespace().map(str::to_string).collect();
+        let selected_range = view.selected_range();
+        let rows: Vec<Row> = window
+            .iter()
+            .zip(&tracks)
+            .enumerate()
+            .map(|(window_row, (&index, track))| {
+                let row_number = offset + window_row;
+                let Some((path, missing)) = track else { return Row::default() };
+                let row = self.track_row(index, path);
+                let mut cells: Vec<Cell> = columns
+                    .iter()
+                    .map(|&column| Cell::from(cell_line(column, &row, &terms, theme)))
+                    .collect();
+
+                let mut style = Style::new();
+                if *missing {
+                    style = style.fg(theme.palette.muted).add_modifier(Modifier::CROSSED_OUT);
+                }
+                if selected_range.is_some_and(|(first, last)| (first..=last).contains(&row_number)) {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+
+                if current_track == Some(index) {
+                    if let Some(first) = columns.first() {
+                        let text = format!("{}{}", theme.glyphs.current_track, cell_text(*first, &row));
+                        cells[0] = Cell::from(text);
+                    }
+                    style = style.add_modifier(Modifier::BOLD);
+                }
+                Row::new(cells).style(style)
+            })
+            .collect();
+
+        let header = Row::new(columns.iter().map(|&column| {
+            let title = column_title(column);
+            match view.sort {
+                Some((sorted_column, direction)) if sorted_column == column => {
+                    format!("{} {}", title, direction.indicator(&theme.glyphs))
+                }
+                _ => title.to_string(),
+            }
+        }))
+        .bold()
+        .underlined();
+
+        // Таблица получает уже прокрученное окно, поэтому рисуется без собственного смещения
+        let mut window_state =
+            TableState::default().with_selected(selected_row.and_then(|row| row.checked_sub(offset)));
+        Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .flex(Flex::Start)
+            .column_spacing(COLUMN_SPACING)
+            .highlight_spacing(HighlightSpacing::Never)
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .render(area, buf, &mut window_state);
+    }
+}
+
+/// Первая видимая строка таблицы: прежняя прокрутка, сдвинутая ровно настолько,
+/// чтобы выделенная строка оказалась на экране
+fn visible_offset(offset: usize, selected: Option<usize>, height: usize, len: usize) -> usize {
+    let offset = offset.min(len.saturating_sub(height));
+    match selected {
+        Some(selected) if selected < offset => selected,
+        Some(selected) if height > 0 && selected >= offset + height => selected + 1 - height,
+        _ => offset,
+    }
+}
+
+/// Ввод строки фильтра. Стрелки пропускаются дальше, к обычным привязкам, а Enter только
+/// завершает ввод, оставляя фильтр.
+///
+/// Esc сбрасывает фильтр, даже когда ввод уже завершен; без фильтра Esc уходит глобальным привязкам.
+fn on_filter_input(view: &Mutex<PlaylistView>, key_event: KeyEvent) -> bool {
+    // Сочетания с Ctrl и Alt - команды, а не ввод
+    if key_event.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+        return false;
+    }
+
+    let key_code = key_event.code;
+    let mut view = view.lock().unwrap();
+    if !view.filter_input {
+        if key_code == KeyCode::Esc && !view.filter.is_empty() {
+            view.clear_filter();
+            return true;
+        }
+        return false;
+    }
+
+    match key_code {
+        KeyCode::Char(c) => view.filter.push(c),
+        KeyCode::Backspace => {
+            if view.filter.pop().is_none() {
+                view.filter_input = false;
+            }
+        }
+        KeyCode::Esc => view.clear_filter(),
+        KeyCode::Enter => {
+            view.filter_input = false;
+            return true;
+        }
+        _ => return false,
+    }
+
+    view.invalidate();
+    if view.selected_visible().is_none() {
+        view.select_row(0);
+    }
+    true
+}
+
+fn active_playlist_id(app_state: &AppState) -> u64 {
+    app_state.read(|state| state.playlists()[state.active_playlist()].id)
+}
+
+fn tracks_label(count: usize) -> String {
+    match count {
+        1 => "track".to_string(),
+        count => format!("{} tracks", count),
+    }
+}
+
+fn playlist_title(view: &PlaylistView, total: usize, theme: &Theme) -> Line<'static> {
+    if view.filter.is_empty() && !view.filter_input {
+        return Line::from(" Playlist ");
+    }
+
+    let cursor = if view.filter_input { theme.glyphs.cursor } else { "" };
+    Line::from(vec![
+        " Playlist ".into(),
+        format!("/{}{}", view.filter, cursor).fg(theme.palette.accent),
+        format!(" ({}/{}) ", view.rows.len(), total).into(),
+    ])
+}
+
+/// Проходит ли трек фильтр: каждое слово фильтра должно найтись в названии,
+/// исполнителе, альбоме или имени файла
+fn matches_filter(terms: &[&str], path: &str, cache: &MetadataCache) -> bool {
+    let metadata = cache.get(path).unwrap_or_default();
+    let name = file_name(path);
+    let fields =
+        [metadata.title.as_deref(), metadata.artist.as_deref(), metadata.album.as_deref(), Some(&name)];
+
+    terms.iter().all(|term| fields.iter().flatten().any(|field| fuzzy_match(term, field).is_some()))
+}
+
+/// Ячейка таблицы с подсветкой совпадений с фильтром в текстовых колонках
+fn cell_line(column: PlaylistColumn, row: &TrackRow, terms: &[String], theme: &Theme) -> Line<'static> {
+    let text = cell_text(column, row);
+    match column {
+        PlaylistColumn::Title | PlaylistColumn::Artist | PlaylistColumn::Album => {
+            Line::from(highlighted_spans(&text, terms, theme))
+        }
+        _ => Line::from(text),
+    }
+}
+
+fn highlighted_spans(text: &str, terms: &[String], theme: &Theme) -> Vec<Span<'static>> {
+    let mut matched = vec![false; text.chars().count()];
+    for term in terms {
+        if let Some(found) = fuzzy_match(term, text) {
+            found.positions.iter().for_each(|&position| matched[position] = true);
+        }
+    }
+
+    let highlight = Style::new().fg(theme.palette.accent).bold();
+    let mut spans: Vec<Span> = vec![];
+    let mut current = String::new();
+    let mut current_matched = false;
+
+    for (c, is_matched) in text.chars().zip(matched) {
+        if is_matched != current_matched && !current.is_empty() {
+            let style = if current_matched { highlight } else { Style::new() };
+            spans.push(Span::styled(std::mem::take(&mut current), style));
+        }
+        current_matched = is_matched;
+        current.push(c);
+    }
+
+    if !current.is_empty() {
+        let style = if current_matched { highlight } else { Style::new() };
+        spans.push(Span::styled(current, style));
+    }
+    spans
+}
@@ -0,0 +1,224 @@
+use std::sync::{Arc, Mutex};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyModifiers},
+    layout::{Position, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::Widget,
+};
+
+use crate::{
+    app::{AppState, ToastLevel},
+    components::{confirm_dialog, prompt_dialog},
+    interaction::{EventHandler, InteractionState, InteractiveWidget},
+    theme::Theme,
+};
+
+const NEW_PLAYLIST_NAME: &str = "Playlist";
+const NEW_PLAYLIST_LABEL: &str = " + ";
+
+/// Состояние панели вкладок
+///
+/// # Fields
+///
+/// * `tab_bounds` - Горизонтальные границы вкладок (относительно виджета) на последней отрисовке;
+///   последняя - кнопка создания плейлиста
+#[derive(Default)]
+struct TabsView {
+    tab_bounds: Vec<(u16, u16)>,
+}
+
+/// Создает вкладки плейлистов
+///
+/// Стрелки и щелчок переключают плейлист, Insert создает новый, Enter спрашивает новое название,
+/// Delete удаляет после подтверждения. Ctrl-PgUp/Ctrl-PgDn переключают плейлист откуда угодно.
+pub fn playlist_tabs(app_state: &AppState, event_handler: &EventHandler, theme: &Theme) -> InteractiveWidget {
+    let view = Arc::new(Mutex::new(TabsView::default()));
+    let theme = *theme;
+
+    InteractiveWidget::default()
+        .focusable()
+        .help_section("Playlist tabs")
+        .on_key_down(KeyCode::Left, {
+            let app_state = app_state.clone();
+            move |_, _| switch_playlist(&app_state, -1)
+        })
+        .describe("Previous playlist")
+        .on_key_down(KeyCode::Right, {
+            let app_state = app_state.clone();
+            move |_, _| switch_playlist(&app_state, 1)
+        })
+        .describe("Next playlist")
+        .on_global_key_down_with_modifiers(KeyCode::PageUp, KeyModifiers::CONTROL, {
+            let app_state = app_state.clone();
+            move |_, _| switch_playlist(&app_state, -1)
+        })
+        .describe("Previous playlist")
+        .on_global_key_down_with_modifiers(KeyCode::PageDown, KeyModifiers::CONTROL, {
+            let app_state = app_state.clone();
+            move |_, _| switch_playlist(&app_state, 1)
+        })
+        .describe("Next playlist")
+        .on_key_down(KeyCode::Insert, {
+            let app_state = app_state.clone();
+            let event_handler = event_handler.clone();
+            move |_, _| create_playlist(&app_state, &event_handler, &theme)
+        })
+        .describe("New playlist")
+        .on_key_down(KeyCode::Enter, {
+            let app_state = app_state.clone();
+            let event_handler = event_handler.clone();
+            move |_, _| rename_playlist(&app_state, &event_handler, &theme)
+        })
+        .describe("Rename playlist")
+        .on_key_down(KeyCode::F(2), {
+            let app_state = app_state.clone();
+            let event_handler = event_handler.clone();
+            move |_, _| rename_playlist(&app_state, &event_handler, &theme)
+        })
+        .describe("Rename playlist")
+        .on_key_down(KeyCode::Delete, {
+            let app_state = app_state.clone();
+            let event_handler = event_handler.clone();
+            move |_, _| delete_playlist(&app_state, &event_handler, &theme)
+        })
+        .describe("Delete playlist")
+        .on_mouse_down({
+            let app_state = app_state.clone();
+            let event_handler = event_handler.clone();
+            let view = view.clone();
+            move |_, mouse_position| on_click(&app_state, &event_handler, &view, &theme, mouse_position)
+        })
+        .describe("Switch playlist, new playlist")
+        .on_mouse_scroll_up({
+            let app_state = app_state.clone();
+            move |_, _| switch_playlist(&app_state, -1)
+        })
+        .describe("Previous playlist")
+        .on_mouse_scroll_down({
+            let app_state = app_state.clone();
+            move |_, _| switch_playlist(&app_state, 1)
+        })
+        .describe("Next playlist")
+        .draw({
+            let app_state = app_state.clone();
+            move |widget_state, area, buf| {
+                draw_playlist_tabs(widget_state, &app_state, &view, &theme, area, buf)
+            }
+        })
+}
+
+fn switch_playlist(app_state: &AppState, offset: isize) {
+    app_state.update(|state| {
+        let count = state.playlists().len() as isize;
+        let index = (state.active_playlist() as isize + offset).rem_euclid(count);
+        state.set_active_playlist(index as usize);
+    });
+}
+
+/// Создает плейлист и сразу предлагает ввести его название
+fn create_playlist(app_state: &AppState, event_handler: &EventHandler, theme: &Theme) {
+    app_state.update(|state| state.create_playlist(NEW_PLAYLIST_NAME));
+    rename_playlist(app_state, event_handler, theme);
+}
+
+fn rename_playlist(app_state: &AppState, event_handler: &EventHandler, theme: &Theme) {
+    let (index, name) = app_state.read(|state| {
+        let index = state.active_playlist();
+        (index, state.playlists()[index].name.clone())
+    });
+
+    let app_state = app_state.clone();
+    prompt_dialog(event_handler, theme, "Playlist name", &name, move |name| {
+        app_state.update(|state| state.rename_playlist(index, &name));
+    });
+}
+
+/// Спрашивает подтверждение: вместе с плейлистом удаляется и его файл
+fn delete_playlist(app_state: &AppState, event_handler: &EventHandler, theme: &Theme) {
+    let (index, name, count) = app_state.read(|state| {
+        let index = state.active_playlist();
+        (index, state.playlists()[index].name.clone(), state.playlists().len())
+    });
+    if count == 1 {
+        app_state.notify(ToastLevel::Warning, "The last playlist cannot be deleted");
+        return;
+    }
+
+    let message = format!("Delete playlist \"{}\" together with its file?", name);
+    let app_state = app_state.clone();
+    confirm_dialog(event_handler, theme, "Delete playlist", &message, "Delete", move || {
+        match app_state.update(|state| state.delete_playlist(index)) {
+            true => app_state.notify(ToastLevel::Success, format!("Deleted playlist \"{}\"", name)),
+            false => app_state.notify(ToastLevel::Warning, "The last playlist cannot be deleted"),
+        }
+    });
+}
+
+fn on_click(
+    app_state: &AppState,
+    event_handler: &EventHandler,
+    view: &Mutex<TabsView>,
+    theme: &Theme,
+    mouse_position: Position,
+) {
+    let (tab, tab_count) = {
+        let view = view.lock().unwrap();
+        let tab = view.tab_bounds.iter().position(|&(x, width)| (x..x + width).contains(&mouse_position.x));
+        (tab, view.tab_bounds.len())
+    };
+
+    match tab {
+        Some(tab) if tab + 1 == tab_count => create_playlist(app_state, event_handler, theme),
+        Some(tab) => app_state.update(|state| state.set_active_playlist(tab)),
+        None => {}
+    }
+}
+
+fn draw_playlist_tabs(
+    widget_state: InteractionState,
+    app_state: &AppState,
+    view: &Mutex<TabsView>,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let (names, active) = app_state.read(|state| {
+        let names: Vec<String> = state.playlists().iter().map(|playlist| playlist.name.clone()).collect();
+        (names, state.active_playlist())
+    });
+
+    let mut view = view.lock().unwrap();
+    let focused = widget_state == InteractionState::Focused;
+
+    let mut spans: Vec<Span> = vec![];
+    let mut tab_bounds = vec![];
+    let mut x = 0;
+
+    for (index, name) in names.iter().enumerate() {
+        let label = Span::from(format!(" {} {} ", index + 1, name));
+
+        let style = match (index == active, focused) {
+            (true, true) => Style::new().reversed().bold(),
+            (true, false) => Style::new().reversed(),
+            _ => Style::new(),
+        };
+
+        let separator = Span::from(theme.glyphs.tab_separator).fg(theme.palette.muted);
+        let width = label.width() as u16;
+        tab_bounds.push((x, width));
+        x += width + separator.width() as u16;
+
+        spans.push(label.patch_style(style));
+        spans.push(separator);
+    }
+
+    let new_playlist = Span::from(NEW_PLAYLIST_LABEL).fg(theme.palette.muted);
+    tab_bounds.push((x, new_playlist.width() as u16));
+    spans.push(new_playlist);
+
+    view.tab_bounds = tab_bounds;
+    Line::from(spans).render(area, buf);
+}
@@ -1,26 +1,84 @@
-use std::time::Duration;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use ratatui::{
     buffer::Buffer,
+    crossterm::event::KeyCode,
     layout::{Position, Rect},
-    style::Stylize,
+    style::{Style, Stylize},
     text::Line,
-    widgets::{Block, Padding, Paragraph, Widget},
+    widgets::{Padding, Paragraph, Widget},
 };
 
-use crate::{app::{AppState, CurrentTrackInfo}, interaction::InteractiveWidget, player::Player};
+use crate::{
+    app::{AppState, StateChange},
+    components::playback::{format_duration, seek_by, seek_to},
+    config::{ProgressBarConfig, WaveformStyle},
+    interaction::{InteractionState, InteractiveWidget},
+    player::Player,
+    theme::Theme,
+    waveform::{Envelope, WaveformCache},
+};
 
 const PADDING: Padding = Padding::new(1, 1, 0, 0);
 const BORDER_WIDTH: u16 = 2;
 
-const PROGRESS_BAR_CHARACTER: char = '━';
-const PROGRESS_BAR_SLIDER_CHARACTER: char = '█';
+/// Биты точек Брайля в левой и правой колонке знакоместа, снизу вверх
+const BRAILLE_DOTS: [[u8; 4]; 2] = [[6, 2, 1, 0], [7, 5, 4, 3]];
+
+/// Шаг перемотки с клавиатуры в секундах
+const SEEK_STEP: f64 = 5.0;
+
+/// Режим полосы прогресса
+///
+/// # Fields
+///
+/// * `waveform` - Вместо линии рисуется огибающая трека
+/// * `style` - Символы, которыми рисуется огибающая
+/// * `hover` - Колонка под курсором (относительно виджета), для подсказки со временем
+struct ProgressBarView {
+    waveform: bool,
+    style: WaveformStyle,
+    hover: Option<u16>,
+}
+
+pub fn progress_bar(
+    app_state: &AppState,
+    player: &Player,
+    waveforms: &WaveformCache,
+    config: &ProgressBarConfig,
+    theme: &Theme,
+) -> InteractiveWidget {
+    let view = Arc::new(Mutex::new(ProgressBarView {
+        waveform: config.waveform,
+        style: config.waveform_style,
+        hover: None,
+    }));
+
+    app_state.subscribe({
+        let view = view.clone();
+        let waveforms = waveforms.clone();
+        move |app_state, changes| {
+            if changes.contains(&StateChange::CurrentTrack) && view.lock().unwrap().waveform {
+                request_waveform(app_state, &waveforms);
+            }
+        }
+    });
 
-pub fn progress_bar(app_state: &AppState, player: &Player) -> InteractiveWidget {
     InteractiveWidget::default()
+        .focusable()
+        .help_section("Progress bar")
         .draw({
             let app_state = app_state.clone();
-            move |_, area, buf| draw_progress_bar(&app_state, area, buf)
+            let view = view.clone();
+            let waveforms = waveforms.clone();
+            let theme = *theme;
+            move |widget_state, area, buf| {
+                draw_progress_bar(widget_state.clone(), &app_state, &view, &waveforms, &theme, area, buf);
+                draw_hover_time(widget_state, &app_state, &view, &theme, area, buf);
+            }
         })
         .on_mouse_down({
             let app_state = app_state.clone();
@@ -28,64 +86,236 @@ pub fn progress_bar(app_state: &AppState, player: &Player) -> InteractiveWidget
 
             move |widget, mouse_position| on_click(widget, mouse_position, &app_state, &player)
         })
+        .describe("Seek")
         .on_mouse_drag({
             let app_state = app_state.clone();
             let player = player.clone();
+            let view = view.clone();
 
-            move |widget, mouse_position| on_click(widget, mouse_position, &app_state, &player)
+            move |widget, mouse_position| {
+                view.lock().unwrap().hover = Some(mouse_position.x);
+                on_click(widget, mouse_position, &app_state, &player)
+            }
+        })
+        .describe("Seek")
+        .on_mouse_move({
+            let view = view.clone();
+            move |_, mouse_position| view.lock().unwrap().hover = Some(mouse_position.x)
+        })
+        .describe("Show time under cursor")
+        .on_mouse_scroll_up({
+            let app_state = app_state.clone();
+            let player = player.clone();
+
+            move |_, _| seek_by(SEEK_STEP, &app_state, &player)
+        })
+        .describe("Seek forward")
+        .on_mouse_scroll_down({
+            let app_state = app_state.clone();
+            let player = player.clone();
+
+            move |_, _| seek_by(-SEEK_STEP, &app_state, &player)
+        })
+        .describe("Seek back")
+        .on_key_down(KeyCode::Right, {
+            let app_state = app_state.clone();
+            let player = player.clone();
+
+            move |_, _| seek_by(SEEK_STEP, &app_state, &player)
+        })
+        .describe("Seek forward")
+        .on_key_down(KeyCode::Left, {
+            let app_state = app_state.clone();
+            let player = player.clone();
+
+            move |_, _| seek_by(-SEEK_STEP, &app_state, &player)
+        })
+        .describe("Seek back")
+        .on_key_down(KeyCode::Char('w'), {
+            let app_state = app_state.clone();
+            let waveforms = waveforms.clone();
+            move |_, _| {
+                let waveform = {
+                    let mut view = view.lock().unwrap();
+                    view.waveform = !view.waveform;
+                    view.waveform
+                };
+                if waveform {
+                    request_waveform(&app_state, &waveforms);
+                }
+            }
         })
+        .describe("Toggle waveform")
 }
 
-fn draw_progress_bar(app_state: &AppState, area: Rect, buf: &mut Buffer) {
-    let mut border_block = Block::bordered().padding(PADDING);
+/// Запускает вычисление огибающей текущего трека
+fn request_waveform(app_state: &AppState, waveforms: &WaveformCache) {
+    let Some(path) = app_state.read(|state| state.current_track_path().cloned()) else { return };
+    let app_state = app_state.clone();
+    waveforms.request(&path, move || app_state.request_redraw());
+}
+
+fn draw_progress_bar(
+    widget_state: InteractionState,
+    app_state: &AppState,
+    view: &Mutex<ProgressBarView>,
+    waveforms: &WaveformCache,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let mut border_block = theme.panel(&widget_state).padding(PADDING);
     let mut gauge_string: String = String::new();
 
     if let Some(info) = app_state.current_track_info() {
         let title = progress_bar_title(info.played_duration, info.duration);
         border_block = border_block.title(Line::from(title).right_aligned());
 
-        let progress_bar_width = area.width - 4; // ширина прогресс бара без границ и отступов
+        // Пока огибающая вычисляется, рисуется обычная линия
+        let view = view.lock().unwrap();
+        let envelope = waveforms.get(&info.path).filter(|_| view.waveform);
+        if let Some(envelope) = envelope {
+            let inner = border_block.inner(area);
+            border_block.render(area, buf);
+            let progress = info.played_duration.as_secs_f64() / info.duration.as_secs_f64();
+            draw_waveform(&envelope, progress, view.style, theme, inner, buf);
+            return;
+        }
+
+        let progress_bar_width = border_block.inner(area).width; // ширина прогресс бара без границ и отступов
 
         let play_progress = info.played_duration.as_secs_f64() / info.duration.as_secs_f64();
         let progress_bar_slider_position = progress_bar_width as f64 * play_progress;
         let bar_length = (progress_bar_slider_position - 1.0).max(0.0) as usize;
 
-        
         for _ in 0..bar_length {
-            gauge_string.push(PROGRESS_BAR_CHARACTER);
+            gauge_string.push(theme.glyphs.progress_line);
         }
     }
 
-    gauge_string.push(PROGRESS_BAR_SLIDER_CHARACTER);
+    gauge_string.push(theme.glyphs.progress_slider);
 
     Paragraph::new(Line::from(gauge_string).bold()).block(border_block).render(area, buf);
 }
 
+/// Огибающая трека; сыгранная часть закрашена другим цветом
+fn draw_waveform(
+    envelope: &Envelope,
+    progress: f64,
+    style: WaveformStyle,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    // Без точек Брайля огибающая рисуется блоками
+    let style = match theme.glyphs.braille {
+        true => style,
+        false => WaveformStyle::Blocks,
+    };
+    // В знакоместе Брайля две колонки точек и четыре ступени вместо восьми
+    let (columns_per_cell, steps) = match style {
+        WaveformStyle::Blocks => (1, 8),
+        WaveformStyle::Braille => (2, 4),
+    };
+    let columns = area.width as usize * columns_per_cell;
+    if columns == 0 {
+        return;
+    }
+    let played_columns = (columns as f64 * progress.clamp(0.0, 1.0)).round() as usize;
+
+    // Высота столбца в ступенях: пик своего участка огибающей
+    let heights: Vec<u16> = (0..columns)
+        .map(|column| {
+            let first = column * envelope.len() / columns;
+            let last = ((column + 1) * envelope.len() / columns).max(first + 1).min(envelope.len());
+            let level = envelope[first.min(last - 1)..last].iter().copied().fold(0.0, f32::max);
+            // Даже тишина видна как тонкая линия
+            ((level * area.height as f32 * steps as f32).round() as u16).max(1)
+        })
+        .collect();
+
+    for (cell, cell_heights) in heights.chunks(columns_per_cell).enumerate() {
+        let color = match cell * columns_per_cell < played_columns {
+            true => theme.palette.accent,
+            false => theme.palette.muted,
+        };
+        for row in 0..area.height {
+            let fills: Vec<u16> =
+                cell_heights.iter().map(|height| height.saturating_sub(row * steps).min(steps)).collect();
+            let symbol = match style {
+                WaveformStyle::Blocks if fills[0] == 0 => ' ',
+                WaveformStyle::Blocks => theme.glyphs.levels[fills[0] as usize - 1],
+                WaveformStyle::Braille => braille_bars(&fills),
+            };
+            let position = (area.x + cell as u16, area.bottom() - 1 - row);
+            buf[position].set_char(symbol).set_fg(color);
+        }
+    }
+}
+
+/// Знак Брайля со столбиками точек высотой `fills` (от 0 до 4) в левой и правой колонке
+fn braille_bars(fills: &[u16]) -> char {
+    let bits = fills
+        .iter()
+        .zip(BRAILLE_DOTS)
+        .flat_map(|(fill, dots)| dots.into_iter().take(*fill as usize))
+        .fold(0u32, |bits, dot| bits | 1 << dot);
+    char::from_u32(0x2800 + bits).unwrap_or(' ')
+}
+
+/// Время трека в колонке под курсором, на верхней границе над курсором
+fn draw_hover_time(
+    widget_state: InteractionState,
+    app_state: &AppState,
+    view: &Mutex<ProgressBarView>,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    if !matches!(widget_state, InteractionState::Hovered | InteractionState::Pressed) {
+        return;
+    }
+    let (Some(hover), Some(info)) = (view.lock().unwrap().hover, app_state.current_track_info()) else {
+        return;
+    };
+
+    let time = info.duration.mul_f64(position_ratio(area.width, hover));
+    let label = format!(" {} ", format_duration(time));
+    let width = label.chars().count() as u16;
+    if area.width < width + 2 {
+        return;
+    }
+    // Подсказка держится над курсором, не заходя на углы рамки
+    let x = hover.saturating_sub(width / 2).clamp(1, area.width - width - 1);
+    buf.set_string(
+        area.x + x,
+        area.y,
+        label,
+        Style::new().fg(theme.palette.accent_text).bg(theme.palette.accent),
+    );
+}
+
 fn on_click(widget: &mut InteractiveWidget, mouse_position: Position, app_state: &AppState, player: &Player) {
     if let Some(info) = app_state.current_track_info() {
-        // Вычисляем ширину активной области слайдера
-        let clickable_width = widget.area().width - PADDING.left - PADDING.right - BORDER_WIDTH;
-
-        // Вычисляем позицию клика относительно начала слайдера
-        let click_position = mouse_position.x as i16 - PADDING.left as i16;
-
-        // Выравниваем позицию клика относительно слайдера
-        let normalized_position = click_position.clamp(0, clickable_width as i16) as f64;
-        let progress_ratio = normalized_position / clickable_width as f64;
-
-        app_state.set_current_track_info(Some(
-            CurrentTrackInfo::new(
-                info.title, 
-                info.artist, 
-                info.album, 
-                info.duration, 
-                Duration::from_secs_f64(progress_ratio * info.duration.as_secs_f64())
-            )
-        ));
-
-        player.seek(progress_ratio);
+        let progress_ratio = position_ratio(widget.area().width, mouse_position.x);
+        seek_to(info.duration.mul_f64(progress_ratio), info, app_state, player);
     }
+}
 
+/// Доля трека, соответствующая колонке `x` (относительно виджета шириной `width`)
+fn position_ratio(width: u16, x: u16) -> f64 {
+    // Вычисляем ширину активной области слайдера
+    let clickable_width = width.saturating_sub(PADDING.left + PADDING.right + BORDER_WIDTH);
+    if clickable_width == 0 {
+        return 0.0;
+    }
+
+    // Вычисляем позицию клика относительно начала слайдера
+    let click_position = x as i16 - PADDING.left as i16;
+
+    // Выравниваем позицию клика относительно слайдера
+    let normalized_position = click_position.clamp(0, clickable_width as i16) as f64;
+    normalized_position / clickable_width as f64
 }
 
 fn progress_bar_title(played_duration: Duration, full_duration: Duration) -> String {
@@ -93,17 +323,3 @@ fn progress_bar_title(played_duration: Duration, full_duration: Duration) -> Str
     let full_duration = format_duration(full_duration);
     format!(" {} / {} ", played_duration, full_duration)
 }
-
-fn format_duration(duration: Duration) -> String {
-    let seconds = duration.as_secs() % 60;
-    let minutes = duration.as_secs() / 60;
-    let minutes = minutes % 60;
-    let hours = minutes / 60;
-
-    let mut result = String::new();
-    if hours > 0 {
-        result.push_str(&format!("{}:", hours));
-    }
-    result.push_str(&format!("{:02}:{:02}", minutes, seconds));
-    result
-}
@@ -0,0 +1,137 @@
+use std::sync::{Arc, Mutex};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::KeyCode,
+    layout::{Position, Rect},
+    style::{Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{List, ListItem, ListState, StatefulWidget},
+};
+
+use crate::{
+    app::AppState,
+    interaction::{InteractionState, InteractiveWidget},
+    metadata::{file_name, MetadataCache},
+    theme::Theme,
+};
+
+/// Высота рамки, на которую смещены строки списка
+const BORDER_WIDTH: u16 = 1;
+
+/// Создает панель очереди воспроизведения
+///
+/// Enter запускает выделенный трек сразу, Delete убирает его из очереди.
+pub fn queue_widget(
+    app_state: &AppState,
+    metadata_cache: &MetadataCache,
+    theme: &Theme,
+) -> InteractiveWidget {
+    let list_state = Arc::new(Mutex::new(ListState::default()));
+
+    InteractiveWidget::default()
+        .focusable()
+        .help_section("Queue")
+        .on_key_down(KeyCode::Up, {
+            let list_state = list_state.clone();
+            move |_, _| list_state.lock().unwrap().select_previous()
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::Down, {
+            let list_state = list_state.clone();
+            move |_, _| list_state.lock().unwrap().select_next()
+        })
+        .describe("Move cursor")
+        .on_key_down(KeyCode::Enter, {
+            let app_state = app_state.clone();
+            let list_state = list_state.clone();
+            move |_, _| {
+                if let Some(position) = list_state.lock().unwrap().selected() {
+                    app_state.update(|state| state.play_from_queue(position));
+                }
+            }
+        })
+        .describe("Play from queue")
+        .on_key_down(KeyCode::Delete, {
+            let app_state = app_state.clone();
+            let list_state = list_state.clone();
+            move |_, _| {
+                if let Some(position) = list_state.lock().unwrap().selected() {
+                    app_state.update(|state| state.remove_from_queue(position));
+                }
+            }
+        })
+        .describe("Remove from queue")
+        .on_mouse_down({
+            let list_state = list_state.clone();
+            move |_, mouse_position| on_click(&list_state, mouse_position)
+        })
+        .describe("Select")
+        .on_mouse_scroll_up({
+            let list_state = list_state.clone();
+            move |_, _| list_state.lock().unwrap().select_previous()
+        })
+        .describe("Move cursor")
+        .on_mouse_scroll_down({
+            let list_state = list_state.clone();
+            move |_, _| list_state.lock().unwrap().select_next()
+        })
+        .describe("Move cursor")
+        .draw({
+            let app_state = app_state.clone();
+            let metadata_cache = metadata_cache.clone();
+            let theme = *theme;
+            move |widget_state, area, buf| {
+                draw_queue(widget_state, &app_state, &metadata_cache, &list_state, &theme, area, buf)
+            }
+        })
+}
+
+fn on_click(list_state: &Mutex<ListState>, mouse_position: Position) {
+    if mouse_position.y < BORDER_WIDTH {
+        return;
+    }
+
+    let mut list_state = list_state.lock().unwrap();
+    let row = list_state.offset() + (mouse_position.y - BORDER_WIDTH) as usize;
+    list_state.select(Some(row));
+}
+
+fn draw_queue(
+    widget_state: InteractionState,
+    app_state: &AppState,
+    metadata_cache: &MetadataCache,
+    list_state: &Mutex<ListState>,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let tracks: Vec<String> = app_state.read(|state| state.queued_tracks().cloned().collect());
+
+    let items: Vec<ListItem> = tracks
+        .iter()
+        .enumerate()
+        .map(|(position, path)| {
+            let metadata = metadata_cache.get(path).unwrap_or_default();
+            let title = metadata.title.unwrap_or_else(|| file_name(path));
+
+            let mut spans =
+                vec![Span::from(format!("{}. ", position + 1)).fg(theme.palette.muted), Span::from(title)];
+            if let Some(artist) = metadata.artist {
+                spans.push(Span::from(format!(" - {}", artist)).fg(theme.palette.muted));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let block = theme.panel(&widget_state).title(format!(" Queue ({}) ", tracks.len()));
+
+    let mut list_state = list_state.lock().unwrap();
+    // Очередь могла сократиться, пока трек был выделен
+    if list_state.selected().is_some_and(|selected| selected >= tracks.len()) {
+        list_state.select(tracks.len().checked_sub(1));
+    }
+
+    let list = List::new(items).block(block).highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    list.render(area, buf, &mut list_state);
+}
@@ -0,0 +1,508 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use audiotags::{AudioTag, Id3v2Tag, Tag};
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::{
+    app::{AppState, StateChange, ToastLevel},
+    interaction::{InteractionState, InteractiveWidget},
+    metadata::{file_name, MetadataCache},
+    player::current_track_info,
+    theme::Theme,
+};
+
+/// Подпись поля, значения которого у выделенных треков различаются
+const KEEP_PLACEHOLDER: &str = "<keep existing>";
+/// Ширина колонки подписей полей
+const LABEL_WIDTH: u16 = 14;
+/// Высота рамки, на которую смещены поля формы
+const BORDER_WIDTH: u16 = 1;
+
+/// Поле тегов, которое можно изменить в редакторе
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TagField {
+    Title,
+    Artist,
+    Album,
+    AlbumArtist,
+    TrackNumber,
+    DiscNumber,
+    Year,
+    Genre,
+    Comment,
+}
+
+const FIELDS: [TagField; 9] = [
+    TagField::Title,
+    TagField::Artist,
+    TagField::Album,
+    TagField::AlbumArtist,
+    TagField::TrackNumber,
+    TagField::DiscNumber,
+    TagField::Year,
+    TagField::Genre,
+    TagField::Comment,
+];
+
+impl TagField {
+    fn label(self) -> &'static str {
+        match self {
+            TagField::Title => "Title",
+            TagField::Artist => "Artist",
+            TagField::Album => "Album",
+            TagField::AlbumArtist => "Album artist",
+            TagField::TrackNumber => "Track",
+            TagField::DiscNumber => "Disc",
+            TagField::Year => "Year",
+            TagField::Genre => "Genre",
+            TagField::Comment => "Comment",
+        }
+    }
+
+    /// Номер трека, диска и год должны быть числами
+    fn is_numeric(self) -> bool {
+        matches!(self, TagField::TrackNumber | TagField::DiscNumber | TagField::Year)
+    }
+
+    /// Значение поля в тегах файла; пустая строка - поля нет
+    fn read(self, tag: &dyn AudioTag) -> String {
+        let value = match self {
+            TagField::Title => tag.title().map(str::to_string),
+            TagField::Artist => tag.artist().map(str::to_string),
+            TagField::Album => tag.album_title().map(str::to_string),
+            TagField::AlbumArtist => tag.album_artist().map(str::to_string),
+            TagField::TrackNumber => tag.track_number().map(|number| number.to_string()),
+            TagField::DiscNumber => tag.disc_number().map(|number| number.to_string()),
+            TagField::Year => tag.year().map(|year| year.to_string()),
+            TagField::Genre => tag.genre().map(str::to_string),
+            TagField::Comment => tag.comment().map(str::to_string),
+        };
+        value.unwrap_or_default()
+    }
+
+    /// Записывает значение в теги; пустое значение удаляет поле. Значение числового поля
+    /// должно быть проверено заранее.
+    fn write(self, tag: &mut dyn AudioTag, value: &str) {
+        let number = value.parse::<u16>().ok();
+        match (self, value.is_empty()) {
+            (TagField::Title, true) => tag.remove_title(),
+            (TagField::Title, false) => tag.set_title(value),
+            (TagField::Artist, true) => tag.remove_artist(),
+            (TagField::Artist, false) => tag.set_artist(value),
+            (TagField::Album, true) => tag.remove_album_title(),
+            (TagField::Album, false) => tag.set_album_title(value),
+            (TagField::AlbumArtist, true) => tag.remove_album_artist(),
+            (TagField::AlbumArtist, false) => tag.set_album_artist(value),
+            (TagField::TrackNumber, true) => tag.remove_track_number(),
+            (TagField::TrackNumber, false) => tag.set_track_number(number.unwrap_or_default()),
+            (TagField::DiscNumber, true) => tag.remove_disc_number(),
+            (TagField::DiscNumber, false) => tag.set_disc_number(number.unwrap_or_default()),
+            (TagField::Year, true) => tag.remove_year(),
+            (TagField::Year, false) => tag.set_year(value.parse().unwrap_or_default()),
+            (TagField::Genre, true) => tag.remove_genre(),
+            (TagField::Genre, false) => tag.set_genre(value),
+            (TagField::Comment, true) => tag.remove_comment(),
+            (TagField::Comment, false) => tag.set_comment(value.to_string()),
+        }
+    }
+
+    fn is_valid(self, value: &str) -> bool {
+        match self {
+            TagField::Year => value.is_empty() || value.parse::<i32>().is_ok(),
+            _ if self.is_numeric() => value.is_empty() || value.parse::<u16>().is_ok(),
+            _ => true,
+        }
+    }
+}
+
+/// Значение поля формы: новое значение для всех треков или прежние значения каждого
+#[derive(Debug, Clone, PartialEq)]
+enum FieldValue {
+    Keep,
+    Set(String),
+}
+
+/// Изменение одного поля одного трека
+struct TagChange {
+    track: usize,
+    field: TagField,
+    old: String,
+    new: String,
+}
+
+/// Состояние редактора тегов
+///
+/// # Fields
+///
+/// * `tracks` - Пути к редактируемым файлам
+/// * `originals` - Значения полей каждого трека на момент открытия редактора
+/// * `initial` - Значения формы на момент открытия: общее значение поля или `Keep`
+/// * `values` - Значения формы
+/// * `field` - Поле, в которое идет ввод
+#[derive(Default)]
+struct EditorView {
+    tracks: Vec<String>,
+    originals: Vec<Vec<String>>,
+    initial: Vec<FieldValue>,
+    values: Vec<FieldValue>,
+    field: usize,
+}
+
+impl EditorView {
+    /// Читает теги треков и заполняет форму: поле, одинаковое у всех треков, показывает
+    /// общее значение, различающееся - заглушку «оставить как есть»
+    fn open(tracks: Vec<String>) -> Self {
+        let originals: Vec<Vec<String>> = tracks
+            .iter()
+            .map(|path| match Tag::new().read_from_path(path) {
+                Ok(tag) => FIELDS.iter().map(|field| field.read(tag.as_ref())).collect(),
+                Err(_) => vec![String::new(); FIELDS.len()],
+            })
+            .collect();
+
+        let initial: Vec<FieldValue> = (0..FIELDS.len())
+            .map(|index| {
+                let first = &originals[0][index];
+                match originals.iter().all(|values| values[index] == *first) {
+                    true => FieldValue::Set(first.clone()),
+                    false => FieldValue::Keep,
+                }
+            })
+            .collect();
+
+        Self { tracks, originals, values: initial.clone(), initial, field: 0 }
+    }
+
+    fn move_field(&mut self, offset: isize) {
+        self.field = (self.field as isize + offset).rem_euclid(FIELDS.len() as isize) as usize;
+    }
+
+    fn type_char(&mut self, c: char) {
+        match &mut self.values[self.field] {
+            FieldValue::Set(value) => value.push(c),
+            value => *value = FieldValue::Set(c.to_string()),
+        }
+    }
+
+    fn delete_char(&mut self) {
+        if let FieldValue::Set(value) = &mut self.values[self.field] {
+            value.pop();
+        }
+    }
+
+    /// Поле с некорректным значением, из-за которого сохранить теги нельзя
+    fn invalid_field(&self) -> Option<TagField> {
+        FIELDS.iter().zip(&self.values).find_map(|(&field, value)| match value {
+            FieldValue::Set(value) if !field.is_valid(value) => Some(field),
+            _ => None,
+        })
+    }
+
+    /// Все изменения, которые будут записаны в файлы
+    fn changes(&self) -> Vec<TagChange> {
+        let mut changes = vec![];
+        for (track, originals) in self.originals.iter().enumerate() {
+            for (index, &field) in FIELDS.iter().enumerate() {
+                let FieldValue::Set(new) = &self.values[index] else { continue };
+                if *new != originals[index] {
+                    let old = originals[index].clone();
+                    changes.push(TagChange { track, field, old, new: new.clone() });
+                }
+            }
+        }
+        changes
+    }
+}
+
+/// Создает модальный редактор тегов для треков, выбранных в плейлисте
+///
+/// Поля, значения которых у выделенных треков различаются, остаются как есть, пока в них
+/// ничего не введено. Под формой показывается, что именно изменится в каждом файле.
+pub fn tag_editor(app_state: &AppState, metadata_cache: &MetadataCache, theme: &Theme) -> InteractiveWidget {
+    let view = Arc::new(Mutex::new(EditorView::default()));
+
+    app_state.subscribe({
+        let view = view.clone();
+        move |app_state, changes| {
+            if changes.contains(&StateChange::TagEditor) {
+                let tracks = app_state.read(|state| state.tag_editor_tracks().to_vec());
+                *view.lock().unwrap() = match tracks.is_empty() {
+                    true => EditorView::default(),
+                    false => EditorView::open(tracks),
+                };
+            }
+        }
+    });
+
+    InteractiveWidget::default()
+        .focusable()
+        .help_section("Tag editor")
+        .on_key_input({
+            let app_state = app_state.clone();
+            let metadata_cache = metadata_cache.clone();
+            let view = view.clone();
+            move |_, key_event| on_input(&app_state, &metadata_cache, &view, key_event)
+        })
+        .help_key(KeyCode::Enter, KeyModifiers::NONE, "Save tags")
+        .help_key(KeyCode::Esc, KeyModifiers::NONE, "Cancel")
+        .help_key(KeyCode::Up, KeyModifiers::NONE, "Previous field")
+        .help_key(KeyCode::BackTab, KeyModifiers::NONE, "Previous field")
+        .help_key(KeyCode::Down, KeyModifiers::NONE, "Next field")
+        .help_key(KeyCode::Tab, KeyModifiers::NONE, "Next field")
+        .help_key(KeyCode::Char('u'), KeyModifiers::CONTROL, "Clear field")
+        .help_key(KeyCode::Char('r'), KeyModifiers::CONTROL, "Revert field")
+        .on_mouse_down({
+            let view = view.clone();
+            move |_, mouse_position| on_click(&view, mouse_position)
+        })
+        .describe("Select field")
+        .draw({
+            let theme = *theme;
+            move |widget_state, area, buf| draw_tag_editor(widget_state, &view, &theme, area, buf)
+        })
+}
+
+/// Редактор забирает себе все клавиши, пока открыт
+fn on_input(
+    app_state: &AppState,
+    metadata_cache: &MetadataCache,
+    view: &Mutex<EditorView>,
+    key_event: KeyEvent,
+) -> bool {
+    if view.lock().unwrap().tracks.is_empty() {
+        return false;
+    }
+
+    // Закрытие редактора уведомляет подписчика, которому нужно состояние редактора,
+    // поэтому оно не должно быть заблокировано
+    match key_event.code {
+        KeyCode::Esc => close(app_state),
+        KeyCode::Enter => save(app_state, metadata_cache, view),
+        _ => edit_field(&mut view.lock().unwrap(), key_event),
+    }
+    true
+}
+
+fn edit_field(view: &mut EditorView, key_event: KeyEvent) {
+    let control = key_event.modifiers.contains(KeyModifiers::CONTROL);
+    match key_event.code {
+        KeyCode::Up | KeyCode::BackTab => view.move_field(-1),
+        KeyCode::Down | KeyCode::Tab => view.move_field(1),
+        KeyCode::Backspace => view.delete_char(),
+        KeyCode::Char('u') if control => {
+            let field = view.field;
+            view.values[field] = FieldValue::Set(String::new());
+        }
+        KeyCode::Char('r') if control => {
+            let field = view.field;
+            view.values[field] = view.initial[field].clone();
+        }
+        KeyCode::Char(c) if !control => view.type_char(c),
+        _ => {}
+    }
+}
+
+fn close(app_state: &AppState) {
+    app_state.update(|state| state.set_tag_editor_tracks(vec![]));
+}
+
+fn on_click(view: &Mutex<EditorView>, mouse_position: Position) {
+    let mut view = view.lock().unwrap();
+    if let Some(row) = mouse_position.y.checked_sub(BORDER_WIDTH) {
+        if (row as usize) < FIELDS.len() {
+            view.field = row as usize;
+        }
+    }
+}
+
+/// Закрывает редактор и записывает изменения в фоне, после чего перечитывает теги
+/// в кэш и обновляет информацию о текущем треке
+fn save(app_state: &AppState, metadata_cache: &MetadataCache, view: &Mutex<EditorView>) {
+    let (values, changed) = {
+        let view = view.lock().unwrap();
+        if let Some(field) = view.invalid_field() {
+            app_state.show_status(format!("{} must be a number", field.label()));
+            return;
+        }
+
+        let values: Vec<(TagField, String)> = FIELDS
+            .iter()
+            .zip(&view.values)
+            .filter_map(|(&field, value)| match value {
+                FieldValue::Set(value) => Some((field, value.clone())),
+                FieldValue::Keep => None,
+            })
+            .collect();
+        let mut changed: Vec<usize> = view.changes().iter().map(|change| change.track).collect();
+        changed.dedup();
+        let changed: Vec<String> = changed.into_iter().map(|track| view.tracks[track].clone()).collect();
+        (values, changed)
+    };
+
+    close(app_state);
+    if changed.is_empty() {
+        return;
+    }
+
+    let app_state = app_state.clone();
+    let metadata_cache = metadata_cache.clone();
+    thread::spawn(move || {
+        let errors: Vec<String> = changed
+            .iter()
+            .filter_map(|path| {
+                write_tags(path, &values).err().map(|error| format!("\"{}\": {}", file_name(path), error))
+            })
+            .collect();
+
+        metadata_cache.reload(&changed);
+        refresh_current_track(&app_state, &metadata_cache, &changed);
+
+        match errors.first() {
+            Some(error) => app_state.notify(ToastLevel::Error, format!("Cannot save tags of {}", error)),
+            None => app_state.notify(ToastLevel::Success, format!("Saved tags of {} tracks", changed.len())),
+        }
+    });
+}
+
+/// Записывает поля в теги файла. У файла без тегов они создаются.
+fn write_tags(path: &str, values: &[(TagField, String)]) -> Result<(), String> {
+    let mut tag: Box<dyn AudioTag + Send + Sync> = match Tag::new().read_from_path(path) {
+        Ok(tag) => tag,
+        // В файле MP3 еще нет тега ID3
+        Err(audiotags::Error::Id3TagError(_)) => Box::new(Id3v2Tag::new()),
+        Err(error) => return Err(error.to_string()),
+    };
+
+    for (field, value) in values {
+        field.write(tag.as_mut(), value);
+    }
+    tag.write_to_path(path).map_err(|error| error.to_string())
+}
+
+/// Подставляет новые теги в информацию о текущем треке, не трогая позицию воспроизведения
+fn refresh_current_track(app_state: &AppState, metadata_cache: &MetadataCache, paths: &[String]) {
+    app_state.update(|state| {
+        let Some(path) = state.current_track_path().filter(|path| paths.contains(path)).cloned() else {
+            return;
+        };
+        let Some(info) = state.current_track_info().cloned() else { return };
+
+        let mut refreshed =
+            current_track_info(&path, metadata_cache.load(&path), info.stream.clone(), info.duration);
+        refreshed.played_duration = info.played_duration;
+        state.set_current_track_info(Some(refreshed));
+    });
+}
+
+fn draw_tag_editor(
+    widget_state: InteractionState,
+    view: &Mutex<EditorView>,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let view = view.lock().unwrap();
+    if view.tracks.is_empty() {
+        return;
+    }
+
+    let title = match view.tracks.as_slice() {
+        [path] => format!(" Edit tags: {} ", file_name(path)),
+        tracks => format!(" Edit tags: {} tracks ", tracks.len()),
+    };
+    let block = theme.panel(&widget_state).title(title).title_bottom(Line::from(vec![
+        " Save ".into(),
+        "<Enter> ".fg(theme.palette.primary),
+        " Cancel ".into(),
+        "<Esc> ".fg(theme.palette.primary),
+        " Clear ".into(),
+        "<Ctrl-U> ".fg(theme.palette.primary),
+        " Revert ".into(),
+        "<Ctrl-R> ".fg(theme.palette.primary),
+    ]));
+
+    Clear.render(area, buf);
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    let [form_area, preview_area] =
+        Layout::vertical([Constraint::Length(FIELDS.len() as u16), Constraint::Fill(1)]).areas(inner);
+
+    let form: Vec<Line> = FIELDS
+        .iter()
+        .zip(&view.values)
+        .enumerate()
+        .map(|(index, (&field, value))| field_line(field, value, index == view.field, theme))
+        .collect();
+    Paragraph::new(form).render(form_area, buf);
+
+    let preview = preview_lines(&view, preview_area.height.saturating_sub(1) as usize, theme);
+    Paragraph::new(preview)
+        .block(Block::new().borders(Borders::TOP).border_set(theme.borders.normal))
+        .render(preview_area, buf);
+}
+
+fn field_line(field: TagField, value: &FieldValue, active: bool, theme: &Theme) -> Line<'static> {
+    let label = format!("{:>width$} ", field.label(), width = LABEL_WIDTH as usize - 1);
+    let mut spans = vec![Span::from(label).fg(theme.palette.muted)];
+
+    let value = match value {
+        FieldValue::Keep => Span::from(KEEP_PLACEHOLDER).fg(theme.palette.muted).italic(),
+        FieldValue::Set(value) if !field.is_valid(value) => Span::from(value.clone()).fg(theme.palette.error),
+        FieldValue::Set(value) => Span::from(value.clone()),
+    };
+    spans.push(value);
+
+    if active {
+        spans.push(Span::from(theme.glyphs.cursor));
+        return Line::from(spans).style(Style::new().add_modifier(Modifier::BOLD));
+    }
+    Line::from(spans)
+}
+
+/// Изменения по файлам в пределах `height` строк
+fn preview_lines(view: &EditorView, height: usize, theme: &Theme) -> Vec<Line<'static>> {
+    let changes = view.changes();
+    if changes.is_empty() {
+        return vec![Line::from("No changes").fg(theme.palette.muted)];
+    }
+
+    let mut lines = vec![];
+    let mut last_track = None;
+    for change in &changes {
+        if last_track != Some(change.track) {
+            lines.push(Line::from(file_name(&view.tracks[change.track])).bold());
+            last_track = Some(change.track);
+        }
+
+        let old =
+            if change.old.is_empty() { theme.glyphs.empty_value.to_string() } else { change.old.clone() };
+        let new =
+            if change.new.is_empty() { theme.glyphs.empty_value.to_string() } else { change.new.clone() };
+        lines.push(Line::from(vec![
+            format!("  {}: ", change.field.label()).fg(theme.palette.muted),
+            Span::from(old).crossed_out(),
+            theme.glyphs.arrow.into(),
+            Span::from(new).fg(theme.palette.success),
+        ]));
+    }
+
+    if lines.len() > height {
+        let hidden = lines.len() - height + 1;
+        lines.truncate(height.saturating_sub(1));
+        lines.push(
+            Line::from(format!("{}and {} more lines", theme.glyphs.ellipsis, hidden)).fg(theme.palette.muted),
+        );
+    }
+    lines
+}
@@ -0,0 +1,101 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Color, Style},
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::{
+    app::{AppState, Toast, ToastLevel},
+    interaction::InteractiveWidget,
+    theme::Theme,
+};
+
+/// Высота одного уведомления вместе с рамкой
+pub const TOAST_HEIGHT: u16 = 3;
+
+/// Создает всплывающие уведомления: рамки с текстом, сложенные снизу вверх, новые - внизу
+///
+/// Уведомления исчезают сами через несколько секунд, щелчок убирает уведомление сразу.
+/// Виджету нужна область высотой [`TOAST_HEIGHT`] на каждое уведомление.
+pub fn toasts(app_state: &AppState, theme: &Theme) -> InteractiveWidget {
+    InteractiveWidget::default()
+        .help_section("Notifications")
+        .on_mouse_down({
+            let app_state = app_state.clone();
+            move |widget, mouse_position| on_click(widget, &app_state, mouse_position)
+        })
+        .describe("Dismiss notification")
+        .draw({
+            let app_state = app_state.clone();
+            let theme = *theme;
+            move |_, area, buf| draw_toasts(&app_state.toasts(), &theme, area, buf)
+        })
+}
+
+fn on_click(widget: &mut InteractiveWidget, app_state: &AppState, mouse_position: Position) {
+    let toasts = app_state.toasts();
+    let areas = toast_areas(widget.area().height, toasts.len());
+    let clicked = areas.iter().position(|&(y, height)| (y..y + height).contains(&mouse_position.y));
+    if let Some(toast) = clicked.and_then(|index| toasts.get(index)) {
+        app_state.update(|state| state.dismiss_toast(toast.id));
+    }
+}
+
+/// Вертикальные границы уведомлений (относительно виджета); если все не помещаются,
+/// место достается самым свежим
+fn toast_areas(height: u16, count: usize) -> Vec<(u16, u16)> {
+    let visible = count.min((height / TOAST_HEIGHT) as usize);
+    let top = height - visible as u16 * TOAST_HEIGHT;
+    let hidden = count - visible;
+
+    (0..count)
+        .map(|index| match index.checked_sub(hidden) {
+            Some(row) => (top + row as u16 * TOAST_HEIGHT, TOAST_HEIGHT),
+            None => (0, 0),
+        })
+        .collect()
+}
+
+fn draw_toasts(toasts: &[Toast], theme: &Theme, area: Rect, buf: &mut Buffer) {
+    let areas = toast_areas(area.height, toasts.len());
+    for (toast, (y, height)) in toasts.iter().zip(areas) {
+        if height == 0 {
+            continue;
+        }
+
+        let (title, color) = level_style(toast.level, theme);
+        let block = Block::bordered()
+            .border_set(theme.borders.normal)
+            .border_style(Style::new().fg(color))
+            .title(title);
+        let toast_area = Rect { y: area.y + y, height, ..area };
+        let text_area = block.inner(toast_area);
+
+        Clear.render(toast_area, buf);
+        block.render(toast_area, buf);
+        Paragraph::new(truncate(&toast.text, text_area.width as usize, theme.glyphs.ellipsis))
+            .style(Style::new().fg(theme.palette.text))
+            .render(text_area, buf);
+    }
+}
+
+fn level_style(level: ToastLevel, theme: &Theme) -> (&'static str, Color) {
+    match level {
+        ToastLevel::Info => (" Info ", theme.palette.info),
+        ToastLevel::Success => (" Done ", theme.palette.success),
+        ToastLevel::Warning => (" Warning ", theme.palette.warning),
+        ToastLevel::Error => (" Error ", theme.palette.error),
+    }
+}
+
+/// Обрезает текст справа, чтобы он поместился в строку уведомления
+fn truncate(text: &str, width: usize, ellipsis: &str) -> String {
+    match text.chars().count() > width {
+        true => {
+            let kept = width.saturating_sub(ellipsis.chars().count());
+            format!("{}{}", text.chars().take(kept).collect::<String>(), ellipsis)
+        }
+        false => text.to_string(),
+    }
+}
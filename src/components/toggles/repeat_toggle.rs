@@ -1,27 +1,34 @@
 use ratatui::buffer::Buffer;
+use ratatui::crossterm::event::KeyCode;
 use ratatui::layout::Rect;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
-use ratatui::widgets::Block;
-use ratatui::widgets::BorderType;
 use ratatui::widgets::Paragraph;
 use ratatui::widgets::Widget;
 
 use crate::app::AppState;
 use crate::interaction::InteractionState;
 use crate::interaction::InteractiveWidget;
+use crate::theme::Theme;
 
-const REPEAT_TOGGLE_LABEL: &str = "⮎⮌";
-
-pub fn repeat_toggle(app_state: &AppState) -> InteractiveWidget {
+pub fn repeat_toggle(app_state: &AppState, theme: &Theme) -> InteractiveWidget {
     InteractiveWidget::default()
+        .focusable()
+        .help_section("Playback controls")
         .on_mouse_down({
             let app_state = app_state.clone();
             move |_, _| on_click(&app_state)
         })
+        .describe("Toggle repeat")
+        .on_key_down(KeyCode::Enter, {
+            let app_state = app_state.clone();
+            move |_, _| on_click(&app_state)
+        })
+        .describe("Toggle repeat")
         .draw({
             let app_state = app_state.clone();
-            move |widget_state, area, buf| draw_repeat_toggle(widget_state, &app_state, area, buf)
+            let theme = *theme;
+            move |widget_state, area, buf| draw_repeat_toggle(widget_state, &app_state, &theme, area, buf)
         })
 }
 
@@ -29,20 +36,21 @@ fn on_click(app_state: &AppState) {
     app_state.set_repeat_state(!app_state.repeat_state());
 }
 
-fn draw_repeat_toggle(widget_state: InteractionState, app_state: &AppState, area: Rect, buf: &mut Buffer) {
-    let view = match widget_state {
-        InteractionState::Hovered => Paragraph::new(Line::from(REPEAT_TOGGLE_LABEL).centered().bold())
-            .block(Block::bordered().border_type(BorderType::Thick)),
-        InteractionState::Pressed => {
-            Paragraph::new(Line::from(REPEAT_TOGGLE_LABEL).centered().bold()).block(Block::bordered())
-        }
-        _ => match app_state.repeat_state() {
-            true => {
-                Paragraph::new(Line::from(REPEAT_TOGGLE_LABEL).centered().bold()).block(Block::bordered())
-            }
-            false => Paragraph::new(Line::from(REPEAT_TOGGLE_LABEL).centered()).block(Block::bordered()),
-        },
+fn draw_repeat_toggle(
+    widget_state: InteractionState,
+    app_state: &AppState,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let mut label = Line::from(theme.glyphs.repeat).centered();
+    let strong = match widget_state {
+        InteractionState::Hovered | InteractionState::Pressed => true,
+        _ => app_state.repeat_state(),
     };
+    if strong {
+        label = label.bold();
+    }
 
-    view.render(area, buf);
+    Paragraph::new(label).block(theme.block(&widget_state)).render(area, buf);
 }
@@ -1,28 +1,33 @@
 use ratatui::buffer::Buffer;
+use ratatui::crossterm::event::KeyCode;
 use ratatui::layout::Rect;
 use ratatui::text::Line;
-use ratatui::widgets::Block;
-use ratatui::widgets::BorderType;
 use ratatui::widgets::Paragraph;
 use ratatui::widgets::Widget;
 
 use crate::app::AppState;
 use crate::interaction::InteractionState;
 use crate::interaction::InteractiveWidget;
+use crate::theme::Theme;
 
-const SHUFFLE_TOGGLE_LABEL: &str = "△▽";
-const SHUFFLE_TOGGLE_LABEL_STRONG: &str = "▲▼";
-const SHUFFLE_TOGGLE_LABEL_PRESSED: &str = "▴▾";
-
-pub fn shuffle_toggle(app_state: &AppState) -> InteractiveWidget {
+pub fn shuffle_toggle(app_state: &AppState, theme: &Theme) -> InteractiveWidget {
     InteractiveWidget::default()
+        .focusable()
+        .help_section("Playback controls")
         .on_mouse_down({
             let app_state = app_state.clone();
             move |_, _| on_click(&app_state)
         })
+        .describe("Toggle shuffle")
+        .on_key_down(KeyCode::Enter, {
+            let app_state = app_state.clone();
+            move |_, _| on_click(&app_state)
+        })
+        .describe("Toggle shuffle")
         .draw({
             let app_state = app_state.clone();
-            move |widget_state, area, buf| draw_shuffle_toggle(widget_state, &app_state, area, buf)
+            let theme = *theme;
+            move |widget_state, area, buf| draw_shuffle_toggle(widget_state, &app_state, &theme, area, buf)
         })
 }
 
@@ -30,20 +35,19 @@ fn on_click(app_state: &AppState) {
     app_state.set_shuffle_state(!app_state.shuffle_state());
 }
 
-fn draw_shuffle_toggle(widget_state: InteractionState, app_state: &AppState, area: Rect, buf: &mut Buffer) {
-    let view = match widget_state {
-        InteractionState::Hovered => Paragraph::new(Line::from(SHUFFLE_TOGGLE_LABEL_STRONG).centered())
-            .block(Block::bordered().border_type(BorderType::Thick)),
-        InteractionState::Pressed => {
-            Paragraph::new(Line::from(SHUFFLE_TOGGLE_LABEL_PRESSED).centered()).block(Block::bordered())
-        }
-        _ => match app_state.shuffle_state() {
-            true => {
-                Paragraph::new(Line::from(SHUFFLE_TOGGLE_LABEL_STRONG).centered()).block(Block::bordered())
-            }
-            false => Paragraph::new(Line::from(SHUFFLE_TOGGLE_LABEL).centered()).block(Block::bordered()),
-        },
+fn draw_shuffle_toggle(
+    widget_state: InteractionState,
+    app_state: &AppState,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let glyphs = theme.glyphs.shuffle;
+    // Включенное перемешивание показывается той же надписью, что и наведение курсора
+    let label = match widget_state {
+        InteractionState::Focused | InteractionState::Default if app_state.shuffle_state() => glyphs.hovered,
+        _ => glyphs.label(&widget_state),
     };
 
-    view.render(area, buf);
+    Paragraph::new(Line::from(label).centered()).block(theme.block(&widget_state)).render(area, buf);
 }
@@ -0,0 +1,407 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::KeyCode,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Stylize},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{
+        canvas::{Canvas, Line as CanvasLine},
+        Paragraph, Widget,
+    },
+};
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+
+use crate::{
+    app::{AppState, PlayerState},
+    audio_tap::AudioTap,
+    interaction::{InteractionState, InteractiveWidget},
+    player::Player,
+    theme::Theme,
+};
+
+/// Период перерисовки во время воспроизведения (~ 30 fps)
+const FRAME_TIME: Duration = Duration::from_millis(33);
+
+/// Выборок в окне преобразования Фурье
+const FFT_SIZE: usize = 2048;
+/// Полосы спектра от нижней до верхней частоты в герцах, по логарифмической шкале
+const SPECTRUM_RANGE: (f32, f32) = (30.0, 16_000.0);
+/// Уровень в децибелах, который соответствует пустому столбцу или индикатору
+const FLOOR_DB: f32 = -60.0;
+/// Скорость спада столбцов спектра: доля высоты в секунду
+const BAR_FALL_SPEED: f32 = 1.5;
+
+/// Выборок на экране осциллографа
+const SCOPE_SAMPLES: usize = 1024;
+
+/// Окно, по которому считается уровень индикатора
+const METER_WINDOW: Duration = Duration::from_millis(50);
+/// Сколько держится отметка пика, прежде чем начать спадать
+const PEAK_HOLD: Duration = Duration::from_secs(1);
+/// Скорость спада отметки пика в децибелах в секунду
+const PEAK_FALL_SPEED: f32 = 20.0;
+
+/// Точка осциллографа, когда точки Брайля недоступны
+const SCOPE_POINT: char = '*';
+
+/// Режим визуализации
+#[derive(Clone, Copy, Default, PartialEq)]
+enum Mode {
+    #[default]
+    Spectrum,
+    Oscilloscope,
+    Meter,
+}
+
+impl Mode {
+    fn next(self) -> Self {
+        match self {
+            Self::Spectrum => Self::Oscilloscope,
+            Self::Oscilloscope => Self::Meter,
+            Self::Meter => Self::Spectrum,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Spectrum => " Spectrum ",
+            Self::Oscilloscope => " Oscilloscope ",
+            Self::Meter => " Level ",
+        }
+    }
+}
+
+/// Состояние визуализации между кадрами
+///
+/// # Fields
+///
+/// * `mode` - Текущий режим
+/// * `fft` - План преобразования Фурье на [`FFT_SIZE`] выборок
+/// * `bars` - Высота столбцов спектра на прошлом кадре, от 0 до 1
+/// * `peak` - Отметка пика индикатора в децибелах
+/// * `peak_time` - Когда пик был достигнут
+/// * `last_frame` - Время прошлого кадра, по нему спадают столбцы и пик
+struct VisualizerView {
+    mode: Mode,
+    fft: Arc<dyn Fft<f32>>,
+    bars: Vec<f32>,
+    peak: f32,
+    peak_time: Instant,
+    last_frame: Instant,
+}
+
+impl Default for VisualizerView {
+    fn default() -> Self {
+        Self {
+            mode: Mode::default(),
+            fft: FftPlanner::new().plan_fft_forward(FFT_SIZE),
+            bars: vec![],
+            peak: FLOOR_DB,
+            peak_time: Instant::now(),
+            last_frame: Instant::now(),
+        }
+    }
+}
+
+/// Создает панель визуализации звука: спектр, осциллограф или индикатор уровня
+///
+/// Выборки берутся из отвода проигрывателя, то есть ровно те, что уходят на звуковой вывод.
+/// Режим переключается щелчком или клавишей `v`.
+pub fn visualizer(app_state: &AppState, player: &Player, theme: &Theme) -> InteractiveWidget {
+    let view = Arc::new(Mutex::new(VisualizerView::default()));
+
+    let widget = InteractiveWidget::default()
+        .focusable()
+        .help_section("Visualizer")
+        .on_mouse_down({
+            let view = view.clone();
+            move |_, _| switch_mode(&view)
+        })
+        .describe("Switch mode")
+        .on_key_down(KeyCode::Char('v'), {
+            let view = view.clone();
+            move |_, _| switch_mode(&view)
+        })
+        .describe("Switch mode")
+        .draw({
+            let app_state = app_state.clone();
+            let tap = player.tap().clone();
+            let theme = *theme;
+            move |widget_state, area, buf| {
+                draw_visualizer(widget_state, &app_state, &tap, &view, &theme, area, buf)
+            }
+        });
+
+    // Интерфейс перерисовывается только по запросу, а визуализации нужен поток кадров
+    thread::spawn({
+        let app_state = app_state.clone();
+        let widget = widget.clone();
+        move || {
+            while !app_state.should_exit() {
+                thread::sleep(FRAME_TIME);
+                if widget.is_visible() && app_state.player_state() == PlayerState::Playing {
+                    app_state.request_redraw();
+                }
+            }
+        }
+    });
+
+    widget
+}
+
+fn switch_mode(view: &Mutex<VisualizerView>) {
+    let mut view = view.lock().unwrap();
+    view.mode = view.mode.next();
+}
+
+fn draw_visualizer(
+    widget_state: InteractionState,
+    app_state: &AppState,
+    tap: &AudioTap,
+    view: &Mutex<VisualizerView>,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let mut view = view.lock().unwrap();
+
+    let block = theme.panel(&widget_state).title(view.mode.title());
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    // На паузе в буфере остаются последние выборки, но звука уже нет
+    let playing = app_state.player_state() == PlayerState::Playing;
+    let elapsed = view.last_frame.elapsed().as_secs_f32();
+    view.last_frame = Instant::now();
+
+    match view.mode {
+        Mode::Spectrum => {
+            let samples = match playing {
+                true => tap.latest(FFT_SIZE),
+                false => vec![],
+            };
+            draw_spectrum(&mut view, &samples, tap.sample_rate(), elapsed, theme, inner, buf);
+        }
+        Mode::Oscilloscope => {
+            let samples = match playing {
+                true => tap.latest(SCOPE_SAMPLES * 2),
+                false => vec![],
+            };
+            draw_oscilloscope(&samples, theme, inner, buf);
+        }
+        Mode::Meter => {
+            let window = (tap.sample_rate() as f32 * METER_WINDOW.as_secs_f32()) as usize;
+            let samples = match playing {
+                true => tap.latest(window),
+                false => vec![],
+            };
+            draw_meter(&mut view, &samples, elapsed, theme, inner, buf);
+        }
+    }
+}
+
+/// Столбцы спектра, по одному на колонку, с полосами частот по логарифмической шкале
+fn draw_spectrum(
+    view: &mut VisualizerView,
+    samples: &[f32],
+    sample_rate: u32,
+    elapsed: f32,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let levels = match samples.len() == FFT_SIZE && sample_rate > 0 {
+        true => band_levels(view.fft.as_ref(), samples, sample_rate, area.width as usize),
+        false => vec![0.0; area.width as usize],
+    };
+
+    view.bars.resize(levels.len(), 0.0);
+    for (bar, level) in view.bars.iter_mut().zip(levels) {
+        *bar = level.max(*bar - BAR_FALL_SPEED * elapsed).max(0.0);
+    }
+
+    for (column, bar) in view.bars.iter().enumerate() {
+        let eighths = (bar * area.height as f32 * 8.0).round() as u16;
+        for row in 0..area.height {
+            let fill = eighths.saturating_sub(row * 8).min(8);
+            if fill == 0 {
+                break;
+            }
+            let color = level_color(row as f32 / area.height as f32, theme);
+            let position = (area.x + column as u16, area.bottom() - 1 - row);
+            buf[position].set_char(theme.glyphs.levels[fill as usize - 1]).set_fg(color);
+        }
+    }
+}
+
+/// Уровни полос спектра от 0 до 1
+fn band_levels(fft: &dyn Fft<f32>, samples: &[f32], sample_rate: u32, bands: usize) -> Vec<f32> {
+    // Окно Ханна убирает размытие спектра от обрезанных краев
+    let window =
+        |index: usize| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * index as f32 / (FFT_SIZE - 1) as f32).cos();
+    let mut spectrum: Vec<Complex<f32>> =
+        samples.iter().enumerate().map(|(index, sample)| Complex::new(sample * window(index), 0.0)).collect();
+    fft.process(&mut spectrum);
+
+    // Синусоида полной громкости дает амплитуду в половину суммы окна
+    let full_scale = FFT_SIZE as f32 / 4.0;
+    let bin_width = sample_rate as f32 / FFT_SIZE as f32;
+    let (low, high) = (SPECTRUM_RANGE.0, SPECTRUM_RANGE.1.min(sample_rate as f32 / 2.0));
+    let frequency = |band: usize| low * (high / low).powf(band as f32 / bands as f32);
+
+    (0..bands)
+        .map(|band| {
+            let first = (frequency(band) / bin_width) as usize;
+            let last = ((frequency(band + 1) / bin_width) as usize).max(first + 1).min(FFT_SIZE / 2);
+            let magnitude =
+                spectrum[first.min(last - 1)..last].iter().map(|bin| bin.norm()).fold(0.0, f32::max);
+            level_ratio(to_db(magnitude / full_scale))
+        })
+        .collect()
+}
+
+/// Осциллограмма, выровненная по переходу сигнала через ноль вверх, чтобы не дрожать
+fn draw_oscilloscope(samples: &[f32], theme: &Theme, area: Rect, buf: &mut Buffer) {
+    let start = samples
+        .windows(2)
+        .take(samples.len().saturating_sub(SCOPE_SAMPLES))
+        .position(|pair| pair[0] <= 0.0 && pair[1] > 0.0)
+        .unwrap_or(0);
+    let samples = &samples[start..samples.len().min(start + SCOPE_SAMPLES)];
+
+    // Брайль дает две точки на колонку, больше выборок на экране не различить
+    let points_per_column = if theme.glyphs.braille { 2 } else { 1 };
+    let points = (area.width as usize * points_per_column).max(1);
+    let step = (samples.len() as f64 / points as f64).max(1.0);
+    let coords: Vec<(f64, f64)> = (0..points)
+        .map_while(|point| {
+            let index = (point as f64 * step) as usize;
+            samples.get(index).map(|sample| (point as f64, *sample as f64))
+        })
+        .collect();
+
+    if !theme.glyphs.braille {
+        draw_scope_points(&coords, theme, area, buf);
+        return;
+    }
+
+    Canvas::default()
+        .marker(Marker::Braille)
+        .x_bounds([0.0, (points - 1) as f64])
+        .y_bounds([-1.0, 1.0])
+        .paint(|context| match coords.len() {
+            0 | 1 => context.draw(&CanvasLine::new(0.0, 0.0, (points - 1) as f64, 0.0, theme.palette.muted)),
+            _ => {
+                for pair in coords.windows(2) {
+                    let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+                    context.draw(&CanvasLine::new(x1, y1, x2, y2, theme.palette.info));
+                }
+            }
+        })
+        .render(area, buf);
+}
+
+/// Осциллограмма из отдельных точек, по одной на колонку
+fn draw_scope_points(coords: &[(f64, f64)], theme: &Theme, area: Rect, buf: &mut Buffer) {
+    if area.is_empty() {
+        return;
+    }
+    let row = |value: f64| ((1.0 - value.clamp(-1.0, 1.0)) / 2.0 * (area.height - 1) as f64).round() as u16;
+    for column in 0..area.width {
+        let (value, color) = match coords.get(column as usize) {
+            Some((_, value)) => (*value, theme.palette.info),
+            None => (0.0, theme.palette.muted),
+        };
+        buf[(area.x + column, area.y + row(value))].set_char(SCOPE_POINT).set_fg(color);
+    }
+}
+
+/// Индикатор среднеквадратичного уровня и пика с удерживаемой отметкой
+fn draw_meter(
+    view: &mut VisualizerView,
+    samples: &[f32],
+    elapsed: f32,
+    theme: &Theme,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let rms =
+        (samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len().max(1) as f32).sqrt();
+    let rms = to_db(rms);
+    let peak = to_db(samples.iter().map(|sample| sample.abs()).fold(0.0, f32::max));
+
+    if peak >= view.peak {
+        view.peak = peak;
+        view.peak_time = Instant::now();
+    } else if view.peak_time.elapsed() > PEAK_HOLD {
+        view.peak = (view.peak - PEAK_FALL_SPEED * elapsed).max(peak);
+    }
+
+    const LABEL_WIDTH: u16 = 5;
+    const VALUE_WIDTH: u16 = 10;
+    let [rms_area, peak_area] = Layout::vertical([Constraint::Length(1), Constraint::Length(1)])
+        .flex(Flex::Center)
+        .spacing(1)
+        .areas(area);
+
+    for (label, level, hold, line_area) in [("RMS", rms, false, rms_area), ("Peak", peak, true, peak_area)] {
+        let [label_area, bar_area, value_area] = Layout::horizontal([
+            Constraint::Length(LABEL_WIDTH),
+            Constraint::Fill(1),
+            Constraint::Length(VALUE_WIDTH),
+        ])
+        .areas(line_area);
+
+        Paragraph::new(label).render(label_area, buf);
+        let value = match level > FLOOR_DB {
+            true => format!("{:.1} dB", level),
+            false => "-inf dB".to_string(),
+        };
+        Paragraph::new(Line::from(value).right_aligned()).render(value_area, buf);
+
+        let filled = (level_ratio(level) * bar_area.width as f32).round() as u16;
+        let held = (level_ratio(view.peak) * bar_area.width as f32).round() as u16;
+        let bar: Vec<Span> = (0..bar_area.width)
+            .map(|column| {
+                let color = level_color(column as f32 / bar_area.width as f32, theme);
+                let glyphs = &theme.glyphs;
+                match column {
+                    column if column < filled => Span::from(glyphs.meter_fill.to_string()).fg(color),
+                    column if hold && held > 0 && column == held - 1 => {
+                        Span::from(glyphs.peak_hold.to_string()).fg(color)
+                    }
+                    _ => Span::from(glyphs.meter_empty.to_string()).fg(theme.palette.muted),
+                }
+            })
+            .collect();
+        Paragraph::new(Line::from(bar)).render(bar_area, buf);
+    }
+}
+
+fn to_db(amplitude: f32) -> f32 {
+    match amplitude > 0.0 {
+        true => 20.0 * amplitude.log10(),
+        false => f32::NEG_INFINITY,
+    }
+}
+
+/// Доля шкалы, которую занимает уровень в децибелах
+fn level_ratio(db: f32) -> f32 {
+    ((db - FLOOR_DB) / -FLOOR_DB).clamp(0.0, 1.0)
+}
+
+/// Цвет участка шкалы: нормальный до -18 дБ, предупреждающий до -6 дБ, дальше цвет ошибки
+fn level_color(ratio: f32, theme: &Theme) -> Color {
+    match ratio * -FLOOR_DB + FLOOR_DB {
+        db if db < -18.0 => theme.palette.success,
+        db if db < -6.0 => theme.palette.warning,
+        _ => theme.palette.error,
+    }
+}
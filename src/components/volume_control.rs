@@ -1,80 +1,104 @@
 use ratatui::{
     buffer::Buffer,
+    crossterm::event::KeyCode,
     layout::{Position, Rect},
     style::Stylize,
     text::Line,
-    widgets::{Block, BorderType, Padding, Paragraph, Widget},
+    widgets::{Padding, Paragraph, Widget},
 };
 
 use crate::{
     app::AppState,
     interaction::{InteractionState, InteractiveWidget},
-    player::Player,
+    theme::Theme,
 };
 
-const VOLUME_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
 const VOLUME_SCROLL_STEP: f32 = 0.03;
 
 const PADDING: Padding = Padding::new(1, 1, 0, 0);
 const BORDER_WIDTH: u16 = 1;
 
-pub fn volume_control(app_state: &AppState, player: &Player) -> InteractiveWidget {
+pub fn volume_control(app_state: &AppState, theme: &Theme) -> InteractiveWidget {
     InteractiveWidget::default()
+        .focusable()
+        .help_section("Playback controls")
         .on_mouse_down({
             let app_state = app_state.clone();
-            let player = player.clone();
-
-            move |widget, mouse_position| on_click(widget, mouse_position, &app_state, &player)
+            move |widget, mouse_position| on_click(widget, mouse_position, &app_state)
         })
+        .describe("Set volume")
         .on_mouse_drag({
             let app_state = app_state.clone();
-            let player = player.clone();
-
-            move |widget, mouse_position| on_click(widget, mouse_position, &app_state, &player)
+            move |widget, mouse_position| on_click(widget, mouse_position, &app_state)
         })
+        .describe("Set volume")
         .on_mouse_scroll_up({
             let app_state = app_state.clone();
-            let player = player.clone();
-
-            move |_, _| increase_volume(&app_state, &player)
+            move |_, _| increase_volume(&app_state)
         })
+        .describe("Volume up")
         .on_mouse_scroll_down({
             let app_state = app_state.clone();
-            let player = player.clone();
-
-            move |_, _| decrease_volume(&app_state, &player)
+            move |_, _| decrease_volume(&app_state)
         })
+        .describe("Volume down")
+        .on_key_down(KeyCode::Up, {
+            let app_state = app_state.clone();
+            move |_, _| increase_volume(&app_state)
+        })
+        .describe("Volume up")
+        .on_key_down(KeyCode::Right, {
+            let app_state = app_state.clone();
+            move |_, _| increase_volume(&app_state)
+        })
+        .describe("Volume up")
+        .on_key_down(KeyCode::Down, {
+            let app_state = app_state.clone();
+            move |_, _| decrease_volume(&app_state)
+        })
+        .describe("Volume down")
+        .on_key_down(KeyCode::Left, {
+            let app_state = app_state.clone();
+            move |_, _| decrease_volume(&app_state)
+        })
+        .describe("Volume down")
         .draw({
             let app_state = app_state.clone();
-            move |interaction_state, area, buf| draw_volume_control(interaction_state, &app_state, area, buf)
+            let theme = *theme;
+            move |interaction_state, area, buf| {
+                draw_volume_control(interaction_state, &app_state, &theme, area, buf)
+            }
         })
 }
 
 fn draw_volume_control(
     interaction_state: InteractionState,
     app_state: &AppState,
+    theme: &Theme,
     area: Rect,
     buf: &mut Buffer,
 ) {
-    let mut frame = Block::bordered().padding(PADDING);
+    let mut frame = theme.block(&interaction_state).padding(PADDING);
 
     let mut frame_label = Line::from(format!(" Vol {}% ", (app_state.volume() * 100.0) as u8));
 
     if interaction_state == InteractionState::Hovered {
-        frame = frame.border_type(BorderType::Thick);
         frame_label = frame_label.bold();
     }
 
     frame = frame.title(frame_label);
 
-    let volume_gauge = get_volume_string(app_state.volume());
+    let volume_gauge = get_volume_string(app_state.volume(), &theme.glyphs.levels);
     let view = Paragraph::new(volume_gauge).block(frame);
     view.render(area, buf);
 }
 
-fn on_click(widget: &mut InteractiveWidget, mouse_position: Position, app_state: &AppState, player: &Player) {
+fn on_click(widget: &mut InteractiveWidget, mouse_position: Position, app_state: &AppState) {
     // Вычисляем ширину активной области слайдера
-    let clickable_width = widget.area().width - PADDING.left - PADDING.right - BORDER_WIDTH;
+    let clickable_width = widget.area().width.saturating_sub(PADDING.left + PADDING.right + BORDER_WIDTH);
+    if clickable_width == 0 {
+        return;
+    }
 
     // Вычисляем позицию клика относительно начала слайдера
     let click_position = mouse_position.x as i16 - PADDING.left as i16;
@@ -85,26 +109,23 @@ fn on_click(widget: &mut InteractiveWidget, mouse_position: Position, app_state:
     let volume = normalized_position / clickable_width as f32;
 
     app_state.set_volume(volume);
-    player.set_volume(volume);
 }
 
-fn increase_volume(app_state: &AppState, player: &Player) {
+fn increase_volume(app_state: &AppState) {
     let volume = (app_state.volume() + VOLUME_SCROLL_STEP).min(1.0);
 
     app_state.set_volume(volume);
-    player.set_volume(volume);
 }
 
-fn decrease_volume(app_state: &AppState, player: &Player) {
+fn decrease_volume(app_state: &AppState) {
     let volume = (app_state.volume() - VOLUME_SCROLL_STEP).max(0.0);
 
     app_state.set_volume(volume);
-    player.set_volume(volume);
 }
 
-fn get_volume_string(volume_ratio: f32) -> String {
+fn get_volume_string(volume_ratio: f32, volume_blocks: &[char]) -> String {
     let volume_ratio = volume_ratio.clamp(0.0, 1.0);
-    let blocks_count = (volume_ratio * VOLUME_BLOCKS.len() as f32).ceil() as usize;
+    let blocks_count = (volume_ratio * volume_blocks.len() as f32).ceil() as usize;
 
-    VOLUME_BLOCKS.iter().take(blocks_count).collect::<String>()
+    volume_blocks.iter().take(blocks_count).collect::<String>()
 }
@@ -0,0 +1,226 @@
+use std::{fs, path::PathBuf};
+
+use serde::Deserialize;
+
+use crate::{
+    album_art::GraphicsProtocol,
+    theme::{BorderStyle, GlyphSetName, PaletteName},
+};
+
+/// Имя каталога приложения в каталогах конфигурации и данных
+const APP_DIR_NAME: &str = "tmp";
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Колонка таблицы плейлиста; она же ключ сортировки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaylistColumn {
+    Number,
+    Title,
+    Artist,
+    Album,
+    AlbumArtist,
+    Year,
+    Genre,
+    Disc,
+    Track,
+    Duration,
+    Bitrate,
+    PlayCount,
+}
+
+/// Настройки плейлиста
+///
+/// # Fields
+///
+/// * `columns` - Колонки таблицы в порядке отображения
+/// * `sort_preset` - Ключи многоуровневой сортировки, от старшего к младшему
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PlaylistConfig {
+    pub columns: Vec<PlaylistColumn>,
+    pub sort_preset: Vec<PlaylistColumn>,
+}
+
+impl Default for PlaylistConfig {
+    fn default() -> Self {
+        Self {
+            columns: vec![
+                PlaylistColumn::Number,
+                PlaylistColumn::Title,
+                PlaylistColumn::Artist,
+                PlaylistColumn::Album,
+                PlaylistColumn::Year,
+                PlaylistColumn::Duration,
+            ],
+            sort_preset: vec![
+                PlaylistColumn::AlbumArtist,
+                PlaylistColumn::Year,
+                PlaylistColumn::Disc,
+                PlaylistColumn::Track,
+            ],
+        }
+    }
+}
+
+/// Настройки медиатеки
+///
+/// # Fields
+///
+/// * `roots` - Каталоги с музыкой, с которых начинается обзор файлов
+/// * `organise_template` - Шаблон пути, по которому раскладываются файлы (см. [`crate::organise`])
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LibraryConfig {
+    pub roots: Vec<PathBuf>,
+    pub organise_template: String,
+}
+
+impl Default for LibraryConfig {
+    fn default() -> Self {
+        Self {
+            roots: dirs::audio_dir().or_else(dirs::home_dir).into_iter().collect(),
+            organise_template: "{albumartist}/{year} - {album}/{disc}{track:02} {title}.{ext}".to_string(),
+        }
+    }
+}
+
+impl LibraryConfig {
+    /// Каталоги медиатеки с раскрытым `~` в начале пути
+    pub fn root_dirs(&self) -> Vec<PathBuf> {
+        self.roots
+            .iter()
+            .map(|root| match (root.strip_prefix("~"), dirs::home_dir()) {
+                (Ok(rest), Some(home)) => home.join(rest),
+                _ => root.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Настройки панели текущего трека
+///
+/// # Fields
+///
+/// * `art_protocol` - Протокол вывода обложки; если не задан, определяется по терминалу
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NowPlayingConfig {
+    pub art_protocol: Option<GraphicsProtocol>,
+}
+
+/// Как рисуется волновая полоса прогресса
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WaveformStyle {
+    /// Столбцы из блоков `▁`..`█`, восемь ступеней на строку
+    #[default]
+    Blocks,
+    /// Точки Брайля: вдвое больше столбцов, но только четыре ступени на строку
+    Braille,
+}
+
+/// Настройки полосы прогресса
+///
+/// # Fields
+///
+/// * `waveform` - Показывать огибающую трека вместо линии; переключается клавишей `w`
+/// * `waveform_style` - Символы, которыми рисуется огибающая
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ProgressBarConfig {
+    pub waveform: bool,
+    pub waveform_style: WaveformStyle,
+}
+
+/// Раскладка интерфейса
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutPreset {
+    /// Выбирается по размеру терминала
+    #[default]
+    Auto,
+    /// Все панели; те, которым не хватает места, скрываются
+    Full,
+    /// Только вкладки и плейлист, полоса прогресса и кнопки
+    Compact,
+    /// Одна-две строки без рамок, например для панели статуса tmux
+    Mini,
+}
+
+/// Настройки раскладки
+///
+/// # Fields
+///
+/// * `preset` - Раскладка интерфейса
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LayoutConfig {
+    pub preset: LayoutPreset,
+}
+
+/// Цвета, заменяющие цвета встроенной палитры (см. [`crate::theme::Palette`]): имя цвета
+/// (`red`, `lightblue`), номер из 256 цветов или `#rrggbb`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeColors {
+    pub text: Option<String>,
+    pub muted: Option<String>,
+    pub accent: Option<String>,
+    pub accent_text: Option<String>,
+    pub primary: Option<String>,
+    pub success: Option<String>,
+    pub warning: Option<String>,
+    pub error: Option<String>,
+    pub info: Option<String>,
+}
+
+/// Настройки оформления
+///
+/// # Fields
+///
+/// * `palette` - Встроенная палитра
+/// * `glyphs` - Набор символов
+/// * `borders` - Рамки виджетов; если не заданы, выбираются по набору символов
+/// * `colors` - Собственные цвета поверх палитры
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub palette: PaletteName,
+    pub glyphs: GlyphSetName,
+    pub borders: Option<BorderStyle>,
+    pub colors: ThemeColors,
+}
+
+/// Настройки приложения из `config.toml` в каталоге конфигурации пользователя
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub playlist: PlaylistConfig,
+    pub library: LibraryConfig,
+    pub now_playing: NowPlayingConfig,
+    pub progress_bar: ProgressBarConfig,
+    pub layout: LayoutConfig,
+    pub theme: ThemeConfig,
+}
+
+impl Config {
+    /// Загружает настройки. Отсутствующий или поврежденный файл дает настройки по умолчанию.
+    pub fn load() -> Self {
+        let Some(path) = config_dir().map(|dir| dir.join(CONFIG_FILE_NAME)) else { return Self::default() };
+
+        fs::read_to_string(path).ok().and_then(|content| toml::from_str(&content).ok()).unwrap_or_default()
+    }
+}
+
+/// Каталог конфигурации приложения
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_DIR_NAME))
+}
+
+/// Каталог данных приложения; создается при первом обращении
+pub fn data_dir() -> Option<PathBuf> {
+    let dir = dirs::data_dir()?.join(APP_DIR_NAME);
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir)
+}
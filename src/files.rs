@@ -0,0 +1,79 @@
+use std::{fs::metadata, path::Path};
+
+use crate::playlist_store::read_playlist;
+
+const TRACK_FORMATS: [&str; 1] = ["mp3"];
+const PLAYLIST_FORMATS: [&str; 2] = ["m3u", "m3u8"];
+
+/// Плейлист из пути, переданного в командной строке
+///
+/// # Returns:
+/// Название (имя файла или каталога) и треки, если путь передан
+pub fn get_initial_playlist() -> Option<(String, Vec<String>)> {
+    let path = std::env::args().nth(1)?;
+    let path = Path::new(&path);
+
+    let name = path.file_name().map_or_else(|| path.to_string_lossy(), |name| name.to_string_lossy());
+    Some((name.to_string(), walk_path(path)))
+}
+
+/// Треки по пути: сам трек, треки файла плейлиста или все треки каталога вместе с подкаталогами
+pub fn walk_path(path: &Path) -> Vec<String> {
+    let mut playlist = vec![];
+
+    let path = Path::new(path);
+    if let Ok(metadata) = metadata(path) {
+        if metadata.is_dir() {
+            playlist.extend(scan_directory(path));
+        } else if metadata.is_file() && is_track_file(path) {
+            playlist.extend(path.to_str().map(str::to_string));
+        } else if metadata.is_file() && is_playlist_file(path) {
+            playlist.extend(read_playlist(path).map(|(_, tracks)| tracks).unwrap_or_default());
+        }
+    }
+    playlist
+}
+
+/// Треки каталога и его подкаталогов в порядке имен. Символические ссылки на каталоги
+/// не обходятся, чтобы не зациклиться, а файлы с именами не в UTF-8 пропускаются: пути треков
+/// хранятся строками.
+fn scan_directory(path: &Path) -> Vec<String> {
+    let mut playlist = vec![];
+
+    if let Ok(entries) = std::fs::read_dir(path) {
+        let mut entries: Vec<_> = entries.flatten().collect();
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let path = entry.path();
+            let path = path.as_path();
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                playlist.extend(scan_directory(path));
+            } else if is_track_file(path) {
+                playlist.extend(path.to_str().map(str::to_string));
+            }
+        }
+    }
+    playlist
+}
+
+/// Поддерживаемый аудиофайл
+pub fn is_track_file(path: &Path) -> bool {
+    has_extension(path, &TRACK_FORMATS)
+}
+
+/// Файл плейлиста M3U
+pub fn is_playlist_file(path: &Path) -> bool {
+    has_extension(path, &PLAYLIST_FORMATS)
+}
+
+fn has_extension(path: &Path, formats: &[&str]) -> bool {
+    if let Some(extension) = path.extension() {
+        if let Some(extension) = extension.to_str() {
+            if formats.contains(&extension.to_lowercase().as_str()) {
+                return true;
+            }
+        }
+    }
+    false
+}
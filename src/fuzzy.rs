@@ -0,0 +1,180 @@
+/// Очки за каждый совпавший символ
+const MATCH_SCORE: i64 = 16;
+/// Бонус за совпадение, идущее сразу за предыдущим
+const CONSECUTIVE_BONUS: i64 = 12;
+/// Бонус за совпадение в начале слова
+const WORD_START_BONUS: i64 = 10;
+/// Штраф за каждый пропущенный символ между совпадениями
+const GAP_PENALTY: i64 = 1;
+
+/// Результат нечеткого сопоставления
+///
+/// # Fields
+///
+/// * `score` - Оценка совпадения, чем больше, тем лучше
+/// * `positions` - Индексы (в символах) совпавших символов строки
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    pub positions: Vec<usize>,
+}
+
+/// Нечетко сопоставляет шаблон со строкой без учета регистра
+///
+/// Символы шаблона должны встречаться в строке в том же порядке, но не обязательно подряд.
+/// Из всех вариантов выбирается тот, где совпадения плотнее и чаще приходятся на начала слов.
+///
+/// # Returns:
+/// `None`, если строка не содержит символы шаблона в нужном порядке
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    let original: Vec<char> = text.chars().collect();
+    // Строчная форма символа бывает из нескольких символов (İ), поэтому для каждого символа
+    // сложенной строки запоминается индекс исходного
+    let (text, origins): (Vec<char>, Vec<usize>) = original
+        .iter()
+        .enumerate()
+        .flat_map(|(index, c)| c.to_lowercase().map(move |lower| (lower, index)))
+        .unzip();
+    // Начало слова - только первый символ строчной формы
+    let word_start =
+        |j: usize| (j == 0 || origins[j - 1] != origins[j]) && is_word_start(&original, origins[j]);
+
+    if pattern.is_empty() {
+        return Some(FuzzyMatch { score: 0, positions: vec![] });
+    }
+    if pattern.len() > text.len() {
+        return None;
+    }
+
+    let (m, n) = (pattern.len(), text.len());
+
+    // scores[i][j] - лучшая оценка, если i-й символ шаблона совпал с j-м символом строки
+    let mut scores = vec![vec![None::<i64>; n]; m];
+    // previous[i][j] - позиция совпадения (i-1)-го символа шаблона для лучшей оценки
+    let mut previous = vec![vec![0usize; n]; m];
+
+    for i in 0..m {
+        // Лучшее значение scores[i-1][k] + k * GAP_PENALTY среди k < j - 1
+        let mut best_gap: Option<(i64, usize)> = None;
+
+        for j in i..n {
+            if i > 0 && j >= 2 {
+                if let Some(score) = scores[i - 1][j - 2] {
+                    let candidate = score + (j - 2) as i64 * GAP_PENALTY;
+                    if best_gap.is_none_or(|(best, _)| candidate > best) {
+                        best_gap = Some((candidate, j - 2));
+                    }
+                }
+            }
+
+            if text[j] != pattern[i] {
+                continue;
+            }
+
+            let bonus = MATCH_SCORE + if word_start(j) { WORD_START_BONUS } else { 0 };
+
+            if i == 0 {
+                scores[i][j] = Some(bonus - j as i64 * GAP_PENALTY / 2);
+                continue;
+            }
+
+            let consecutive =
+                if j >= 1 { scores[i - 1][j - 1].map(|s| (s + CONSECUTIVE_BONUS, j - 1)) } else { None };
+            let gapped = best_gap.map(|(s, k)| (s - (j - 1) as i64 * GAP_PENALTY, k));
+
+            let best = match (consecutive, gapped) {
+                (Some(a), Some(b)) => Some(if a.0 >= b.0 { a } else { b }),
+                (a, b) => a.or(b),
+            };
+
+            if let Some((score, k)) = best {
+                scores[i][j] = Some(score + bonus);
+                previous[i][j] = k;
+            }
+        }
+    }
+
+    let (mut position, score) = scores[m - 1]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|score| (j, score)))
+        .max_by_key(|&(j, score)| (score, std::cmp::Reverse(j)))?;
+
+    let mut positions = vec![position; m];
+    for i in (1..m).rev() {
+        position = previous[i][position];
+        positions[i - 1] = position;
+    }
+    let mut positions: Vec<usize> = positions.into_iter().map(|j| origins[j]).collect();
+    positions.dedup();
+
+    Some(FuzzyMatch { score, positions })
+}
+
+fn is_word_start(text: &[char], index: usize) -> bool {
+    if index == 0 {
+        return true;
+    }
+
+    let (previous, current) = (text[index - 1], text[index]);
+    !previous.is_alphanumeric() || (previous.is_lowercase() && current.is_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(pattern: &str, text: &str) -> Option<Vec<usize>> {
+        fuzzy_match(pattern, text).map(|found| found.positions)
+    }
+
+    fn score(pattern: &str, text: &str) -> i64 {
+        fuzzy_match(pattern, text).unwrap().score
+    }
+
+    #[test]
+    fn empty_pattern_matches_everything() {
+        assert_eq!(fuzzy_match("", "anything"), Some(FuzzyMatch { score: 0, positions: vec![] }));
+        assert_eq!(fuzzy_match("", ""), Some(FuzzyMatch { score: 0, positions: vec![] }));
+    }
+
+    #[test]
+    fn requires_characters_in_order() {
+        assert_eq!(positions("abc", "a_b_c"), Some(vec![0, 2, 4]));
+        assert_eq!(positions("cba", "a_b_c"), None);
+        assert_eq!(positions("abcd", "abc"), None);
+        assert_eq!(positions("x", "abc"), None);
+    }
+
+    #[test]
+    fn ignores_case() {
+        assert_eq!(positions("BEAT", "the beatles"), Some(vec![4, 5, 6, 7]));
+        assert_eq!(positions("ёж", "ЁЖИК"), Some(vec![0, 1]));
+    }
+
+    #[test]
+    fn folds_multi_character_lowercase_forms() {
+        // 'İ' в нижнем регистре - два символа: 'i' и U+0307
+        assert_eq!(positions("İst", "İstanbul"), Some(vec![0, 1, 2]));
+        assert_eq!(positions("İST", "istİst"), Some(vec![3, 4, 5]));
+        assert_eq!(positions("is", "İstanbul"), Some(vec![0, 1]));
+        assert_eq!(positions("bul", "İİİbul"), Some(vec![3, 4, 5]));
+    }
+
+    #[test]
+    fn positions_count_characters_not_bytes() {
+        assert_eq!(positions("ja", "Äa ja"), Some(vec![3, 4]));
+    }
+
+    #[test]
+    fn prefers_consecutive_and_word_start_matches() {
+        // «abc» целиком лучше, чем буквы вразброс в начале строки
+        assert_eq!(positions("abc", "a_b_c abc"), Some(vec![6, 7, 8]));
+        // Начала слов лучше середины слова
+        assert_eq!(positions("dt", "dust dark tower"), Some(vec![5, 10]));
+        assert_eq!(positions("mF", "someFile"), Some(vec![2, 4]));
+        assert!(score("lo", "Lonely") > score("lo", "hello"));
+        assert!(score("ab", "ab") > score("ab", "a b"));
+    }
+}
@@ -5,37 +5,82 @@ use std::{
 };
 
 use ratatui::{
-    crossterm::event::{self, Event, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind},
+    crossterm::event::{
+        self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
     layout::Position,
 };
 
 use crate::app::AppState;
 
-use super::{event_type::MouseEventType, InteractiveWidget};
+use super::{
+    event_type::MouseEventType,
+    help::{Gesture, HelpEntry, HelpSection},
+    InteractiveWidget,
+};
+
+/// Раздел справки с клавишами, которые работают при любом фокусе
+const GLOBAL_HELP_SECTION: &str = "Anywhere";
 
 /// Обработчик событий для всего приложения
+///
+/// Клавиши сначала получает виджет в фокусе, затем Tab/Shift-Tab переводят фокус,
+/// и в последнюю очередь срабатывают глобальные привязки виджетов. Своих привязок у обработчика
+/// нет: даже выход по Esc - глобальная привязка приложения, поэтому модальное окно в фокусе
+/// может закрыться по Esc, не закрыв приложение. Виджеты, зарегистрированные позже,
+/// лежат поверх ранних: событие мыши получает только верхний виджет под курсором.
+///
+/// Поверх всех виджетов лежит стопка модальных окон. Пока она не пуста, весь ввод достается
+/// только верхнему окну, а фокус остальных виджетов не меняется и возвращается к ним
+/// сам, когда окна закрываются.
 #[derive(Clone)]
 pub struct EventHandler {
     app_state: AppState,
     components: Arc<Mutex<Vec<InteractiveWidget>>>,
+    focused_component: Arc<Mutex<Option<usize>>>,
+    overlays: Arc<Mutex<Vec<Overlay>>>,
+}
+
+/// Модальное окно в стопке
+///
+/// # Fields
+///
+/// * `widget` - Виджет окна
+/// * `size` - Размер окна (ширина, высота); приложение выводит окно по центру экрана
+#[derive(Clone)]
+struct Overlay {
+    widget: InteractiveWidget,
+    size: (u16, u16),
 }
 
 pub trait Handelable {
     /// Обработка событий мыши
-    /// 
+    ///
     /// # Args:
     /// * `event_type` - Тип события мыши
     /// * `relative_position` - Позиция события мыши (относительно виджета)
     fn handle_mouse_event(&mut self, event_type: MouseEventType, relative_position: Position);
 
-    /// Обработка событий клавиатуры
-    /// 
+    /// Обработка событий клавиатуры виджетом в фокусе
+    ///
     /// # Args:
-    /// * `key_code` - Код клавиши
-    fn handle_key_event(&mut self, key_code: KeyCode);
+    /// * `key_event` - Клавиша и модификаторы
+    ///
+    /// # Returns:
+    /// `true`, если клавиша была обработана
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> bool;
+
+    /// Обработка событий клавиатуры, не обработанных виджетом в фокусе
+    ///
+    /// # Args:
+    /// * `key_event` - Клавиша и модификаторы
+    ///
+    /// # Returns:
+    /// `true`, если клавиша была обработана
+    fn handle_global_key_event(&mut self, key_event: KeyEvent) -> bool;
 
     /// Обработка событий вставки
-    /// 
+    ///
     /// # Args:
     /// * `paste_event` - Событие вставки
     fn handle_paste_event(&mut self, paste_event: String);
@@ -43,14 +88,77 @@ pub trait Handelable {
 
 impl EventHandler {
     pub fn new(app_state: &AppState) -> Self {
-        Self { app_state: app_state.clone(), components: Arc::new(Mutex::new(Vec::new())) }
+        Self {
+            app_state: app_state.clone(),
+            components: Arc::new(Mutex::new(Vec::new())),
+            focused_component: Arc::new(Mutex::new(None)),
+            overlays: Arc::new(Mutex::new(Vec::new())),
+        }
     }
 
+    /// Регистрирует виджет. Первый виджет, способный принять фокус, получает его сразу.
     pub fn register_component(&mut self, component: InteractiveWidget) -> InteractiveWidget {
-        self.components.lock().unwrap().push(component.clone());
+        let index = {
+            let mut components = self.components.lock().unwrap();
+            components.push(component.clone());
+            components.len() - 1
+        };
+
+        if component.is_focusable() && self.focused_component.lock().unwrap().is_none() {
+            self.set_focus(Some(index));
+        }
+
         component
     }
 
+    /// Передает фокус зарегистрированному виджету
+    pub fn focus(&self, component: &InteractiveWidget) {
+        let components = self.components.lock().unwrap().clone();
+        let index = components.iter().position(|registered| registered.ptr_eq(component));
+        if index.is_some() {
+            self.set_focus(index);
+        }
+    }
+
+    /// Кладет модальное окно на верх стопки; уже открытое окно остается на своем месте
+    pub fn open_overlay(&self, widget: &InteractiveWidget, size: (u16, u16)) {
+        let mut overlays = self.overlays.lock().unwrap();
+        if overlays.iter().any(|overlay| overlay.widget.ptr_eq(widget)) {
+            return;
+        }
+
+        if let Some(top) = overlays.last_mut() {
+            top.widget.set_focused(false);
+        }
+        let mut widget = widget.clone();
+        widget.set_focused(true);
+        overlays.push(Overlay { widget, size });
+    }
+
+    /// Убирает модальное окно из стопки и с экрана; ввод переходит к окну под ним
+    pub fn close_overlay(&self, widget: &InteractiveWidget) {
+        let mut overlays = self.overlays.lock().unwrap();
+        let Some(index) = overlays.iter().position(|overlay| overlay.widget.ptr_eq(widget)) else { return };
+
+        let mut overlay = overlays.remove(index);
+        overlay.widget.set_focused(false);
+        overlay.widget.hide();
+        if let Some(top) = overlays.last_mut() {
+            top.widget.set_focused(true);
+        }
+    }
+
+    /// Модальные окна с их размерами, снизу вверх
+    pub fn overlays(&self) -> Vec<(InteractiveWidget, (u16, u16))> {
+        let overlays = self.overlays.lock().unwrap();
+        overlays.iter().map(|overlay| (overlay.widget.clone(), overlay.size)).collect()
+    }
+
+    /// Верхнее модальное окно, которому достается весь ввод
+    fn top_overlay(&self) -> Option<InteractiveWidget> {
+        self.overlays.lock().unwrap().last().map(|overlay| overlay.widget.clone())
+    }
+
     pub fn start(&mut self) -> Result<(), Error> {
         let event_handler = self.clone();
 
@@ -60,8 +168,13 @@ impl EventHandler {
                     Event::Key(key_event) => event_handler.handle_key_event(key_event),
                     Event::Mouse(mouse_event) => event_handler.handle_mouse_event(mouse_event),
                     Event::Paste(paste_event) => event_handler.handle_paste_event(paste_event),
-                    _ => {}
+                    Event::FocusGained => event_handler.app_state.set_terminal_focused(true),
+                    Event::FocusLost => event_handler.app_state.set_terminal_focused(false),
+                    Event::Resize(_, _) => {}
                 };
+
+                // Любой ввод (в том числе изменение размера терминала) может изменить вид интерфейса
+                event_handler.app_state.request_redraw();
             }
 
             Ok(())
@@ -70,54 +183,187 @@ impl EventHandler {
     }
 
     fn handle_key_event(&self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Esc => {
-                self.app_state.set_exit(true);
+        if key_event.kind == KeyEventKind::Release {
+            return;
+        }
+
+        if let Some(mut overlay) = self.top_overlay() {
+            overlay.handle_key_event(key_event);
+            return;
+        }
+
+        // Виджет в фокусе может сам обрабатывать Tab, например, для перехода между полями формы
+        if let Some(mut component) = self.focused() {
+            if component.handle_key_event(key_event) {
+                return;
             }
+        }
+
+        match key_event.code {
+            KeyCode::Tab => self.focus_next(),
+            KeyCode::BackTab => self.focus_previous(),
             _ => {
-                for component in self.components.lock().unwrap().iter_mut() {
-                    component.handle_key_event(key_event.code);
-                }
+                self.dispatch_global_key_event(key_event);
             }
         }
     }
- 
+
+    /// Передает клавишу, не обработанную виджетом в фокусе, глобальным привязкам виджетов
+    fn dispatch_global_key_event(&self, key_event: KeyEvent) -> bool {
+        let components = self.components.lock().unwrap().clone();
+        components.into_iter().any(|mut component| component.handle_global_key_event(key_event))
+    }
+
     fn handle_mouse_event(&self, mouse_event: MouseEvent) {
         let mouse_position = Position::new(mouse_event.column, mouse_event.row);
 
-        for component in self.components.lock().unwrap().iter_mut() {
-            let area = component.area();
-            if !area.contains(mouse_position) {
+        // Пока открыто модальное окно, виджеты под ним мышь не получают, даже вне окна
+        let mut covered = false;
+        if let Some(mut overlay) = self.top_overlay() {
+            covered = true;
+            match overlay.area().contains(mouse_position) {
+                true => dispatch_mouse_event(&mut overlay, mouse_event.kind, mouse_position),
+                false => overlay.handle_mouse_event(MouseEventType::Out, mouse_position),
+            }
+        }
+
+        let components = self.components.lock().unwrap().clone();
+        for (index, mut component) in components.into_iter().enumerate().rev() {
+            if covered || !component.area().contains(mouse_position) {
                 component.handle_mouse_event(MouseEventType::Out, mouse_position);
                 continue;
             }
 
-            let relative_mouse_position = Position::new(mouse_position.x - area.x, mouse_position.y - area.y);
-
-            match mouse_event.kind {
-                MouseEventKind::Down(MouseButton::Left) => {
-                    component.handle_mouse_event(MouseEventType::Down, relative_mouse_position)
-                }
-                MouseEventKind::Drag(MouseButton::Left) => {
-                    component.handle_mouse_event(MouseEventType::Drag, relative_mouse_position)
-                }
-                MouseEventKind::ScrollDown => {
-                    component.handle_mouse_event(MouseEventType::ScrollDown, relative_mouse_position)
-                }
-                MouseEventKind::ScrollUp => {
-                    component.handle_mouse_event(MouseEventType::ScrollUp, relative_mouse_position)
-                }
-                MouseEventKind::Up(_) | MouseEventKind::Moved => {
-                    component.handle_mouse_event(MouseEventType::Over, relative_mouse_position)
-                }
-                _ => {}
+            covered = true;
+            if mouse_event.kind == MouseEventKind::Down(MouseButton::Left) && component.is_focusable() {
+                self.set_focus(Some(index));
             }
+            dispatch_mouse_event(&mut component, mouse_event.kind, mouse_position);
         }
     }
 
     fn handle_paste_event(&self, paste_event: String) {
-        for component in self.components.lock().unwrap().iter_mut() {
+        if let Some(mut overlay) = self.top_overlay() {
+            overlay.handle_paste_event(paste_event);
+            return;
+        }
+
+        let components = self.components.lock().unwrap().clone();
+        for mut component in components {
             component.handle_paste_event(paste_event.clone());
         }
     }
+
+    /// Справка по всем описанным привязкам зарегистрированных виджетов
+    ///
+    /// Первым идет раздел с глобальными клавишами и переводом фокуса, затем разделы виджетов
+    /// в порядке регистрации; разделы с одинаковым названием объединяются.
+    pub fn help(&self) -> Vec<HelpSection> {
+        let mut global = HelpSection {
+            title: GLOBAL_HELP_SECTION,
+            entries: vec![
+                HelpEntry {
+                    action: "Focus next panel",
+                    gesture: Gesture::Key(KeyCode::Tab, KeyModifiers::NONE),
+                },
+                HelpEntry {
+                    action: "Focus previous panel",
+                    gesture: Gesture::Key(KeyCode::BackTab, KeyModifiers::NONE),
+                },
+            ],
+        };
+        let mut sections: Vec<HelpSection> = vec![];
+
+        let components = self.components.lock().unwrap().clone();
+        for section in components.iter().filter_map(InteractiveWidget::help) {
+            let (global_entries, entries): (Vec<_>, Vec<_>) =
+                section.entries.into_iter().partition(|entry| entry.gesture.is_global());
+            global.entries.extend(global_entries);
+
+            match sections.iter_mut().find(|existing| existing.title == section.title) {
+                Some(existing) => existing.entries.extend(entries),
+                None => sections.push(HelpSection { title: section.title, entries }),
+            }
+        }
+
+        sections.retain(|section| !section.entries.is_empty());
+        sections.insert(0, global);
+        sections
+    }
+
+    /// Виджет, находящийся в фокусе
+    pub fn focused(&self) -> Option<InteractiveWidget> {
+        let index = (*self.focused_component.lock().unwrap())?;
+        self.components.lock().unwrap().get(index).cloned()
+    }
+
+    fn focus_next(&self) {
+        self.move_focus(1);
+    }
+
+    fn focus_previous(&self) {
+        self.move_focus(-1);
+    }
+
+    /// Переводит фокус на следующий (или предыдущий) видимый виджет, способный его принять
+    fn move_focus(&self, step: isize) {
+        let focusable: Vec<usize> = self
+            .components
+            .lock()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .filter(|(_, component)| component.is_focusable() && component.is_visible())
+            .map(|(index, _)| index)
+            .collect();
+
+        if focusable.is_empty() {
+            return;
+        }
+
+        let current = *self.focused_component.lock().unwrap();
+        let next = match current.and_then(|index| focusable.iter().position(|&i| i == index)) {
+            Some(position) => {
+                let count = focusable.len() as isize;
+                focusable[(position as isize + step).rem_euclid(count) as usize]
+            }
+            // Виджет в фокусе скрыт: фокус переходит к ближайшему видимому в том же направлении
+            None if step > 0 => {
+                let after = current.and_then(|index| focusable.iter().find(|&&i| i > index));
+                after.copied().unwrap_or(focusable[0])
+            }
+            None => {
+                let before = current.and_then(|index| focusable.iter().rfind(|&&i| i < index));
+                before.copied().unwrap_or(focusable[focusable.len() - 1])
+            }
+        };
+
+        self.set_focus(Some(next));
+    }
+
+    fn set_focus(&self, index: Option<usize>) {
+        let mut components = self.components.lock().unwrap().clone();
+        *self.focused_component.lock().unwrap() = index;
+
+        for (i, component) in components.iter_mut().enumerate() {
+            component.set_focused(Some(i) == index);
+        }
+    }
+}
+
+/// Передает событие мыши виджету под курсором с позицией относительно виджета
+fn dispatch_mouse_event(widget: &mut InteractiveWidget, kind: MouseEventKind, mouse_position: Position) {
+    let area = widget.area();
+    let relative_mouse_position = Position::new(mouse_position.x - area.x, mouse_position.y - area.y);
+
+    let event_type = match kind {
+        MouseEventKind::Down(MouseButton::Left) => MouseEventType::Down,
+        MouseEventKind::Drag(MouseButton::Left) => MouseEventType::Drag,
+        MouseEventKind::ScrollDown => MouseEventType::ScrollDown,
+        MouseEventKind::ScrollUp => MouseEventType::ScrollUp,
+        MouseEventKind::Moved => MouseEventType::Move,
+        MouseEventKind::Up(_) => MouseEventType::Over,
+        _ => return,
+    };
+    widget.handle_mouse_event(event_type, relative_mouse_position);
 }
@@ -2,6 +2,8 @@
 #[derive(Debug)]
 pub enum MouseEventType {
     Over,
+    /// Курсор сдвинулся над виджетом без нажатых кнопок
+    Move,
     Out,
     Down,
     Drag,
@@ -0,0 +1,93 @@
+use ratatui::crossterm::event::{KeyCode, KeyModifiers};
+
+/// Чем вызывается действие виджета
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    /// Клавиша, которая срабатывает, когда виджет в фокусе
+    Key(KeyCode, KeyModifiers),
+    /// Клавиша, которая срабатывает, где бы ни был фокус
+    GlobalKey(KeyCode, KeyModifiers),
+    Click,
+    Drag,
+    Hover,
+    ScrollUp,
+    ScrollDown,
+    Paste,
+}
+
+impl Gesture {
+    pub fn is_global(&self) -> bool {
+        matches!(self, Gesture::GlobalKey(..))
+    }
+
+    pub fn is_mouse(&self) -> bool {
+        !matches!(self, Gesture::Key(..) | Gesture::GlobalKey(..))
+    }
+
+    /// Название для справки: клавиша в угловых скобках, как в подсказках, или жест мыши
+    pub fn label(&self) -> String {
+        match self {
+            Gesture::Key(key_code, modifiers) | Gesture::GlobalKey(key_code, modifiers) => {
+                format!("<{}>", key_label(*key_code, *modifiers))
+            }
+            Gesture::Click => "Click".to_string(),
+            Gesture::Drag => "Drag".to_string(),
+            Gesture::Hover => "Hover".to_string(),
+            Gesture::ScrollUp => "Wheel up".to_string(),
+            Gesture::ScrollDown => "Wheel down".to_string(),
+            Gesture::Paste => "Paste".to_string(),
+        }
+    }
+}
+
+/// Действие виджета и то, чем оно вызывается
+///
+/// # Fields
+///
+/// * `action` - Что делает действие
+/// * `gesture` - Клавиша или жест мыши
+#[derive(Debug, Clone)]
+pub struct HelpEntry {
+    pub action: &'static str,
+    pub gesture: Gesture,
+}
+
+/// Раздел справки: действия одной области интерфейса
+///
+/// # Fields
+///
+/// * `title` - Название области
+/// * `entries` - Действия в порядке объявления привязок
+#[derive(Debug, Clone)]
+pub struct HelpSection {
+    pub title: &'static str,
+    pub entries: Vec<HelpEntry>,
+}
+
+fn key_label(key_code: KeyCode, modifiers: KeyModifiers) -> String {
+    let key = match key_code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        // С модификаторами буква пишется заглавной, как в подсказках: <Ctrl-Z>
+        KeyCode::Char(c) if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            c.to_uppercase().to_string()
+        }
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(number) => format!("F{}", number),
+        KeyCode::BackTab => "Shift-Tab".to_string(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        KeyCode::Delete => "Del".to_string(),
+        KeyCode::Insert => "Ins".to_string(),
+        key_code => format!("{:?}", key_code),
+    };
+
+    let mut label = String::new();
+    for (modifier, name) in
+        [(KeyModifiers::CONTROL, "Ctrl-"), (KeyModifiers::ALT, "Alt-"), (KeyModifiers::SHIFT, "Shift-")]
+    {
+        if modifiers.contains(modifier) {
+            label.push_str(name);
+        }
+    }
+    label + &key
+}
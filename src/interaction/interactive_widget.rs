@@ -1,9 +1,19 @@
 use ratatui::{
-    buffer::Buffer, crossterm::event::KeyCode, layout::{Position, Rect}, widgets::Widget
+    buffer::Buffer,
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    layout::{Position, Rect},
+    widgets::Widget,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
 };
-use std::{collections::HashMap, sync::{Arc, Mutex}};
 
-use super::{event_handler::Handelable, event_type::MouseEventType};
+use super::{
+    event_handler::Handelable,
+    event_type::MouseEventType,
+    help::{Gesture, HelpEntry, HelpSection},
+};
 
 /// Состояние интерактивного виджета
 #[derive(Default, Eq, PartialEq, Hash, Clone)]
@@ -12,6 +22,7 @@ pub enum InteractionState {
     Default,
     Hovered,
     Pressed,
+    Focused,
 }
 
 /// Тип обработчика событий мыши
@@ -26,18 +37,35 @@ type DrawHandler = dyn Fn(InteractionState, Rect, &mut Buffer) + Send + Sync;
 /// Тип функции обработки события вставки
 type PasteHandler = dyn Fn(&mut InteractiveWidget, String) + Send + Sync;
 
+/// Тип перехватчика ввода с клавиатуры, возвращает `true`, если клавиша обработана
+type KeyInputHandler = dyn Fn(&mut InteractiveWidget, KeyEvent) -> bool + Send + Sync;
+
+/// Таблица обработчиков клавиш с модификаторами
+type KeyBindings = Arc<Mutex<HashMap<(KeyCode, KeyModifiers), Arc<KeyboardHandler>>>>;
+
 /// Интерактивный виджет с поддержкой событий мыши
+///
+/// Привязки можно подписать для справки: `describe` после привязки описывает её действие,
+/// `help_section` задает раздел справки, в котором виджет их покажет.
 #[derive(Default, Clone)]
 pub struct InteractiveWidget {
     area: Arc<Mutex<Rect>>,
     state: Arc<Mutex<InteractionState>>,
+    focusable: bool,
+    focused: Arc<Mutex<bool>>,
     on_mouse_down_fn: Option<Arc<MouseHandler>>,
     on_mouse_drag_fn: Option<Arc<MouseHandler>>,
+    on_mouse_move_fn: Option<Arc<MouseHandler>>,
     on_mouse_scroll_up_fn: Option<Arc<MouseHandler>>,
     on_mouse_scroll_down_fn: Option<Arc<MouseHandler>>,
     on_paste_fn: Option<Arc<PasteHandler>>,
-    on_key_down_fns: Arc<Mutex<HashMap<KeyCode, Box<KeyboardHandler>>>>,
-    draw_fn: Option<Arc<DrawHandler>>
+    on_key_input_fn: Option<Arc<KeyInputHandler>>,
+    on_key_down_fns: KeyBindings,
+    on_global_key_down_fns: KeyBindings,
+    draw_fn: Option<Arc<DrawHandler>>,
+    help_title: Option<&'static str>,
+    help_entries: Arc<Mutex<Vec<HelpEntry>>>,
+    last_gesture: Option<Gesture>,
 }
 
 impl InteractiveWidget {
@@ -47,6 +75,7 @@ impl InteractiveWidget {
         F: Fn(&mut InteractiveWidget, Position) + Send + Sync + 'static,
     {
         self.on_mouse_down_fn = Some(Arc::new(handler));
+        self.last_gesture = Some(Gesture::Click);
         self
     }
 
@@ -55,6 +84,17 @@ impl InteractiveWidget {
         F: Fn(&mut InteractiveWidget, Position) + Send + Sync + 'static,
     {
         self.on_mouse_drag_fn = Some(Arc::new(handler));
+        self.last_gesture = Some(Gesture::Drag);
+        self
+    }
+
+    /// Обработчик движения курсора над виджетом без нажатых кнопок
+    pub fn on_mouse_move<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&mut InteractiveWidget, Position) + Send + Sync + 'static,
+    {
+        self.on_mouse_move_fn = Some(Arc::new(handler));
+        self.last_gesture = Some(Gesture::Hover);
         self
     }
 
@@ -63,6 +103,7 @@ impl InteractiveWidget {
         F: Fn(&mut InteractiveWidget, Position) + Send + Sync + 'static,
     {
         self.on_mouse_scroll_up_fn = Some(Arc::new(handler));
+        self.last_gesture = Some(Gesture::ScrollUp);
         self
     }
 
@@ -71,6 +112,7 @@ impl InteractiveWidget {
         F: Fn(&mut InteractiveWidget, Position) + Send + Sync + 'static,
     {
         self.on_mouse_scroll_down_fn = Some(Arc::new(handler));
+        self.last_gesture = Some(Gesture::ScrollDown);
         self
     }
 
@@ -79,16 +121,91 @@ impl InteractiveWidget {
         F: Fn(&mut InteractiveWidget, String) + Send + Sync + 'static,
     {
         self.on_paste_fn = Some(Arc::new(handler));
+        self.last_gesture = Some(Gesture::Paste);
         self
     }
 
+    /// Перехватчик ввода: получает клавиши виджета в фокусе раньше его привязок.
+    /// Нужен для ввода текста, когда любая клавиша должна попасть в поле ввода.
+    pub fn on_key_input<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&mut InteractiveWidget, KeyEvent) -> bool + Send + Sync + 'static,
+    {
+        self.on_key_input_fn = Some(Arc::new(handler));
+        self.last_gesture = None;
+        self
+    }
+
+    /// Обработчик клавиши, срабатывающий только когда виджет в фокусе
     pub fn on_key_down<F>(self, key: KeyCode, handler: F) -> Self
+    where
+        F: Fn(&mut InteractiveWidget, KeyCode) + Send + Sync + 'static,
+    {
+        self.on_key_down_with_modifiers(key, KeyModifiers::NONE, handler)
+    }
+
+    /// Обработчик сочетания клавиши с модификаторами (Ctrl, Alt, Shift), срабатывающий только
+    /// когда виджет в фокусе. Для символьных клавиш Shift не учитывается: он уже отражен в символе.
+    pub fn on_key_down_with_modifiers<F>(mut self, key: KeyCode, modifiers: KeyModifiers, handler: F) -> Self
     where
         F: Fn(&mut InteractiveWidget, KeyCode) + Send + Sync + 'static,
     {
         if let Ok(mut on_key_down_fns) = self.on_key_down_fns.lock() {
-            on_key_down_fns.insert(key, Box::new(handler));
+            on_key_down_fns.insert(binding_key(key, modifiers), Arc::new(handler));
         }
+        self.last_gesture = Some(Gesture::Key(key, modifiers));
+        self
+    }
+
+    /// Глобальный обработчик клавиши, срабатывающий, если виджет в фокусе её не обработал
+    pub fn on_global_key_down<F>(self, key: KeyCode, handler: F) -> Self
+    where
+        F: Fn(&mut InteractiveWidget, KeyCode) + Send + Sync + 'static,
+    {
+        self.on_global_key_down_with_modifiers(key, KeyModifiers::NONE, handler)
+    }
+
+    /// Глобальный обработчик сочетания клавиши с модификаторами
+    pub fn on_global_key_down_with_modifiers<F>(
+        mut self,
+        key: KeyCode,
+        modifiers: KeyModifiers,
+        handler: F,
+    ) -> Self
+    where
+        F: Fn(&mut InteractiveWidget, KeyCode) + Send + Sync + 'static,
+    {
+        if let Ok(mut on_global_key_down_fns) = self.on_global_key_down_fns.lock() {
+            on_global_key_down_fns.insert(binding_key(key, modifiers), Arc::new(handler));
+        }
+        self.last_gesture = Some(Gesture::GlobalKey(key, modifiers));
+        self
+    }
+
+    /// Раздел справки, в котором показываются описанные привязки виджета. Разделы
+    /// с одинаковым названием у разных виджетов объединяются.
+    pub fn help_section(mut self, title: &'static str) -> Self {
+        self.help_title = Some(title);
+        self
+    }
+
+    /// Описывает для справки действие привязки, объявленной последней
+    pub fn describe(mut self, action: &'static str) -> Self {
+        if let Some(gesture) = self.last_gesture.take() {
+            self.help_entries.lock().unwrap().push(HelpEntry { action, gesture });
+        }
+        self
+    }
+
+    /// Описывает для справки клавишу, которую виджет обрабатывает сам в `on_key_input`
+    pub fn help_key(self, key: KeyCode, modifiers: KeyModifiers, action: &'static str) -> Self {
+        self.help_entries.lock().unwrap().push(HelpEntry { action, gesture: Gesture::Key(key, modifiers) });
+        self
+    }
+
+    /// Разрешает виджету получать фокус клавиатуры
+    pub fn focusable(mut self) -> Self {
+        self.focusable = true;
         self
     }
 
@@ -111,6 +228,27 @@ impl InteractiveWidget {
         }
     }
 
+    /// Убирает виджет с экрана до следующей отрисовки: без области он не получает
+    /// событий мыши и пропускается при переводе фокуса
+    pub fn hide(&mut self) {
+        self.set_area(Rect::default());
+    }
+
+    pub fn is_visible(&self) -> bool {
+        !self.area().is_empty()
+    }
+
+    /// Описанные привязки виджета, если у него задан раздел справки
+    pub fn help(&self) -> Option<HelpSection> {
+        let title = self.help_title?;
+        Some(HelpSection { title, entries: self.help_entries.lock().unwrap().clone() })
+    }
+
+    /// Являются ли виджеты копиями одного и того же виджета
+    pub fn ptr_eq(&self, other: &InteractiveWidget) -> bool {
+        Arc::ptr_eq(&self.area, &other.area)
+    }
+
     pub fn state(&self) -> InteractionState {
         self.state.lock().unwrap().clone()
     }
@@ -122,6 +260,31 @@ impl InteractiveWidget {
             }
         }
     }
+
+    pub fn is_focusable(&self) -> bool {
+        self.focusable
+    }
+
+    pub fn is_focused(&self) -> bool {
+        *self.focused.lock().unwrap()
+    }
+
+    pub fn set_focused(&mut self, value: bool) {
+        if let Ok(mut focused) = self.focused.lock() {
+            *focused = value;
+        }
+    }
+
+    /// Состояние для отрисовки: нажатие важнее фокуса, а фокус - наведения, чтобы курсор
+    /// над виджетом в фокусе не прятал его выделение
+    fn visible_state(&self) -> InteractionState {
+        match self.state() {
+            InteractionState::Default | InteractionState::Hovered if self.is_focused() => {
+                InteractionState::Focused
+            }
+            state => state,
+        }
+    }
 }
 
 impl Widget for &InteractiveWidget {
@@ -129,16 +292,36 @@ impl Widget for &InteractiveWidget {
         self.clone().set_area(area);
 
         if let Some(draw_fn) = &self.draw_fn.clone() {
-            draw_fn(self.state(), area, buf);
+            draw_fn(self.visible_state(), area, buf);
         }
     }
 }
 
+/// Ключ таблицы привязок: Shift у символьных клавиш отбрасывается, так как терминалы
+/// сообщают о нем непоследовательно
+fn binding_key(key_code: KeyCode, modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    match key_code {
+        KeyCode::Char(_) => (key_code, modifiers - KeyModifiers::SHIFT),
+        _ => (key_code, modifiers),
+    }
+}
+
+/// Достает обработчик клавиши, не удерживая блокировку таблицы во время его вызова
+fn key_handler(bindings: &KeyBindings, key_event: KeyEvent) -> Option<Arc<KeyboardHandler>> {
+    bindings.lock().ok()?.get(&binding_key(key_event.code, key_event.modifiers)).cloned()
+}
+
 impl Handelable for InteractiveWidget {
     // Обработчики событий
     fn handle_mouse_event(&mut self, event_type: MouseEventType, position: Position) {
         match event_type {
             MouseEventType::Over => self.set_state(InteractionState::Hovered),
+            MouseEventType::Move => {
+                self.set_state(InteractionState::Hovered);
+                if let Some(handler) = &self.on_mouse_move_fn.clone() {
+                    handler(self, position);
+                }
+            }
             MouseEventType::Out => self.set_state(InteractionState::Default),
             MouseEventType::Down => {
                 if let Some(handler) = &self.on_mouse_down_fn.clone() {
@@ -165,11 +348,29 @@ impl Handelable for InteractiveWidget {
         }
     }
 
-    fn handle_key_event(&mut self, key_code: KeyCode) {
-        if let Ok(on_key_down_fns) = self.on_key_down_fns.clone().lock() {
-            if let Some(handler) = on_key_down_fns.get(&key_code).clone() {
-                handler(self, key_code);
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> bool {
+        if let Some(handler) = &self.on_key_input_fn.clone() {
+            if handler(self, key_event) {
+                return true;
+            }
+        }
+
+        match key_handler(&self.on_key_down_fns, key_event) {
+            Some(handler) => {
+                handler(self, key_event.code);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn handle_global_key_event(&mut self, key_event: KeyEvent) -> bool {
+        match key_handler(&self.on_global_key_down_fns, key_event) {
+            Some(handler) => {
+                handler(self, key_event.code);
+                true
             }
+            None => false,
         }
     }
 
@@ -5,4 +5,8 @@ mod interactive_widget;
 pub use interactive_widget::InteractionState;
 pub use interactive_widget::InteractiveWidget;
 
+mod help;
+pub use help::Gesture;
+pub use help::HelpSection;
+
 mod event_type;
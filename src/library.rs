@@ -0,0 +1,394 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf, MAIN_SEPARATOR},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use rusqlite::{params, params_from_iter, Connection, Params};
+
+use crate::{
+    app::{AppState, ToastLevel},
+    config::{data_dir, LibraryConfig},
+    files::walk_path,
+    metadata::{file_stamp, MetadataCache},
+};
+
+const LIBRARY_FILE_NAME: &str = "library.sqlite";
+
+/// Сколько измененных файлов записывать в базу одной транзакцией
+const SCAN_BATCH: usize = 64;
+
+/// Сколько удаленных треков помнить для поиска их нового места
+const DEPARTED_LIMIT: usize = 10_000;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS tracks (
+        path TEXT PRIMARY KEY,
+        mtime INTEGER NOT NULL,
+        size INTEGER NOT NULL,
+        title TEXT,
+        artist TEXT,
+        album TEXT,
+        album_artist TEXT,
+        year INTEGER,
+        genre TEXT,
+        track_number INTEGER,
+        disc_number INTEGER,
+        duration_ms INTEGER,
+        bitrate INTEGER
+    );
+    CREATE INDEX IF NOT EXISTS tracks_artist ON tracks (COALESCE(album_artist, artist, ''));
+    CREATE INDEX IF NOT EXISTS tracks_genre ON tracks (genre);
+    CREATE INDEX IF NOT EXISTS tracks_year ON tracks (year);
+    CREATE TABLE IF NOT EXISTS departed (
+        path TEXT PRIMARY KEY,
+        size INTEGER NOT NULL,
+        title TEXT,
+        artist TEXT,
+        album TEXT,
+        duration_ms INTEGER
+    );
+    CREATE INDEX IF NOT EXISTS tracks_size ON tracks (size);
+";
+
+/// Поле, по которому группируются треки медиатеки
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LibraryField {
+    Artist,
+    Album,
+    Genre,
+    Year,
+}
+
+impl LibraryField {
+    /// SQL-выражение значения поля; отсутствующее значение - пустая строка
+    fn expression(self) -> &'static str {
+        match self {
+            LibraryField::Artist => "COALESCE(album_artist, artist, '')",
+            LibraryField::Album => "COALESCE(album, '')",
+            LibraryField::Genre => "COALESCE(genre, '')",
+            LibraryField::Year => "COALESCE(CAST(year AS TEXT), '')",
+        }
+    }
+}
+
+/// Трек медиатеки для списка треков
+///
+/// # Fields
+///
+/// * `path` - Путь к файлу
+/// * `title` - Название трека
+/// * `track_number` - Номер трека в альбоме
+/// * `duration` - Длительность
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibraryTrack {
+    pub path: String,
+    pub title: Option<String>,
+    pub track_number: Option<u16>,
+    pub duration: Option<Duration>,
+}
+
+/// Индекс медиатеки в базе SQLite в каталоге данных
+///
+/// Хранит теги и параметры потока каждого трека из каталогов медиатеки вместе со временем
+/// изменения и размером файла, по которым повторное сканирование находит измененные файлы.
+/// Размер и теги удаленных треков запоминаются отдельно: по ним трек находится, если файл
+/// был перемещен.
+///
+/// # Fields
+///
+/// * `connection` - Соединение с базой; `None`, если базу открыть не удалось
+/// * `roots` - Каталоги медиатеки
+/// * `version` - Растет при каждом изменении индекса
+/// * `scanning` - Идет ли сканирование
+/// * `metadata_cache` - Кэш тегов, через который читаются файлы
+#[derive(Default, Clone)]
+pub struct Library {
+    connection: Option<Arc<Mutex<Connection>>>,
+    roots: Vec<PathBuf>,
+    version: Arc<AtomicU64>,
+    scanning: Arc<AtomicBool>,
+    metadata_cache: MetadataCache,
+}
+
+impl Library {
+    pub fn open(config: &LibraryConfig, metadata_cache: &MetadataCache) -> Self {
+        let connection = data_dir()
+            .and_then(|dir| Connection::open(dir.join(LIBRARY_FILE_NAME)).ok())
+            .filter(|connection| connection.execute_batch(SCHEMA).is_ok())
+            .map(|connection| Arc::new(Mutex::new(connection)));
+
+        Self {
+            connection,
+            roots: config.root_dirs(),
+            metadata_cache: metadata_cache.clone(),
+            ..Self::default()
+        }
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+
+    pub fn is_scanning(&self) -> bool {
+        self.scanning.load(Ordering::Acquire)
+    }
+
+    /// Сканирует каталоги медиатеки в фоне. Теги перечитываются только у новых файлов
+    /// и файлов, у которых изменились время изменения или размер; исчезнувшие файлы
+    /// удаляются из индекса.
+    pub fn rescan(&self, app_state: &AppState) {
+        if self.connection.is_none() || self.scanning.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let library = self.clone();
+        let app_state = app_state.clone();
+        thread::spawn(move || {
+            let (updated, removed) = library.scan(&app_state);
+            library.scanning.store(false, Ordering::Release);
+
+            if updated + removed > 0 {
+                let message = format!("Library: {} tracks updated, {} removed", updated, removed);
+                app_state.notify(ToastLevel::Info, message);
+            }
+            app_state.request_redraw();
+        });
+    }
+
+    /// # Returns:
+    /// Число обновленных и удаленных треков
+    fn scan(&self, app_state: &AppState) -> (usize, usize) {
+        if self.connection.is_none() {
+            return (0, 0);
+        }
+
+        let indexed: HashMap<String, (i64, i64)> = self
+            .query("SELECT path, mtime, size FROM tracks", [], |row| {
+                Ok((row.get(0)?, (row.get(1)?, row.get(2)?)))
+            })
+            .into_iter()
+            .collect();
+
+        let paths: HashSet<String> = self.roots.iter().flat_map(|root| walk_path(root)).collect();
+
+        let changed: Vec<(String, i64, i64)> = paths
+            .iter()
+            .filter_map(|path| {
+                let (mtime, size) = file_stamp(path)?;
+                (indexed.get(path) != Some(&(mtime, size))).then(|| (path.clone(), mtime, size))
+            })
+            .collect();
+
+        for batch in changed.chunks(SCAN_BATCH) {
+            self.write_tracks(batch);
+            app_state.request_redraw();
+        }
+
+        // Треки из недоступного каталога (например, неподключенного диска) остаются в индексе
+        let unavailable: Vec<&PathBuf> = self.roots.iter().filter(|root| !root.exists()).collect();
+        let missing: Vec<String> = indexed
+            .into_keys()
+            .filter(|path| !paths.contains(path))
+            .filter(|path| !unavailable.iter().any(|root| Path::new(path).starts_with(root)))
+            .collect();
+        self.remove_paths(&missing);
+
+        (changed.len(), missing.len())
+    }
+
+    /// Перечитывает в индекс треки по путям: файлы или каталоги целиком
+    ///
+    /// # Returns:
+    /// Число обновленных треков
+    pub fn update_paths(&self, paths: &[PathBuf]) -> usize {
+        let tracks: Vec<(String, i64, i64)> = paths
+            .iter()
+            .flat_map(|path| walk_path(path))
+            .filter_map(|path| file_stamp(&path).map(|(mtime, size)| (path, mtime, size)))
+            .collect();
+
+        for batch in tracks.chunks(SCAN_BATCH) {
+            self.write_tracks(batch);
+        }
+        tracks.len()
+    }
+
+    fn write_tracks(&self, batch: &[(String, i64, i64)]) {
+        let Some(connection) = &self.connection else { return };
+        let paths: Vec<String> = batch.iter().map(|(path, _, _)| path.clone()).collect();
+        let tracks: Vec<_> = batch
+            .iter()
+            .zip(self.metadata_cache.load_many(&paths))
+            .map(|((path, mtime, size), metadata)| (path, *mtime, *size, metadata))
+            .collect();
+
+        let mut connection = connection.lock().unwrap();
+        let Ok(transaction) = connection.transaction() else { return };
+        for (path, mtime, size, metadata) in &tracks {
+            let _ = transaction.execute(
+                "INSERT OR REPLACE INTO tracks (path, mtime, size, title, artist, album, album_artist,
+                    year, genre, track_number, disc_number, duration_ms, bitrate)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                params![
+                    path,
+                    mtime,
+                    size,
+                    metadata.title,
+                    metadata.artist,
+                    metadata.album,
+                    metadata.album_artist,
+                    metadata.year,
+                    metadata.genre,
+                    metadata.track_number,
+                    metadata.disc_number,
+                    metadata.duration.map(|duration| duration.as_millis() as i64),
+                    metadata.bitrate,
+                ],
+            );
+            let _ = transaction.execute("DELETE FROM departed WHERE path = ?1", [path]);
+        }
+        if transaction.commit().is_ok() {
+            self.version.fetch_add(1, Ordering::AcqRel);
+        }
+    }
+
+    /// Удаляет из индекса треки по путям файлов или каталогов, запоминая их размер и теги
+    ///
+    /// # Returns:
+    /// Число удаленных треков
+    pub fn remove_paths(&self, paths: &[String]) -> usize {
+        let Some(connection) = &self.connection else { return 0 };
+        if paths.is_empty() {
+            return 0;
+        }
+
+        let mut connection = connection.lock().unwrap();
+        let Ok(transaction) = connection.transaction() else { return 0 };
+
+        let mut removed = 0;
+        for path in paths {
+            let condition = "path = ?1 OR substr(path, 1, length(?2)) = ?2";
+            let directory = format!("{}{}", path.trim_end_matches(MAIN_SEPARATOR), MAIN_SEPARATOR);
+            let _ = transaction.execute(
+                &format!(
+                    "INSERT OR REPLACE INTO departed (path, size, title, artist, album, duration_ms)
+                     SELECT path, size, title, artist, album, duration_ms FROM tracks WHERE {}",
+                    condition
+                ),
+                [path, &directory],
+            );
+            removed += transaction
+                .execute(&format!("DELETE FROM tracks WHERE {}", condition), [path, &directory])
+                .unwrap_or(0);
+        }
+        let _ = transaction.execute(
+            "DELETE FROM departed
+             WHERE rowid NOT IN (SELECT rowid FROM departed ORDER BY rowid DESC LIMIT ?1)",
+            [DEPARTED_LIMIT],
+        );
+
+        if transaction.commit().is_ok() && removed > 0 {
+            self.version.fetch_add(1, Ordering::AcqRel);
+        }
+        removed
+    }
+
+    /// Новое место удаленного трека: единственный трек индекса с тем же размером и тегами
+    pub fn relocation(&self, path: &str) -> Option<String> {
+        let candidates = self.query(
+            "SELECT tracks.path FROM departed JOIN tracks
+                ON tracks.size = departed.size
+                AND tracks.title IS departed.title
+                AND tracks.artist IS departed.artist
+                AND tracks.album IS departed.album
+                AND tracks.duration_ms IS departed.duration_ms
+             WHERE departed.path = ?1 AND tracks.path != departed.path
+             LIMIT 2",
+            [path],
+            |row| row.get::<_, String>(0),
+        );
+
+        match candidates.as_slice() {
+            [candidate] => Some(candidate.clone()),
+            _ => None,
+        }
+    }
+
+    /// Различные значения поля среди треков, подходящих под фильтры. Альбомы упорядочены
+    /// по году, остальные значения - по алфавиту.
+    ///
+    /// # Args:
+    /// * `field` - Поле, значения которого нужны
+    /// * `filters` - Поля и значения, которым должны соответствовать треки
+    pub fn values(&self, field: LibraryField, filters: &[(LibraryField, String)]) -> Vec<String> {
+        let order = match field {
+            LibraryField::Album => "MIN(year), value COLLATE NOCASE",
+            _ => "value COLLATE NOCASE",
+        };
+        let sql = format!(
+            "SELECT {} AS value FROM tracks WHERE {} GROUP BY value ORDER BY {}",
+            field.expression(),
+            filter_clause(filters),
+            order
+        );
+
+        self.query(&sql, filter_values(filters), |row| row.get(0))
+    }
+
+    /// Треки, подходящие под фильтры, по исполнителю, году, альбому, диску и номеру
+    pub fn tracks(&self, filters: &[(LibraryField, String)]) -> Vec<LibraryTrack> {
+        let sql = format!(
+            "SELECT path, title, track_number, duration_ms FROM tracks WHERE {}
+             ORDER BY {} COLLATE NOCASE, year, {} COLLATE NOCASE, disc_number, track_number, path",
+            filter_clause(filters),
+            LibraryField::Artist.expression(),
+            LibraryField::Album.expression()
+        );
+
+        self.query(&sql, filter_values(filters), |row| {
+            Ok(LibraryTrack {
+                path: row.get(0)?,
+                title: row.get(1)?,
+                track_number: row.get(2)?,
+                duration: row.get::<_, Option<u64>>(3)?.map(Duration::from_millis),
+            })
+        })
+    }
+
+    fn query<T>(
+        &self,
+        sql: &str,
+        params: impl Params,
+        map: impl FnMut(&rusqlite::Row) -> rusqlite::Result<T>,
+    ) -> Vec<T> {
+        let Some(connection) = &self.connection else { return vec![] };
+        let connection = connection.lock().unwrap();
+        let Ok(mut statement) = connection.prepare(sql) else { return vec![] };
+
+        statement.query_map(params, map).map(|rows| rows.flatten().collect()).unwrap_or_default()
+    }
+}
+
+fn filter_values(filters: &[(LibraryField, String)]) -> impl Params + '_ {
+    params_from_iter(filters.iter().map(|(_, value)| value))
+}
+
+/// Условие `WHERE` с параметрами на каждое значение фильтра
+fn filter_clause(filters: &[(LibraryField, String)]) -> String {
+    if filters.is_empty() {
+        return "1".to_string();
+    }
+
+    filters
+        .iter()
+        .enumerate()
+        .map(|(index, (field, _))| format!("{} = ?{}", field.expression(), index + 1))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
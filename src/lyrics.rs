@@ -0,0 +1,272 @@
+use std::{fs, path::Path, time::Duration};
+
+use id3::{frame::TimestampFormat, Tag};
+
+/// Текст песни
+pub enum Lyrics {
+    /// Строки со временем начала, по возрастанию времени
+    Synced(Vec<(Duration, String)>),
+    /// Строки без времени
+    Plain(Vec<String>),
+}
+
+impl Lyrics {
+    /// Загружает текст песни: файл `.lrc` рядом с треком, а если его нет - синхронизированный
+    /// (SYLT) или обычный (USLT) текст из тегов ID3
+    pub fn load(path: &str) -> Option<Self> {
+        let path = Path::new(path);
+        let sidecar = ["lrc", "LRC"]
+            .iter()
+            .map(|extension| path.with_extension(extension))
+            .find(|sidecar| sidecar.is_file());
+        if let Some(text) = sidecar.and_then(|sidecar| fs::read_to_string(sidecar).ok()) {
+            return Some(Self::parse(&text));
+        }
+
+        let tag = Tag::read_from_path(path).ok()?;
+        let synced = tag
+            .synchronised_lyrics()
+            .find(|lyrics| lyrics.timestamp_format == TimestampFormat::Ms && !lyrics.content.is_empty());
+        if let Some(lyrics) = synced {
+            let mut lines: Vec<(Duration, String)> = lyrics
+                .content
+                .iter()
+                .map(|(time, text)| (Duration::from_millis(*time as u64), text.trim().to_string()))
+                .collect();
+            lines.sort_by_key(|(time, _)| *time);
+            return Some(Self::Synced(lines));
+        }
+
+        // В USLT нередко лежит текст в формате LRC
+        let lyrics = tag.lyrics().find(|lyrics| !lyrics.text.trim().is_empty())?;
+        Some(Self::parse(&lyrics.text))
+    }
+
+    /// Разбирает текст в формате LRC; текст без отметок времени считается обычным
+    ///
+    /// Строка может начинаться с нескольких отметок `[мм:сс.сс]`, отметки слов `<мм:сс.сс>`
+    /// внутри строки отбрасываются, а тег `[offset:мс]` сдвигает все строки.
+    pub fn parse(text: &str) -> Self {
+        let mut offset = 0i64;
+        let mut synced = vec![];
+        let mut plain = vec![];
+
+        for line in text.lines() {
+            let mut rest = line.trim();
+            let mut times = vec![];
+            // Строка с тегами вроде `[ar:Исполнитель]` не относится к тексту
+            let mut metadata = false;
+            while let Some((tag, after)) = rest.strip_prefix('[').and_then(|tag| tag.split_once(']')) {
+                if let Some(time) = parse_timestamp(tag) {
+                    times.push(time);
+                } else if let Some((key, value)) = tag.split_once(':') {
+                    metadata = true;
+                    if key.trim() == "offset" {
+                        offset = value.trim().parse().unwrap_or(0);
+                    }
+                } else {
+                    break;
+                }
+                rest = after;
+            }
+
+            let text = strip_word_timestamps(rest);
+            match times.is_empty() {
+                true if !metadata => plain.push(text),
+                true => {}
+                false => synced.extend(times.into_iter().map(|time| (time, text.clone()))),
+            }
+        }
+
+        if synced.is_empty() {
+            // Пустые строки по краям остаются от форматирования файла
+            let start = plain.iter().position(|line| !line.is_empty()).unwrap_or(plain.len());
+            let end = plain.iter().rposition(|line| !line.is_empty()).map_or(start, |end| end + 1);
+            return Self::Plain(plain[start..end].to_vec());
+        }
+
+        // Положительный сдвиг показывает строки раньше
+        let shift = |time: Duration| match offset >= 0 {
+            true => time.saturating_sub(Duration::from_millis(offset as u64)),
+            false => time + Duration::from_millis(offset.unsigned_abs()),
+        };
+        let mut synced: Vec<(Duration, String)> =
+            synced.into_iter().map(|(time, text)| (shift(time), text)).collect();
+        synced.sort_by_key(|(time, _)| *time);
+        Self::Synced(synced)
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Synced(lines) => lines.len(),
+            Self::Plain(lines) => lines.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn text(&self, index: usize) -> &str {
+        match self {
+            Self::Synced(lines) => &lines[index].1,
+            Self::Plain(lines) => &lines[index],
+        }
+    }
+
+    /// Время начала строки; у обычного текста его нет
+    pub fn time(&self, index: usize) -> Option<Duration> {
+        match self {
+            Self::Synced(lines) => lines.get(index).map(|(time, _)| *time),
+            Self::Plain(_) => None,
+        }
+    }
+
+    /// Строка, которая звучит в момент `position`; `None` до первой строки и для обычного текста
+    pub fn current_line(&self, position: Duration) -> Option<usize> {
+        match self {
+            Self::Synced(lines) => lines.iter().rposition(|(time, _)| *time <= position),
+            Self::Plain(_) => None,
+        }
+    }
+}
+
+/// Время из отметки `мм:сс`, `мм:сс.сс` или `мм:сс:сс`
+///
+/// # Returns:
+/// `None` и для отметок, время которых не помещается в [`Duration`]
+fn parse_timestamp(tag: &str) -> Option<Duration> {
+    let (minutes, seconds) = tag.split_once(':')?;
+    let minutes: u64 = minutes.trim().parse().ok()?;
+    // Встречается и двоеточие перед сотыми долями
+    let seconds: f64 = seconds.trim().replacen(':', ".", 1).parse().ok()?;
+    let seconds = Duration::try_from_secs_f64(seconds).ok()?;
+    Duration::from_secs(minutes.checked_mul(60)?).checked_add(seconds)
+}
+
+/// Убирает из строки отметки времени отдельных слов из расширенного формата LRC
+fn strip_word_timestamps(line: &str) -> String {
+    let mut text = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find('<') {
+        let Some(length) = rest[start..].find('>') else { break };
+        text.push_str(&rest[..start]);
+        if parse_timestamp(&rest[start + 1..start + length]).is_none() {
+            text.push_str(&rest[start..start + length + 1]);
+        }
+        rest = &rest[start + length + 1..];
+    }
+    text.push_str(rest);
+    text.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn synced(text: &str) -> Vec<(Duration, String)> {
+        match Lyrics::parse(text) {
+            Lyrics::Synced(lines) => lines,
+            Lyrics::Plain(lines) => panic!("expected synced lyrics, got plain {:?}", lines),
+        }
+    }
+
+    fn plain(text: &str) -> Vec<String> {
+        match Lyrics::parse(text) {
+            Lyrics::Plain(lines) => lines,
+            Lyrics::Synced(lines) => panic!("expected plain lyrics, got synced {:?}", lines),
+        }
+    }
+
+    #[test]
+    fn parses_timestamps() {
+        assert_eq!(parse_timestamp("01:02.50"), Some(ms(62_500)));
+        assert_eq!(parse_timestamp("00:05"), Some(ms(5_000)));
+        assert_eq!(parse_timestamp("1:02:25"), Some(ms(62_250)));
+        assert_eq!(parse_timestamp(" 10:00.00 "), Some(ms(600_000)));
+        assert_eq!(parse_timestamp("ar:Artist"), None);
+        assert_eq!(parse_timestamp("00:-1"), None);
+        assert_eq!(parse_timestamp("00:nan"), None);
+        assert_eq!(parse_timestamp("0005"), None);
+        assert_eq!(parse_timestamp("00:1e30"), None);
+        assert_eq!(parse_timestamp("00:inf"), None);
+        assert_eq!(parse_timestamp("18446744073709551615:00"), None);
+        assert_eq!(parse_timestamp("307445734561825860:59.99"), None);
+    }
+
+    #[test]
+    fn strips_word_timestamps() {
+        assert_eq!(strip_word_timestamps("<00:01.00>Hello <00:01.50>world"), "Hello world");
+        assert_eq!(strip_word_timestamps("  a <b> c  "), "a <b> c");
+        assert_eq!(strip_word_timestamps("1 < 2"), "1 < 2");
+        assert_eq!(strip_word_timestamps("<00:03>"), "");
+    }
+
+    #[test]
+    fn sorts_synced_lines() {
+        let lines = synced("[00:10.00]Second\n[00:05.00]First\n\n[00:20.00]");
+        assert_eq!(
+            lines,
+            vec![
+                (ms(5_000), "First".to_string()),
+                (ms(10_000), "Second".to_string()),
+                (ms(20_000), String::new())
+            ]
+        );
+    }
+
+    #[test]
+    fn repeats_line_with_several_timestamps() {
+        let lines = synced("[00:01.00][00:30.00]Chorus\n[00:10.00]Verse");
+        assert_eq!(
+            lines,
+            vec![
+                (ms(1_000), "Chorus".to_string()),
+                (ms(10_000), "Verse".to_string()),
+                (ms(30_000), "Chorus".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_metadata_tags() {
+        let lines = synced("[ar:Artist]\n[ti:Title]\n[length: 03:00]\n[00:01.00]Line");
+        assert_eq!(lines, vec![(ms(1_000), "Line".to_string())]);
+
+        assert_eq!(plain("[ar:Artist]\n\nFirst\n\nSecond\n\n"), vec!["First", "", "Second"]);
+    }
+
+    #[test]
+    fn applies_offset() {
+        let earlier = synced("[offset:500]\n[00:00.20]A\n[00:02.00]B");
+        assert_eq!(earlier, vec![(ms(0), "A".to_string()), (ms(1_500), "B".to_string())]);
+
+        let later = synced("[00:02.00]B\n[offset: -250]");
+        assert_eq!(later, vec![(ms(2_250), "B".to_string())]);
+    }
+
+    #[test]
+    fn strips_word_timestamps_from_lines() {
+        let lines = synced("[00:01.00]<00:01.00>Hello <00:01.40>there");
+        assert_eq!(lines, vec![(ms(1_000), "Hello there".to_string())]);
+    }
+
+    #[test]
+    fn keeps_plain_text() {
+        assert_eq!(plain("Just words\n[not a tag] here"), vec!["Just words", "[not a tag] here"]);
+        assert!(plain("").is_empty());
+    }
+
+    #[test]
+    fn finds_current_line() {
+        let lyrics = Lyrics::parse("[00:01.00]A\n[00:05.00]B");
+        assert_eq!(lyrics.current_line(ms(500)), None);
+        assert_eq!(lyrics.current_line(ms(1_000)), Some(0));
+        assert_eq!(lyrics.current_line(ms(9_000)), Some(1));
+        assert_eq!(Lyrics::parse("A").current_line(ms(9_000)), None);
+    }
+}
@@ -1,7 +1,21 @@
+mod album_art;
 mod app;
+mod audio_tap;
 mod components;
+mod config;
+mod files;
+mod fuzzy;
 mod interaction;
+mod library;
+mod lyrics;
+mod metadata;
+mod organise;
+mod play_counts;
 mod player;
+mod playlist_store;
+mod theme;
+mod watcher;
+mod waveform;
 
 use std::io::Error;
 
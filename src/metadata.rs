@@ -0,0 +1,383 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, UNIX_EPOCH},
+};
+
+use audiotags::Tag;
+use rusqlite::{params, Connection, OptionalExtension};
+use symphonia::core::{
+    codecs::CodecParameters, formats::FormatOptions, io::MediaSourceStream, meta::MetadataOptions,
+    probe::Hint,
+};
+
+use crate::config::data_dir;
+
+/// Сколько файлов читать между уведомлениями о прогрессе фоновой загрузки
+const PREFETCH_BATCH: usize = 32;
+
+const CACHE_FILE_NAME: &str = "metadata.sqlite";
+
+/// Параметры хэша FNV-1a (64 бита)
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS metadata (
+        path TEXT PRIMARY KEY,
+        mtime INTEGER NOT NULL,
+        size INTEGER NOT NULL,
+        title TEXT,
+        artist TEXT,
+        album TEXT,
+        album_artist TEXT,
+        year INTEGER,
+        genre TEXT,
+        track_number INTEGER,
+        disc_number INTEGER,
+        duration_ms INTEGER,
+        bitrate INTEGER,
+        art_hash INTEGER
+    );
+";
+
+/// Теги трека
+///
+/// # Fields
+///
+/// * `title` - Название трека
+/// * `artist` - Исполнитель
+/// * `album` - Альбом
+/// * `album_artist` - Исполнитель альбома
+/// * `year` - Год
+/// * `genre` - Жанр
+/// * `track_number` - Номер трека в альбоме
+/// * `disc_number` - Номер диска
+/// * `duration` - Длительность
+/// * `bitrate` - Средний битрейт в кбит/с
+/// * `art_hash` - Хэш встроенной обложки, если она есть
+#[derive(Default, Clone, PartialEq)]
+pub struct TrackMetadata {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub album_artist: Option<String>,
+    pub year: Option<i32>,
+    pub genre: Option<String>,
+    pub track_number: Option<u16>,
+    pub disc_number: Option<u16>,
+    pub duration: Option<Duration>,
+    pub bitrate: Option<u32>,
+    pub art_hash: Option<u64>,
+}
+
+impl TrackMetadata {
+    /// Читает теги и параметры потока из файла. Файл без тегов дает пустые теги.
+    pub fn read(path: &str) -> Self {
+        let mut metadata = match Tag::new().read_from_path(path) {
+            Ok(tag) => Self {
+                title: tag.title().map(str::to_string),
+                artist: tag.artist().map(str::to_string),
+                album: tag.album().map(|album| album.title.to_string()),
+                album_artist: tag.album_artist().map(str::to_string),
+                year: tag.year(),
+                genre: tag.genre().map(str::to_string),
+                track_number: tag.track_number(),
+                disc_number: tag.disc_number(),
+                art_hash: tag.album_cover().map(|cover| stable_hash(cover.data)),
+                ..Self::default()
+            },
+            Err(_) => Self::default(),
+        };
+
+        if let Some((duration, bitrate)) = read_stream_info(path) {
+            metadata.duration = Some(duration);
+            metadata.bitrate = bitrate;
+        }
+        metadata
+    }
+
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            title: row.get("title")?,
+            artist: row.get("artist")?,
+            album: row.get("album")?,
+            album_artist: row.get("album_artist")?,
+            year: row.get("year")?,
+            genre: row.get("genre")?,
+            track_number: row.get("track_number")?,
+            disc_number: row.get("disc_number")?,
+            duration: row.get::<_, Option<u64>>("duration_ms")?.map(Duration::from_millis),
+            bitrate: row.get("bitrate")?,
+            art_hash: row.get::<_, Option<i64>>("art_hash")?.map(|hash| hash as u64),
+        })
+    }
+}
+
+/// Параметры аудиопотока
+///
+/// # Fields
+///
+/// * `codec` - Кодек, например `MP3` или `FLAC`; если symphonia его не знает - расширение файла
+/// * `sample_rate` - Частота дискретизации в Гц
+/// * `bit_depth` - Разрядность; у форматов со сжатием с потерями ее нет
+/// * `channels` - Число каналов
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StreamInfo {
+    pub codec: Option<String>,
+    pub sample_rate: Option<u32>,
+    pub bit_depth: Option<u32>,
+    pub channels: Option<usize>,
+}
+
+impl StreamInfo {
+    /// Читает параметры потока из заголовков файла
+    pub fn read(path: &str) -> Self {
+        let params = codec_params(path);
+        let codec = params
+            .as_ref()
+            .and_then(|params| symphonia::default::get_codecs().get_codec(params.codec))
+            .map(|codec| codec.short_name.to_string())
+            .or_else(|| Path::new(path).extension().map(|extension| extension.to_string_lossy().to_string()));
+
+        Self {
+            codec: codec.map(|codec| codec.to_uppercase()),
+            sample_rate: params.as_ref().and_then(|params| params.sample_rate),
+            bit_depth: params.as_ref().and_then(|params| params.bits_per_sample),
+            channels: params.as_ref().and_then(|params| params.channels).map(|channels| channels.count()),
+        }
+    }
+}
+
+/// Параметры основного потока из заголовков файла
+fn codec_params(path: &str) -> Option<CodecParameters> {
+    let file = File::open(path).ok()?;
+
+    let mut hint = Hint::new();
+    if let Some(extension) = Path::new(path).extension().and_then(|extension| extension.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let probed = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .ok()?;
+
+    probed.format.default_track().map(|track| track.codec_params.clone())
+}
+
+/// Читает из заголовков файла длительность и оценивает средний битрейт по размеру файла
+fn read_stream_info(path: &str) -> Option<(Duration, Option<u32>)> {
+    let size = fs::metadata(path).ok()?.len();
+    let params = codec_params(path)?;
+    let time = params.time_base?.calc_time(params.n_frames?);
+    let duration = Duration::from_secs(time.seconds) + Duration::from_secs_f64(time.frac);
+
+    let bitrate =
+        (duration.as_secs_f64() > 0.0).then(|| (size as f64 * 8.0 / duration.as_secs_f64() / 1000.0) as u32);
+    Some((duration, bitrate))
+}
+
+/// Время изменения (в миллисекундах) и размер файла: по ним видно, что файл изменился
+pub fn file_stamp(path: &str) -> Option<(i64, i64)> {
+    let metadata = fs::metadata(Path::new(path)).ok()?;
+    let mtime = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_millis() as i64;
+    Some((mtime, metadata.len() as i64))
+}
+
+/// Хэш FNV-1a для ключей, которые сохраняются на диск
+///
+/// Результат [`std::hash::DefaultHasher`] может измениться с новой версией Rust,
+/// и сохраненные ключи перестали бы совпадать с вычисленными заново.
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET_BASIS, |hash, &byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+/// Имя файла без каталога
+pub fn file_name(path: &str) -> String {
+    Path::new(path).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or(path.to_string())
+}
+
+/// Отметка файла (см. [`file_stamp`]) и прочитанные при ней теги
+type CacheEntry = (Option<(i64, i64)>, TrackMetadata);
+
+/// Общий кэш тегов, чтобы не перечитывать файлы при каждой отрисовке и каждом запуске трека
+///
+/// Прочитанные теги сохраняются в базе SQLite в каталоге данных вместе со временем изменения
+/// и размером файла; файл перечитывается, только если они изменились. Кэшем пользуются
+/// плейлист, медиатека и проигрыватель.
+///
+/// # Fields
+///
+/// * `entries` - Теги по пути к файлу вместе с отметкой файла, для которой они прочитаны
+/// * `version` - Растет при каждом пополнении кэша
+/// * `connection` - Соединение с базой; `None`, если базу открыть не удалось
+#[derive(Default, Clone)]
+pub struct MetadataCache {
+    entries: Arc<Mutex<HashMap<String, CacheEntry>>>,
+    version: Arc<AtomicU64>,
+    connection: Option<Arc<Mutex<Connection>>>,
+}
+
+impl MetadataCache {
+    pub fn open() -> Self {
+        let connection = data_dir()
+            .and_then(|dir| Connection::open(dir.join(CACHE_FILE_NAME)).ok())
+            .filter(|connection| connection.execute_batch(SCHEMA).is_ok())
+            .map(|connection| Arc::new(Mutex::new(connection)));
+
+        Self { connection, ..Self::default() }
+    }
+
+    /// Теги трека, если они уже прочитаны
+    pub fn get(&self, path: &str) -> Option<TrackMetadata> {
+        self.entries.lock().unwrap().get(path).map(|(_, metadata)| metadata.clone())
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+
+    /// Актуальные теги трека: из памяти или базы, если файл с тех пор не менялся,
+    /// иначе из самого файла
+    pub fn load(&self, path: &str) -> TrackMetadata {
+        self.load_batch(&[path.to_string()]);
+        self.get(path).unwrap_or_default()
+    }
+
+    /// Перечитывает теги из файлов, даже если отметки файлов не изменились (например,
+    /// после записи тегов в ту же миллисекунду)
+    pub fn reload(&self, paths: &[String]) {
+        self.entries.lock().unwrap().retain(|path, _| !paths.contains(path));
+        if let Some(connection) = &self.connection {
+            let connection = connection.lock().unwrap();
+            for path in paths {
+                let _ = connection.execute("DELETE FROM metadata WHERE path = ?1", [path]);
+            }
+        }
+        self.load_batch(paths);
+    }
+
+    /// Актуальные теги нескольких треков (см. [`MetadataCache::load`]) в том же порядке
+    pub fn load_many(&self, paths: &[String]) -> Vec<TrackMetadata> {
+        self.load_batch(paths);
+        paths.iter().map(|path| self.get(path).unwrap_or_default()).collect()
+    }
+
+    /// Обновляет теги файлов, изменившихся после того, как они попали в кэш. Прочитанные
+    /// из файлов теги записываются в базу одной транзакцией.
+    fn load_batch(&self, paths: &[String]) {
+        let mut loaded = vec![];
+        let mut read = vec![];
+
+        for path in paths {
+            let stamp = file_stamp(path);
+            let cached = self.entries.lock().unwrap().get(path).is_some_and(|(cached, _)| *cached == stamp);
+            if cached {
+                continue;
+            }
+
+            match stamp.and_then(|stamp| self.stored(path, stamp)) {
+                Some(metadata) => loaded.push((path.clone(), (stamp, metadata))),
+                None => read.push((path.clone(), (stamp, TrackMetadata::read(path)))),
+            }
+        }
+
+        self.store(&read);
+        if loaded.is_empty() && read.is_empty() {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        for (path, entry) in loaded.into_iter().chain(read) {
+            entries.insert(path, entry);
+        }
+        self.version.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Теги из базы, если они прочитаны из файла с той же отметкой
+    fn stored(&self, path: &str, (mtime, size): (i64, i64)) -> Option<TrackMetadata> {
+        let connection = self.connection.as_ref()?.lock().unwrap();
+        let mut statement = connection
+            .prepare_cached("SELECT * FROM metadata WHERE path = ?1 AND mtime = ?2 AND size = ?3")
+            .ok()?;
+        statement.query_row(params![path, mtime, size], TrackMetadata::from_row).optional().ok()?
+    }
+
+    fn store(&self, tracks: &[(String, CacheEntry)]) {
+        let Some(connection) = &self.connection else { return };
+        let mut connection = connection.lock().unwrap();
+        let Ok(transaction) = connection.transaction() else { return };
+
+        for (path, (stamp, metadata)) in tracks {
+            let Some((mtime, size)) = stamp else { continue };
+            let _ = transaction.execute(
+                "INSERT OR REPLACE INTO metadata (path, mtime, size, title, artist, album, album_artist,
+                    year, genre, track_number, disc_number, duration_ms, bitrate, art_hash)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                params![
+                    path,
+                    mtime,
+                    size,
+                    metadata.title,
+                    metadata.artist,
+                    metadata.album,
+                    metadata.album_artist,
+                    metadata.year,
+                    metadata.genre,
+                    metadata.track_number,
+                    metadata.disc_number,
+                    metadata.duration.map(|duration| duration.as_millis() as i64),
+                    metadata.bitrate,
+                    metadata.art_hash.map(|hash| hash as i64),
+                ],
+            );
+        }
+        let _ = transaction.commit();
+    }
+
+    /// Читает в фоне теги файлов, которых еще нет в кэше
+    ///
+    /// # Args:
+    /// * `paths` - Пути к файлам
+    /// * `on_progress` - Вызывается после каждой порции прочитанных файлов
+    pub fn prefetch<F>(&self, paths: Vec<String>, on_progress: F)
+    where
+        F: Fn() + Send + 'static,
+    {
+        let missing: Vec<String> = {
+            let entries = self.entries.lock().unwrap();
+            paths.into_iter().filter(|path| !entries.contains_key(path)).collect()
+        };
+
+        if missing.is_empty() {
+            return;
+        }
+
+        let cache = self.clone();
+        thread::spawn(move || {
+            for batch in missing.chunks(PREFETCH_BATCH) {
+                cache.load_batch(batch);
+                on_progress();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stable_hash_is_fnv1a() {
+        assert_eq!(stable_hash(b""), 0xcbf29ce484222325);
+        assert_eq!(stable_hash(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(stable_hash(b"foobar"), 0x85944171f73967e8);
+    }
+}
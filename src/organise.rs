@@ -0,0 +1,331 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::metadata::TrackMetadata;
+
+/// Подстановка для отсутствующего текстового тега
+const UNKNOWN_VALUE: &str = "Unknown";
+/// Символы, недопустимые в именах файлов на распространенных файловых системах
+const ILLEGAL_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Перемещение одного файла
+///
+/// # Fields
+///
+/// * `from` - Текущий путь
+/// * `to` - Путь по шаблону
+/// * `conflict` - Почему файл нельзя переместить: место занято другим файлом или другим
+///   перемещаемым треком
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rename {
+    pub from: String,
+    pub to: String,
+    pub conflict: Option<&'static str>,
+}
+
+/// Составляет план перемещения треков по шаблону
+///
+/// Путь по шаблону строится от каталога медиатеки, в котором лежит трек, а для треков вне
+/// медиатеки - от каталога самого трека. Треки, которые уже лежат на своем месте, в план
+/// не попадают.
+///
+/// Перемещения идут в порядке выполнения: трек, чье место займет другой, перемещается раньше.
+/// Треки, которые меняются местами по кругу, и треки, чье место не освободится, считаются
+/// конфликтами.
+///
+/// # Args:
+/// * `template` - Шаблон вроде `{albumartist}/{year} - {album}/{track:02} {title}.{ext}`
+/// * `tracks` - Пути к трекам и их теги
+/// * `roots` - Каталоги медиатеки
+pub fn plan_renames(template: &str, tracks: &[(String, TrackMetadata)], roots: &[PathBuf]) -> Vec<Rename> {
+    let mut renames: Vec<Rename> = tracks
+        .iter()
+        .map(|(path, metadata)| {
+            let source = Path::new(path);
+            let base = roots
+                .iter()
+                .find(|root| source.starts_with(root))
+                .cloned()
+                .or_else(|| source.parent().map(Path::to_path_buf))
+                .unwrap_or_default();
+            let to = base.join(expand_template(template, source, metadata));
+            Rename { from: path.clone(), to: to.to_string_lossy().to_string(), conflict: None }
+        })
+        .filter(|rename| rename.from != rename.to)
+        .collect();
+
+    let mut targets: HashMap<String, usize> = HashMap::new();
+    for rename in &renames {
+        *targets.entry(rename.to.clone()).or_default() += 1;
+    }
+    let sources: HashSet<String> = renames.iter().map(|rename| rename.from.clone()).collect();
+    for rename in &mut renames {
+        if targets[&rename.to] > 1 {
+            rename.conflict = Some("same target as another track");
+        } else if Path::new(&rename.to).exists() && !sources.contains(&rename.to) {
+            rename.conflict = Some("target exists");
+        }
+    }
+
+    // Перемещение, которое освобождает место для данного
+    let positions: HashMap<String, usize> =
+        renames.iter().enumerate().map(|(position, rename)| (rename.from.clone(), position)).collect();
+    let vacating: Vec<Option<usize>> =
+        renames.iter().map(|rename| positions.get(&rename.to).copied()).collect();
+
+    for start in 0..renames.len() {
+        let mut position = vacating[start];
+        for _ in 0..renames.len() {
+            match position {
+                Some(current) if current == start => {
+                    renames[start].conflict = Some("swaps places with another track");
+                    break;
+                }
+                Some(current) => position = vacating[current],
+                None => break,
+            }
+        }
+    }
+
+    // Место, которое занимает неперемещаемый трек, не освободится
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for position in 0..renames.len() {
+            let blocked = vacating[position].is_some_and(|vacating| renames[vacating].conflict.is_some());
+            if blocked && renames[position].conflict.is_none() {
+                renames[position].conflict = Some("target is a track that cannot move");
+                changed = true;
+            }
+        }
+    }
+
+    let mut order = vec![];
+    let mut placed = vec![false; renames.len()];
+    for start in 0..renames.len() {
+        let mut chain = vec![];
+        let mut position = Some(start);
+        while let Some(current) = position.filter(|&current| !placed[current] && !chain.contains(&current)) {
+            chain.push(current);
+            position = vacating[current];
+        }
+        for &current in chain.iter().rev() {
+            placed[current] = true;
+            order.push(current);
+        }
+    }
+
+    let mut renames: Vec<Option<Rename>> = renames.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|position| renames[position].take()).collect()
+}
+
+/// Путь по шаблону относительно базового каталога
+///
+/// Поля: `{title}`, `{artist}`, `{album}`, `{albumartist}`, `{genre}`, `{year}`, `{track}`,
+/// `{disc}`, `{filename}` (имя файла без расширения) и `{ext}`. Ширина `{track:02}` дополняет
+/// число нулями; отсутствующие числа и год пропускаются. Значения полей очищаются от символов,
+/// недопустимых в именах файлов, поэтому каталоги задаются только косой чертой самого шаблона.
+pub fn expand_template(template: &str, source: &Path, metadata: &TrackMetadata) -> PathBuf {
+    let mut expanded = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(length) = rest[start..].find('}') else { break };
+        expanded.push_str(&rest[..start]);
+
+        let placeholder = &rest[start + 1..start + length];
+        let (name, width) = match placeholder.split_once(':') {
+            Some((name, width)) => (name, width.parse::<usize>().unwrap_or(0)),
+            None => (placeholder, 0),
+        };
+        match field_value(name, source, metadata) {
+            Some(value) if value.is_empty() => {}
+            Some(value) => expanded.push_str(&sanitize(&format!("{:0>width$}", value, width = width))),
+            None => expanded.push_str(&rest[start..start + length + 1]),
+        }
+        rest = &rest[start + length + 1..];
+    }
+    expanded.push_str(rest);
+
+    expanded.split('/').filter(|component| !component.is_empty()).map(sanitize_component).collect()
+}
+
+/// Значение поля шаблона; `None` - такого поля нет
+fn field_value(name: &str, source: &Path, metadata: &TrackMetadata) -> Option<String> {
+    let text = |value: &Option<String>| value.clone().unwrap_or_else(|| UNKNOWN_VALUE.to_string());
+    let number = |value: Option<u16>| value.map(|number| number.to_string()).unwrap_or_default();
+
+    let value = match name {
+        "title" => text(&metadata.title),
+        "artist" => text(&metadata.artist),
+        "album" => text(&metadata.album),
+        "albumartist" => text(&metadata.album_artist.clone().or_else(|| metadata.artist.clone())),
+        "genre" => text(&metadata.genre),
+        "year" => metadata.year.map(|year| year.to_string()).unwrap_or_default(),
+        "track" => number(metadata.track_number),
+        "disc" => number(metadata.disc_number),
+        "filename" => source.file_stem()?.to_string_lossy().to_string(),
+        "ext" => source.extension()?.to_string_lossy().to_string(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Заменяет недопустимые и управляющие символы подчеркиванием
+fn sanitize(value: &str) -> String {
+    value.chars().map(|c| if ILLEGAL_CHARS.contains(&c) || c.is_control() { '_' } else { c }).collect()
+}
+
+/// Имя каталога или файла без пробелов по краям и точек в конце (их не допускают некоторые
+/// файловые системы). Заодно `.` и `..` не выведут путь за пределы базового каталога.
+fn sanitize_component(component: &str) -> String {
+    match component.trim().trim_end_matches('.') {
+        "" => "_".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// Перемещает файл, создавая недостающие каталоги, и удаляет опустевшие каталоги,
+/// из которых он был перемещен, вплоть до общего с новым путем. Существующий файл
+/// не перезаписывается.
+pub fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if to.exists() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, "target exists"));
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Между файловыми системами переименование невозможно: файл копируется
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+
+    let mut dir = from.parent();
+    while let Some(parent) = dir {
+        if to.starts_with(parent) || fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> TrackMetadata {
+        TrackMetadata {
+            title: Some("Song".to_string()),
+            artist: Some("Artist".to_string()),
+            album: Some("Album".to_string()),
+            year: Some(2001),
+            track_number: Some(7),
+            ..Default::default()
+        }
+    }
+
+    fn expand(template: &str, metadata: &TrackMetadata) -> String {
+        expand_template(template, Path::new("/music/old name.flac"), metadata).to_string_lossy().to_string()
+    }
+
+    fn plan(moves: &[(&str, &str)]) -> Vec<Rename> {
+        // Шаблон из одного имени файла: каждый трек получает путь, записанный в его названии
+        let tracks: Vec<(String, TrackMetadata)> = moves
+            .iter()
+            .map(|(from, to)| {
+                (from.to_string(), TrackMetadata { title: Some(to.to_string()), ..Default::default() })
+            })
+            .collect();
+        plan_renames("{title}", &tracks, &[PathBuf::from("/nonexistent")])
+    }
+
+    #[test]
+    fn expands_fields_and_padding() {
+        assert_eq!(
+            expand("{artist}/{year} - {album}/{track:02} {title}.{ext}", &metadata()),
+            "Artist/2001 - Album/07 Song.flac"
+        );
+        assert_eq!(expand("{track:03}", &metadata()), "007");
+        assert_eq!(expand("{filename}", &metadata()), "old name");
+    }
+
+    #[test]
+    fn missing_fields() {
+        let empty = TrackMetadata::default();
+        assert_eq!(expand("{artist}/{title}", &empty), "Unknown/Unknown");
+        assert_eq!(expand("{track:02} {title}", &empty), "Unknown");
+        assert_eq!(expand("{year}/{title}", &empty), "Unknown");
+        assert_eq!(expand("{albumartist}", &metadata()), "Artist");
+    }
+
+    #[test]
+    fn unknown_fields_are_kept() {
+        assert_eq!(expand("{composer} {title}", &metadata()), "{composer} Song");
+        assert_eq!(expand("{title", &metadata()), "{title");
+    }
+
+    #[test]
+    fn values_cannot_add_directories() {
+        let metadata = TrackMetadata { title: Some("AC/DC: Live?".to_string()), ..Default::default() };
+        assert_eq!(expand("{title}", &metadata), "AC_DC_ Live_");
+    }
+
+    #[test]
+    fn components_cannot_leave_base() {
+        let metadata = TrackMetadata { album: Some("..".to_string()), ..Default::default() };
+        assert_eq!(expand("{album}/x", &metadata), "_/x");
+        assert_eq!(expand("../x", &metadata), "_/x");
+        assert_eq!(expand("a//b", &metadata), "a/b");
+    }
+
+    #[test]
+    fn sanitizes_components() {
+        assert_eq!(sanitize_component("  Album...  "), "Album");
+        assert_eq!(sanitize_component("."), "_");
+        assert_eq!(sanitize_component("   "), "_");
+        assert_eq!(sanitize_component("Vol. 1"), "Vol. 1");
+    }
+
+    #[test]
+    fn skips_tracks_in_place() {
+        assert!(plan(&[("/nonexistent/a", "a")]).is_empty());
+    }
+
+    #[test]
+    fn duplicate_targets_conflict() {
+        let renames = plan(&[("/nonexistent/a", "c"), ("/nonexistent/b", "c")]);
+        assert_eq!(renames.len(), 2);
+        assert!(renames.iter().all(|rename| rename.conflict == Some("same target as another track")));
+    }
+
+    #[test]
+    fn chains_move_vacating_track_first() {
+        let renames = plan(&[("/nonexistent/a", "b"), ("/nonexistent/b", "c"), ("/nonexistent/c", "d")]);
+        let order: Vec<&str> = renames.iter().map(|rename| rename.from.as_str()).collect();
+        assert_eq!(order, ["/nonexistent/c", "/nonexistent/b", "/nonexistent/a"]);
+        assert!(renames.iter().all(|rename| rename.conflict.is_none()));
+    }
+
+    #[test]
+    fn swaps_conflict() {
+        let renames = plan(&[("/nonexistent/a", "b"), ("/nonexistent/b", "a"), ("/nonexistent/x", "y")]);
+        let conflicts: Vec<(&str, Option<&str>)> =
+            renames.iter().map(|rename| (rename.from.as_str(), rename.conflict)).collect();
+        assert!(conflicts.contains(&("/nonexistent/a", Some("swaps places with another track"))));
+        assert!(conflicts.contains(&("/nonexistent/b", Some("swaps places with another track"))));
+        assert!(conflicts.contains(&("/nonexistent/x", None)));
+    }
+
+    #[test]
+    fn blocked_chains_conflict() {
+        let renames = plan(&[("/nonexistent/a", "b"), ("/nonexistent/b", "c"), ("/nonexistent/d", "c")]);
+        assert!(renames.iter().all(|rename| rename.conflict.is_some()));
+        let a = renames.iter().find(|rename| rename.from == "/nonexistent/a").unwrap();
+        assert_eq!(a.conflict, Some("target is a track that cannot move"));
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::{
+    app::{AppState, PlayerState, StateChange},
+    config::data_dir,
+};
+
+const PLAY_COUNTS_FILE_NAME: &str = "play_counts.tsv";
+
+/// Счетчики прослушиваний треков, сохраняемые в каталоге данных
+///
+/// Файл хранит по строке на трек: число прослушиваний и путь через табуляцию.
+#[derive(Default, Clone)]
+pub struct PlayCounts {
+    counts: Arc<Mutex<HashMap<String, u32>>>,
+    file: Option<PathBuf>,
+}
+
+impl PlayCounts {
+    pub fn load() -> Self {
+        let file = data_dir().map(|dir| dir.join(PLAY_COUNTS_FILE_NAME));
+
+        let counts = file
+            .as_ref()
+            .and_then(|file| fs::read_to_string(file).ok())
+            .map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| {
+                        let (count, path) = line.split_once('\t')?;
+                        Some((path.to_string(), count.parse().ok()?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self { counts: Arc::new(Mutex::new(counts)), file }
+    }
+
+    pub fn get(&self, path: &str) -> u32 {
+        self.counts.lock().unwrap().get(path).copied().unwrap_or(0)
+    }
+
+    /// Засчитывает прослушивание каждого запущенного трека
+    pub fn track(&self, app_state: &AppState) {
+        let counted_playback = AtomicU64::new(0);
+        let play_counts = self.clone();
+
+        app_state.subscribe(move |app_state, changes| {
+            if !changes.contains(&StateChange::CurrentTrack) {
+                return;
+            }
+
+            let started = app_state.read(|state| {
+                (*state.player_state() == PlayerState::Playing)
+                    .then(|| (state.playback_id(), state.current_track_path().cloned()))
+            });
+
+            if let Some((playback_id, Some(path))) = started {
+                if counted_playback.swap(playback_id, Ordering::AcqRel) != playback_id {
+                    play_counts.increment(&path);
+                }
+            }
+        });
+    }
+
+    fn increment(&self, path: &str) {
+        let content = {
+            let mut counts = self.counts.lock().unwrap();
+            *counts.entry(path.to_string()).or_default() += 1;
+            counts.iter().map(|(path, count)| format!("{}\t{}\n", count, path)).collect::<String>()
+        };
+
+        if let Some(file) = &self.file {
+            let _ = fs::write(file, content);
+        }
+    }
+}
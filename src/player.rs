@@ -1,153 +1,269 @@
 use std::{
     fs::File,
     io::BufReader,
-    sync::{mpsc::{self, Sender}, Arc},
+    path::Path,
+    sync::mpsc::{self, RecvTimeoutError, Sender},
     thread,
     time::Duration,
 };
 
-use audiotags::Tag;
 use rodio::{Decoder, OutputStream, Sink, Source};
 
-use crate::app::{AppState, CurrentTrackInfo, PlayerState};
+use crate::{
+    app::{AppState, CurrentTrackInfo, PlayerState, StateChange, ToastLevel},
+    audio_tap::{AudioTap, Tapped},
+    metadata::{file_name, MetadataCache, StreamInfo, TrackMetadata},
+};
+
+/// Период опроса позиции воспроизведения
+const PROGRESS_TICK: Duration = Duration::from_millis(250);
 
 #[derive(Debug)]
 enum PlayerCommand {
-    Play(String),
-    Stop,
-    Pause,
-    Resume,
+    /// Привести воспроизведение в соответствие с состоянием приложения
+    Sync,
     Seek(f64),
-    SetVolume(f32),
 }
 
+/// Проигрыватель
+///
+/// Сам следит за состоянием приложения: трек, пауза и громкость меняются через [`AppState`],
+/// а проигрыватель подстраивает под них вывод звука.
 #[derive(Clone)]
 pub struct Player {
     command_sender: Sender<PlayerCommand>,
+    tap: AudioTap,
 }
 
 impl Player {
-    pub fn new(app_state: &AppState) -> Self {
+    pub fn new(app_state: &AppState, metadata_cache: &MetadataCache) -> Self {
         let (sender, receiver) = mpsc::channel();
+        let tap = AudioTap::default();
+
+        app_state.subscribe({
+            let sender = sender.clone();
+            move |_, changes| {
+                let affects_playback = changes.iter().any(|change| {
+                    matches!(
+                        change,
+                        StateChange::PlayerState | StateChange::CurrentTrack | StateChange::Volume
+                    )
+                });
+
+                if affects_playback {
+                    let _ = sender.send(PlayerCommand::Sync);
+                }
+            }
+        });
 
         thread::spawn({
             let app_state = app_state.clone();
+            let metadata_cache = metadata_cache.clone();
+            let tap = tap.clone();
             move || {
-                let mut current_file_path = Option::<String>::None;
                 let (_stream, stream_handle) = OutputStream::try_default().unwrap();
-                let sink = Arc::new(Sink::try_new(&stream_handle).unwrap());
+                let sink = Sink::try_new(&stream_handle).unwrap();
 
-                for command in receiver {
-                    match command {
-                        PlayerCommand::Play(path) => {
-                            if let Ok(file) = File::open(path.clone()) {
-                                current_file_path = Some(path.clone());
-
-                                let decoder = Decoder::new(BufReader::new(file)).unwrap();
+                // Номер запуска, трек которого сейчас загружен в sink
+                let mut loaded_playback = Option::<u64>::None;
+                // Сколько треков подряд не удалось открыть
+                let mut failures = 0;
 
-                                if let Some(duration) = decoder.total_duration() {
-                                    app_state.set_current_track_info(current_track_info(&path, duration));
-                                }
+                loop {
+                    let command = match receiver.recv_timeout(PROGRESS_TICK) {
+                        Ok(command) => Some(command),
+                        Err(RecvTimeoutError::Timeout) => None,
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    };
 
-                                sink.append(decoder);
-                            }
-                            sink.play();
-                            launch_sink_state_checker(&app_state, sink.clone());
-                        }
-                        PlayerCommand::Stop => {
-                            sink.stop();
-                            current_file_path = None;
-                        }
-                        PlayerCommand::Pause => {
-                            sink.pause();
-                        }
-                        PlayerCommand::Resume => {
-                            sink.play();
-                        }
-                        PlayerCommand::SetVolume(volume) => {
-                            sink.set_volume(volume);
-                        }
-                        PlayerCommand::Seek(ratio) => {
-                            sink.stop();
-
-                            if let Some(path) = current_file_path.clone() {
-                                if let Ok(file) = File::open(path.clone()) {
-                                    current_file_path = Some(path);
-                                    let mut decoder = Decoder::new(BufReader::new(file)).unwrap();
-                                    let total_duration = decoder
-                                        .total_duration()
-                                        .unwrap_or(Duration::from_secs(0))
-                                        .as_secs_f64();
-                                    let duration = total_duration * ratio;
-                                    let _ = decoder.try_seek(Duration::from_secs_f64(duration));
-                                    sink.append(decoder);
-                                }
+                    match command {
+                        Some(PlayerCommand::Sync) => sync_with_state(
+                            &app_state,
+                            &metadata_cache,
+                            &sink,
+                            &tap,
+                            &mut loaded_playback,
+                            &mut failures,
+                        ),
+                        Some(PlayerCommand::Seek(ratio)) => {
+                            if let Some(info) = app_state.current_track_info() {
+                                let _ = sink.try_seek(info.duration.mul_f64(ratio.clamp(0.0, 1.0)));
                             }
                         }
+                        None => {}
                     }
+
+                    update_track_progress(&app_state, &sink, &mut loaded_playback);
                 }
             }
         });
 
-        Self { command_sender: sender }
+        Self { command_sender: sender, tap }
     }
 
-    // Методы для управления из UI
-    pub fn play(&self, path: String) {
-        self.command_sender.send(PlayerCommand::Play(path)).unwrap();
+    /// Выборки, которые сейчас уходят на звуковой вывод
+    pub fn tap(&self) -> &AudioTap {
+        &self.tap
     }
 
-    pub fn stop(&self) {
-        self.command_sender.send(PlayerCommand::Stop).unwrap();
+    pub fn seek(&self, position: f64) {
+        self.command_sender.send(PlayerCommand::Seek(position)).unwrap();
     }
+}
 
-    pub fn pause(&self) {
-        self.command_sender.send(PlayerCommand::Pause).unwrap();
-    }
+/// Подстраивает sink под состояние приложения
+fn sync_with_state(
+    app_state: &AppState,
+    metadata_cache: &MetadataCache,
+    sink: &Sink,
+    tap: &AudioTap,
+    loaded_playback: &mut Option<u64>,
+    failures: &mut usize,
+) {
+    let (player_state, playback_id, path, volume) = app_state.read(|state| {
+        (
+            state.player_state().clone(),
+            state.playback_id(),
+            state.current_track_path().cloned(),
+            state.volume(),
+        )
+    });
+
+    sink.set_volume(volume);
 
-    pub fn resume(&self) {
-        self.command_sender.send(PlayerCommand::Resume).unwrap();
+    if player_state == PlayerState::Stopped {
+        *failures = 0;
+        if loaded_playback.take().is_some() {
+            sink.stop();
+        }
+        return;
     }
 
-    pub fn set_volume(&self, volume: f32) {
-        self.command_sender.send(PlayerCommand::SetVolume(volume)).unwrap();
+    if *loaded_playback != Some(playback_id) {
+        sink.stop();
+        *loaded_playback = Some(playback_id);
+
+        if let Some(path) = path {
+            match load_track(app_state, metadata_cache, sink, tap, &path, playback_id) {
+                Ok(()) => *failures = 0,
+                Err(error) => {
+                    *loaded_playback = None;
+                    *failures += 1;
+                    skip_unplayable(app_state, &path, &error, playback_id, *failures);
+                    return;
+                }
+            }
+        }
     }
 
-    pub fn seek(&self, position: f64) {
-        self.command_sender.send(PlayerCommand::Seek(position)).unwrap();
+    match player_state {
+        PlayerState::Playing => sink.play(),
+        _ => sink.pause(),
     }
 }
 
-fn current_track_info(path: &String, track_duration: Duration) -> Option<CurrentTrackInfo> {
-    if let Some(tag) = Tag::new().read_from_path(&path).ok() {
-        let title = tag.title().unwrap_or("Unknown");
-        let artist = tag.artist().unwrap_or("Unknown");
-
-        let album =
-            if let Some(album) = tag.album() { album.title.to_string() } else { "Unknown".to_string() };
-
-        return Some(CurrentTrackInfo::new(
-            title.to_string(),
-            artist.to_string(),
-            album.to_string(),
-            track_duration,
-            Duration::from_secs(0),
-        ));
+/// Загружает трек в sink
+///
+/// # Returns:
+/// Описание ошибки, если файл не открылся или не декодируется
+fn load_track(
+    app_state: &AppState,
+    metadata_cache: &MetadataCache,
+    sink: &Sink,
+    tap: &AudioTap,
+    path: &str,
+    playback_id: u64,
+) -> Result<(), String> {
+    let file = File::open(path).map_err(|error| error.to_string())?;
+    let decoder = Decoder::new(BufReader::new(file)).map_err(|error| error.to_string())?;
+
+    // Декодер MP3 часто не знает длительность, тогда она берется из заголовков файла
+    let metadata = metadata_cache.load(path);
+    if let Some(duration) = decoder.total_duration().or(metadata.duration) {
+        let info = current_track_info(path, metadata, StreamInfo::read(path), duration);
+        app_state.update(|state| {
+            if state.playback_id() == playback_id {
+                state.set_current_track_info(Some(info));
+            }
+        });
     }
 
-    None
+    sink.append(Tapped::new(decoder.convert_samples(), tap.clone()));
+    Ok(())
 }
 
+/// Сообщает о треке, который не удалось открыть, и переходит к следующему. Если подряд
+/// не открылись все треки его плейлиста, воспроизведение останавливается, а не перебирает их по кругу.
+fn skip_unplayable(app_state: &AppState, path: &str, error: &str, playback_id: u64, failures: usize) {
+    app_state.notify(ToastLevel::Error, format!("Cannot play \"{}\": {}", file_name(path), error));
 
-fn launch_sink_state_checker(app_state: &AppState, sink: Arc<Sink>) {
-    thread::spawn({
-        let sink = sink.clone();
-        let app_state = app_state.clone();
-        move || {
-            while !sink.empty() { thread::sleep(Duration::from_secs(1)); }
+    let missing = !Path::new(path).exists();
+    app_state.update(|state| {
+        if missing {
+            state.set_tracks_missing([path.to_string()], true);
+        }
+        if state.playback_id() != playback_id {
+            return;
+        }
 
-            app_state.set_current_track_info(None);
-            app_state.set_player_state(PlayerState::Stopped);
+        match failures >= state.current_playlist_len() {
+            true => state.stop(),
+            false => state.skip_forward(),
         }
     });
-}
\ No newline at end of file
+}
+
+/// Информация о треке из кэша тегов; трек без названия называется по имени файла
+pub fn current_track_info(
+    path: &str,
+    metadata: TrackMetadata,
+    stream: StreamInfo,
+    track_duration: Duration,
+) -> CurrentTrackInfo {
+    CurrentTrackInfo {
+        path: path.to_string(),
+        title: metadata.title.unwrap_or_else(|| file_name(path)),
+        artist: metadata.artist.unwrap_or_else(|| "Unknown".to_string()),
+        album: metadata.album.unwrap_or_else(|| "Unknown".to_string()),
+        year: metadata.year,
+        track_number: metadata.track_number,
+        disc_number: metadata.disc_number,
+        genre: metadata.genre,
+        bitrate: metadata.bitrate,
+        stream,
+        duration: track_duration,
+        played_duration: Duration::from_secs(0),
+    }
+}
+
+/// Обновляет позицию воспроизведения и по окончании трека переходит к следующему
+///
+/// Позиция публикуется только при смене секунды, чтобы не будить интерфейс лишний раз.
+fn update_track_progress(app_state: &AppState, sink: &Sink, loaded_playback: &mut Option<u64>) {
+    let Some(playback_id) = *loaded_playback else { return };
+
+    if sink.empty() {
+        *loaded_playback = None;
+        app_state.update(|state| {
+            if state.playback_id() == playback_id {
+                state.skip_forward();
+            }
+        });
+        return;
+    }
+
+    let position = sink.get_pos();
+    app_state.update(|state| {
+        if state.playback_id() != playback_id {
+            return;
+        }
+
+        if let Some(info) = state.current_track_info() {
+            if position.as_secs() != info.played_duration.as_secs() {
+                let mut info = info.clone();
+                info.played_duration = position;
+                state.set_current_track_info(Some(info));
+            }
+        }
+    });
+}
@@ -0,0 +1,201 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    app::{AppState, NamedPlaylist, StateChange},
+    config::data_dir,
+};
+
+const PLAYLISTS_DIR_NAME: &str = "playlists";
+const PLAYLIST_EXTENSION: &str = "m3u8";
+
+const M3U_HEADER: &str = "#EXTM3U";
+const M3U_PLAYLIST_NAME: &str = "#PLAYLIST:";
+
+/// Сколько ждать паузы в правках, прежде чем записать плейлисты: перетаскивание
+/// и перемещение треков стрелками - это десятки правок подряд
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// Плейлисты, сохраняемые файлами M3U8 в каталоге данных
+///
+/// Название плейлиста хранится в директиве `#PLAYLIST:`, а имя файла получается из него
+/// заменой недопустимых символов. Разные названия могут дать одно имя файла (`a/b` и `a:b`),
+/// поэтому за каждым плейлистом закреплен свой файл, а занятое имя получает номер.
+///
+/// # Fields
+///
+/// * `dir` - Каталог с файлами плейлистов
+/// * `saved` - Плейлисты в том виде, в котором они записаны на диск
+/// * `loaded_files` - Файлы плейлистов, прочитанных [`PlaylistStore::load`], в том же порядке
+#[derive(Default, Clone)]
+pub struct PlaylistStore {
+    dir: Option<PathBuf>,
+    saved: Arc<Mutex<HashMap<u64, SavedPlaylist>>>,
+    loaded_files: Arc<Mutex<Vec<PathBuf>>>,
+}
+
+/// Плейлист, записанный на диск
+///
+/// # Fields
+///
+/// * `playlist` - Содержимое на момент записи
+/// * `file` - Файл плейлиста; `None`, если плейлист еще ни разу не записывался
+struct SavedPlaylist {
+    playlist: NamedPlaylist,
+    file: Option<PathBuf>,
+}
+
+impl PlaylistStore {
+    pub fn open() -> Self {
+        let dir = data_dir().map(|dir| dir.join(PLAYLISTS_DIR_NAME));
+        if let Some(dir) = &dir {
+            let _ = fs::create_dir_all(dir);
+        }
+
+        Self { dir, saved: Default::default(), loaded_files: Default::default() }
+    }
+
+    /// Читает сохраненные плейлисты, упорядоченные по названию
+    ///
+    /// # Returns:
+    /// Названия и треки плейлистов
+    pub fn load(&self) -> Vec<(String, Vec<String>)> {
+        let Some(entries) = self.dir.as_ref().and_then(|dir| fs::read_dir(dir).ok()) else { return vec![] };
+
+        let mut playlists: Vec<(PathBuf, (String, Vec<String>))> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == PLAYLIST_EXTENSION))
+            .filter_map(|path| Some((path.clone(), read_playlist(&path)?)))
+            .collect();
+
+        playlists.sort_by_key(|(_, (name, _))| name.to_lowercase());
+        let (files, playlists) = playlists.into_iter().unzip();
+        *self.loaded_files.lock().unwrap() = files;
+        playlists
+    }
+
+    /// Сохраняет на диск изменения плейлистов: создание, переименование, удаление и правки
+    ///
+    /// Файлы пишет отдельный поток, когда правки утихнут на [`SAVE_DELAY`], и только
+    /// для изменившихся плейлистов. Правки последних мгновений перед выходом записывает
+    /// [`PlaylistStore::flush`].
+    ///
+    /// Плейлисты в состоянии должны идти в том же порядке, в котором их вернул [`PlaylistStore::load`];
+    /// добавленные после них еще не записаны.
+    pub fn track(&self, app_state: &AppState) {
+        let playlists = app_state.read(|state| state.playlists().to_vec());
+        let files = std::mem::take(&mut *self.loaded_files.lock().unwrap());
+        *self.saved.lock().unwrap() = playlists
+            .into_iter()
+            .enumerate()
+            .map(|(index, playlist)| {
+                (playlist.id, SavedPlaylist { file: files.get(index).cloned(), playlist })
+            })
+            .collect();
+
+        let (sender, receiver) = mpsc::channel();
+        app_state.subscribe(move |_, changes| {
+            if changes.contains(&StateChange::Playlist) || changes.contains(&StateChange::Playlists) {
+                let _ = sender.send(());
+            }
+        });
+
+        thread::spawn({
+            let store = self.clone();
+            let app_state = app_state.clone();
+            move || {
+                while receiver.recv().is_ok() {
+                    while receiver.recv_timeout(SAVE_DELAY).is_ok() {}
+                    store.flush(&app_state);
+                }
+            }
+        });
+    }
+
+    /// Сразу записывает несохраненные изменения плейлистов
+    pub fn flush(&self, app_state: &AppState) {
+        self.sync(&app_state.read(|state| state.playlists().to_vec()));
+    }
+
+    /// Записывает изменившиеся плейлисты и удаляет файлы удаленных и переименованных
+    fn sync(&self, playlists: &[NamedPlaylist]) {
+        let Some(dir) = &self.dir else { return };
+        let mut saved = self.saved.lock().unwrap();
+
+        saved.retain(|id, old| {
+            let renamed = playlists
+                .iter()
+                .find(|playlist| playlist.id == *id)
+                .is_none_or(|new| new.name != old.playlist.name);
+            if let (true, Some(file)) = (renamed, &old.file) {
+                let _ = fs::remove_file(file);
+            }
+            !renamed
+        });
+
+        for playlist in playlists {
+            let old = saved.get(&playlist.id);
+            if old.is_some_and(|old| old.playlist == *playlist) {
+                continue;
+            }
+
+            let file = match old.and_then(|old| old.file.clone()) {
+                Some(file) => file,
+                None => free_playlist_file(dir, &playlist.name, &saved),
+            };
+            let _ = fs::write(&file, to_m3u(playlist));
+            saved.insert(playlist.id, SavedPlaylist { playlist: playlist.clone(), file: Some(file) });
+        }
+    }
+}
+
+/// Файл для плейлиста: символы, недопустимые в именах файлов, заменяются на `_`. Если файл
+/// с таким именем уже есть, к имени добавляется номер.
+fn free_playlist_file(dir: &Path, name: &str, saved: &HashMap<u64, SavedPlaylist>) -> PathBuf {
+    let file_name: String =
+        name.chars().map(|c| if c.is_control() || "/\\:*?\"<>|".contains(c) { '_' } else { c }).collect();
+    let taken =
+        |file: &PathBuf| file.exists() || saved.values().any(|saved| saved.file.as_ref() == Some(file));
+
+    std::iter::once(file_name.clone())
+        .chain((2..).map(|number| format!("{} ({})", file_name, number)))
+        .map(|candidate| dir.join(format!("{}.{}", candidate, PLAYLIST_EXTENSION)))
+        .find(|file| !taken(file))
+        .unwrap()
+}
+
+fn to_m3u(playlist: &NamedPlaylist) -> String {
+    let mut content = format!("{}\n{}{}\n", M3U_HEADER, M3U_PLAYLIST_NAME, playlist.name);
+    for track in &playlist.tracks {
+        content.push_str(track);
+        content.push('\n');
+    }
+    content
+}
+
+/// Читает плейлист M3U8. Относительные пути отсчитываются от каталога файла,
+/// а без директивы `#PLAYLIST:` названием служит имя файла.
+pub fn read_playlist(path: &Path) -> Option<(String, Vec<String>)> {
+    let content = fs::read_to_string(path).ok()?;
+    let base = path.parent().unwrap_or(Path::new(""));
+
+    let mut name = path.file_stem()?.to_string_lossy().to_string();
+    let mut tracks = vec![];
+
+    for line in content.lines().map(str::trim) {
+        if let Some(playlist_name) = line.strip_prefix(M3U_PLAYLIST_NAME) {
+            name = playlist_name.trim().to_string();
+        } else if !line.is_empty() && !line.starts_with('#') {
+            tracks.push(base.join(line).to_string_lossy().to_string());
+        }
+    }
+
+    Some((name, tracks))
+}
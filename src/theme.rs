@@ -0,0 +1,384 @@
+use std::str::FromStr;
+
+use ratatui::{
+    style::{Color, Style},
+    symbols::border,
+    widgets::Block,
+};
+use serde::Deserialize;
+
+use crate::{config::ThemeConfig, interaction::InteractionState};
+
+/// Встроенная палитра
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaletteName {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+/// Набор символов интерфейса
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GlyphSetName {
+    #[default]
+    Unicode,
+    /// Только ASCII, для терминалов и шрифтов без псевдографики
+    Ascii,
+}
+
+/// Рамки виджетов
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BorderStyle {
+    Plain,
+    Rounded,
+    Ascii,
+}
+
+/// Цвета интерфейса
+///
+/// # Fields
+///
+/// * `text` - Основной текст
+/// * `muted` - Второстепенный текст: номера, подписи полей, подсказки
+/// * `accent` - Выделение: текущая строка, найденное, сообщения о состоянии
+/// * `accent_text` - Текст на фоне цвета `accent`
+/// * `primary` - Клавиши в подсказках и каталоги
+/// * `success` - Новые значения и допустимые действия
+/// * `warning` - Средний уровень громкости
+/// * `error` - Ошибки, конфликты и перегрузка
+/// * `info` - Линия осциллографа
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub text: Color,
+    pub muted: Color,
+    pub accent: Color,
+    pub accent_text: Color,
+    pub primary: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub info: Color,
+}
+
+impl Palette {
+    pub const DARK: Self = Self {
+        text: Color::Reset,
+        muted: Color::DarkGray,
+        accent: Color::Yellow,
+        accent_text: Color::Black,
+        primary: Color::Blue,
+        success: Color::Green,
+        warning: Color::Yellow,
+        error: Color::Red,
+        info: Color::Cyan,
+    };
+
+    /// Желтый на светлом фоне не читается, поэтому выделение пурпурное
+    pub const LIGHT: Self = Self {
+        text: Color::Reset,
+        muted: Color::DarkGray,
+        accent: Color::Magenta,
+        accent_text: Color::White,
+        primary: Color::Blue,
+        success: Color::Green,
+        warning: Color::Rgb(175, 95, 0),
+        error: Color::Red,
+        info: Color::Blue,
+    };
+
+    pub const HIGH_CONTRAST: Self = Self {
+        text: Color::White,
+        muted: Color::Gray,
+        accent: Color::LightYellow,
+        accent_text: Color::Black,
+        primary: Color::LightCyan,
+        success: Color::LightGreen,
+        warning: Color::LightYellow,
+        error: Color::LightRed,
+        info: Color::LightCyan,
+    };
+}
+
+/// Рамки виджета в разных состояниях
+///
+/// # Fields
+///
+/// * `normal` - Обычная рамка
+/// * `hovered` - Рамка кнопки под курсором
+/// * `focused` - Рамка виджета в фокусе
+/// * `main` - Рамка окна приложения
+#[derive(Debug, Clone, Copy)]
+pub struct BorderSets {
+    pub normal: border::Set,
+    pub hovered: border::Set,
+    pub focused: border::Set,
+    pub main: border::Set,
+}
+
+impl BorderSets {
+    pub const PLAIN: Self =
+        Self { normal: border::PLAIN, hovered: border::THICK, focused: border::DOUBLE, main: border::THICK };
+
+    pub const ROUNDED: Self = Self {
+        normal: border::ROUNDED,
+        hovered: border::THICK,
+        focused: border::DOUBLE,
+        main: border::THICK,
+    };
+
+    pub const ASCII: Self = Self {
+        normal: border::Set {
+            top_left: "+",
+            top_right: "+",
+            bottom_left: "+",
+            bottom_right: "+",
+            vertical_left: "|",
+            vertical_right: "|",
+            horizontal_top: "-",
+            horizontal_bottom: "-",
+        },
+        hovered: border::Set {
+            top_left: "+",
+            top_right: "+",
+            bottom_left: "+",
+            bottom_right: "+",
+            vertical_left: "|",
+            vertical_right: "|",
+            horizontal_top: "=",
+            horizontal_bottom: "=",
+        },
+        focused: border::Set {
+            top_left: "#",
+            top_right: "#",
+            bottom_left: "#",
+            bottom_right: "#",
+            vertical_left: "#",
+            vertical_right: "#",
+            horizontal_top: "=",
+            horizontal_bottom: "=",
+        },
+        main: border::Set {
+            top_left: "+",
+            top_right: "+",
+            bottom_left: "+",
+            bottom_right: "+",
+            vertical_left: "|",
+            vertical_right: "|",
+            horizontal_top: "=",
+            horizontal_bottom: "=",
+        },
+    };
+}
+
+/// Надписи кнопки в обычном состоянии, под курсором и при нажатии
+#[derive(Debug, Clone, Copy)]
+pub struct ButtonGlyphs {
+    pub normal: &'static str,
+    pub hovered: &'static str,
+    pub pressed: &'static str,
+}
+
+impl ButtonGlyphs {
+    pub fn label(&self, widget_state: &InteractionState) -> &'static str {
+        match widget_state {
+            InteractionState::Hovered => self.hovered,
+            InteractionState::Pressed => self.pressed,
+            _ => self.normal,
+        }
+    }
+}
+
+/// Символы интерфейса
+///
+/// # Fields
+///
+/// * `title` - Заголовок окна приложения
+/// * `play`, `pause`, `stop`, `previous`, `next` - Кнопки управления воспроизведением
+/// * `shuffle` - Переключатель перемешивания; надпись `hovered` означает, что оно включено
+/// * `repeat` - Переключатель повтора
+/// * `levels` - Восемь ступеней высоты столбца, от низшей к высшей
+/// * `progress_line`, `progress_slider` - Линия и бегунок полосы прогресса
+/// * `meter_fill`, `meter_empty`, `peak_hold` - Заполненная и пустая часть индикатора уровня
+///   и отметка пика
+/// * `braille` - Можно рисовать точками Брайля
+/// * `current_track` - Отметка текущего трека в плейлисте
+/// * `cursor` - Курсор поля ввода
+/// * `tab_separator` - Разделитель вкладок плейлистов
+/// * `separator` - Разделитель сведений в строке
+/// * `arrow` - Стрелка между старым и новым значением
+/// * `ellipsis` - Многоточие у обрезанного текста
+/// * `empty_value` - Пустое значение тега
+/// * `sort_ascending`, `sort_descending` - Направление сортировки в заголовке колонки
+/// * `up_down` - Клавиши со стрелками вверх и вниз в подсказках
+#[derive(Debug, Clone, Copy)]
+pub struct Glyphs {
+    pub title: &'static str,
+    pub play: ButtonGlyphs,
+    pub pause: ButtonGlyphs,
+    pub stop: ButtonGlyphs,
+    pub previous: ButtonGlyphs,
+    pub next: ButtonGlyphs,
+    pub shuffle: ButtonGlyphs,
+    pub repeat: &'static str,
+    pub levels: [char; 8],
+    pub progress_line: char,
+    pub progress_slider: char,
+    pub meter_fill: char,
+    pub meter_empty: char,
+    pub peak_hold: char,
+    pub braille: bool,
+    pub current_track: &'static str,
+    pub cursor: &'static str,
+    pub tab_separator: &'static str,
+    pub separator: &'static str,
+    pub arrow: &'static str,
+    pub ellipsis: &'static str,
+    pub empty_value: &'static str,
+    pub sort_ascending: &'static str,
+    pub sort_descending: &'static str,
+    pub up_down: &'static str,
+}
+
+impl Glyphs {
+    pub const UNICODE: Self = Self {
+        title: " 𝄞 TMP 𝄞 ",
+        play: ButtonGlyphs { normal: "▷", hovered: "▶", pressed: "▸" },
+        pause: ButtonGlyphs { normal: "‖", hovered: "‖", pressed: "∥" },
+        stop: ButtonGlyphs { normal: "▢", hovered: "■", pressed: "▪" },
+        previous: ButtonGlyphs { normal: "|◁◁", hovered: "|◀◀", pressed: "|◂◂" },
+        next: ButtonGlyphs { normal: "▷▷|", hovered: "▶▶|", pressed: "▸▸|" },
+        shuffle: ButtonGlyphs { normal: "△▽", hovered: "▲▼", pressed: "▴▾" },
+        repeat: "⮎⮌",
+        levels: ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+        progress_line: '━',
+        progress_slider: '█',
+        meter_fill: '█',
+        meter_empty: '·',
+        peak_hold: '▐',
+        braille: true,
+        current_track: "▶ ",
+        cursor: "▏",
+        tab_separator: "│",
+        separator: " · ",
+        arrow: " → ",
+        ellipsis: "…",
+        empty_value: "∅",
+        sort_ascending: "▲",
+        sort_descending: "▼",
+        up_down: "↑/↓",
+    };
+
+    pub const ASCII: Self = Self {
+        title: " TMP ",
+        play: ButtonGlyphs { normal: ">", hovered: ">", pressed: "-" },
+        pause: ButtonGlyphs { normal: "||", hovered: "||", pressed: "| |" },
+        stop: ButtonGlyphs { normal: "[]", hovered: "[#]", pressed: "." },
+        previous: ButtonGlyphs { normal: "|<<", hovered: "|<<", pressed: "|<" },
+        next: ButtonGlyphs { normal: ">>|", hovered: ">>|", pressed: ">|" },
+        shuffle: ButtonGlyphs { normal: "shf", hovered: "SHF", pressed: "s" },
+        repeat: "rep",
+        levels: ['_', '.', ':', '-', '=', '+', '*', '#'],
+        progress_line: '=',
+        progress_slider: '#',
+        meter_fill: '#',
+        meter_empty: '.',
+        peak_hold: '|',
+        braille: false,
+        current_track: "> ",
+        cursor: "_",
+        tab_separator: "|",
+        separator: " - ",
+        arrow: " -> ",
+        ellipsis: "...",
+        empty_value: "-",
+        sort_ascending: "^",
+        sort_descending: "v",
+        up_down: "Up/Down",
+    };
+}
+
+/// Оформление интерфейса: цвета, рамки и символы
+///
+/// Загружается из секции `[theme]` настроек и передается всем функциям отрисовки.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub palette: Palette,
+    pub borders: BorderSets,
+    pub glyphs: Glyphs,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self { palette: Palette::DARK, borders: BorderSets::PLAIN, glyphs: Glyphs::UNICODE }
+    }
+}
+
+impl Theme {
+    /// Собирает тему из настроек. Цвета из `[theme.colors]` заменяют цвета встроенной палитры;
+    /// нераспознанные цвета пропускаются.
+    pub fn load(config: &ThemeConfig) -> Self {
+        let mut palette = match config.palette {
+            PaletteName::Dark => Palette::DARK,
+            PaletteName::Light => Palette::LIGHT,
+            PaletteName::HighContrast => Palette::HIGH_CONTRAST,
+        };
+        let colors = &config.colors;
+        for (color, value) in [
+            (&mut palette.text, &colors.text),
+            (&mut palette.muted, &colors.muted),
+            (&mut palette.accent, &colors.accent),
+            (&mut palette.accent_text, &colors.accent_text),
+            (&mut palette.primary, &colors.primary),
+            (&mut palette.success, &colors.success),
+            (&mut palette.warning, &colors.warning),
+            (&mut palette.error, &colors.error),
+            (&mut palette.info, &colors.info),
+        ] {
+            if let Some(parsed) = value.as_deref().and_then(|value| Color::from_str(value).ok()) {
+                *color = parsed;
+            }
+        }
+
+        let glyphs = match config.glyphs {
+            GlyphSetName::Unicode => Glyphs::UNICODE,
+            GlyphSetName::Ascii => Glyphs::ASCII,
+        };
+        // Без явного выбора рамки следуют набору символов
+        let borders = match (config.borders, config.glyphs) {
+            (Some(BorderStyle::Plain), _) | (None, GlyphSetName::Unicode) => BorderSets::PLAIN,
+            (Some(BorderStyle::Rounded), _) => BorderSets::ROUNDED,
+            (Some(BorderStyle::Ascii), _) | (None, GlyphSetName::Ascii) => BorderSets::ASCII,
+        };
+
+        Self { palette, borders, glyphs }
+    }
+
+    /// Рамка виджета: под курсором жирная, в фокусе двойная
+    pub fn block(&self, widget_state: &InteractionState) -> Block<'static> {
+        let border_set = match widget_state {
+            InteractionState::Hovered => self.borders.hovered,
+            InteractionState::Focused => self.borders.focused,
+            _ => self.borders.normal,
+        };
+        Block::bordered().border_set(border_set).style(Style::new().fg(self.palette.text))
+    }
+
+    /// Рамка панели: меняется только от фокуса, наведение курсора на панель ее не выделяет
+    ///
+    /// Щелчок переводит фокус на панель до нажатия, поэтому нажатая панель тоже в фокусе.
+    pub fn panel(&self, widget_state: &InteractionState) -> Block<'static> {
+        match widget_state {
+            InteractionState::Focused | InteractionState::Pressed => self.block(&InteractionState::Focused),
+            _ => self.block(&InteractionState::Default),
+        }
+    }
+
+    /// Клавиша в подсказке
+    pub fn key(&self) -> Style {
+        Style::new().fg(self.palette.primary)
+    }
+}
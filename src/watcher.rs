@@ -0,0 +1,156 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use notify::{
+    event::{EventKind, ModifyKind},
+    RecommendedWatcher, RecursiveMode, Watcher,
+};
+
+use crate::{
+    app::{AppState, StateChange, ToastLevel},
+    config::LibraryConfig,
+    files::is_track_file,
+    library::Library,
+    theme::Theme,
+};
+
+/// Сколько ждать, пока поток событий файловой системы утихнет, прежде чем их обработать
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Следит за каталогами медиатеки и поддерживает в актуальном состоянии индекс медиатеки
+/// и отметки об отсутствующих файлах треков в плейлистах
+///
+/// Пропавший трек ищется в индексе по размеру и тегам, и, если новое место нашлось,
+/// пользователю предлагается обновить пути в плейлистах.
+pub fn watch_tracks(app_state: &AppState, library: &Library, config: &LibraryConfig, theme: &Theme) {
+    watch_playlists(app_state, library, theme);
+
+    let roots = config.root_dirs();
+    let app_state = app_state.clone();
+    let library = library.clone();
+    let theme = *theme;
+
+    thread::spawn(move || {
+        let (sender, receiver) = mpsc::channel();
+        let Ok(mut watcher) = RecommendedWatcher::new(sender, notify::Config::default()) else { return };
+        for root in &roots {
+            let _ = watcher.watch(root, RecursiveMode::Recursive);
+        }
+
+        // События копятся, пока не наступит пауза: копирование альбома - это сотни событий
+        let mut changed = HashSet::<PathBuf>::new();
+        let mut deadline = None;
+        loop {
+            let timeout = deadline.map_or(Duration::MAX, |deadline: Instant| {
+                deadline.saturating_duration_since(Instant::now())
+            });
+
+            match receiver.recv_timeout(timeout) {
+                Ok(Ok(event)) if is_content_change(&event.kind) => {
+                    changed.extend(event.paths);
+                    deadline.get_or_insert_with(|| Instant::now() + DEBOUNCE);
+                }
+                Ok(_) => {}
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    apply_changes(&app_state, &library, &theme, std::mem::take(&mut changed));
+                    deadline = None;
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+}
+
+/// Проверяет, есть ли на диске файлы треков, добавленных в плейлисты
+fn watch_playlists(app_state: &AppState, library: &Library, theme: &Theme) {
+    let checked = Arc::new(Mutex::new(HashSet::<String>::new()));
+    let library = library.clone();
+    let theme = *theme;
+
+    let check = move |app_state: &AppState| {
+        let unchecked: Vec<String> = app_state.read(|state| {
+            let checked = checked.lock().unwrap();
+            let tracks = state.playlists().iter().flat_map(|playlist| &playlist.tracks);
+            tracks
+                .filter(|path| !checked.contains(*path))
+                .cloned()
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect()
+        });
+        if unchecked.is_empty() {
+            return;
+        }
+
+        checked.lock().unwrap().extend(unchecked.iter().cloned());
+        let app_state = app_state.clone();
+        let library = library.clone();
+        thread::spawn(move || {
+            let missing: Vec<String> =
+                unchecked.into_iter().filter(|path| !Path::new(path).exists()).collect();
+            if !missing.is_empty() {
+                app_state.update(|state| state.set_tracks_missing(missing, true));
+                offer_relocation(&app_state, &library, &theme);
+            }
+        });
+    };
+
+    check(app_state);
+    app_state.subscribe(move |app_state, changes| {
+        if changes.contains(&StateChange::Playlist) || changes.contains(&StateChange::Playlists) {
+            check(app_state);
+        }
+    });
+}
+
+/// Создание, удаление, перемещение или изменение содержимого файла
+fn is_content_change(kind: &EventKind) -> bool {
+    match kind {
+        EventKind::Create(_) | EventKind::Remove(_) => true,
+        EventKind::Modify(modify) => !matches!(modify, ModifyKind::Metadata(_)),
+        _ => false,
+    }
+}
+
+/// Переносит изменения файлов в индекс медиатеки и отметки об отсутствующих треках.
+/// Что случилось с файлом, определяется по его состоянию после всех событий.
+fn apply_changes(app_state: &AppState, library: &Library, theme: &Theme, changed: HashSet<PathBuf>) {
+    let (appeared, vanished): (Vec<PathBuf>, Vec<PathBuf>) =
+        changed.into_iter().partition(|path| path.exists());
+
+    let appeared: Vec<PathBuf> =
+        appeared.into_iter().filter(|path| path.is_dir() || is_track_file(path)).collect();
+    library.update_paths(&appeared);
+    library.remove_paths(&vanished.iter().map(|path| path.to_string_lossy().to_string()).collect::<Vec<_>>());
+
+    let under = |paths: &[PathBuf], track: &str| paths.iter().any(|path| Path::new(track).starts_with(path));
+    app_state.update(|state| {
+        let tracks: HashSet<String> =
+            state.playlists().iter().flat_map(|playlist| playlist.tracks.iter().cloned()).collect();
+
+        let missing = tracks.iter().filter(|track| under(&vanished, track) && !Path::new(track).exists());
+        state.set_tracks_missing(missing.cloned().collect::<Vec<_>>(), true);
+
+        let found = tracks.iter().filter(|track| under(&appeared, track) && Path::new(track).exists());
+        state.set_tracks_missing(found.cloned().collect::<Vec<_>>(), false);
+    });
+
+    offer_relocation(app_state, library, theme);
+}
+
+/// Сообщает о пропавших треках, новое место которых нашлось в медиатеке
+fn offer_relocation(app_state: &AppState, library: &Library, theme: &Theme) {
+    let missing: Vec<String> = app_state.read(|state| state.missing_tracks().cloned().collect());
+    let relocatable = missing.iter().filter(|path| library.relocation(path).is_some()).count();
+
+    if relocatable > 0 {
+        let separator = theme.glyphs.separator;
+        let message = format!("{} moved tracks found{}R in the playlist to relocate", relocatable, separator);
+        app_state.notify(ToastLevel::Info, message);
+    }
+}
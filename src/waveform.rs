@@ -0,0 +1,132 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io::BufReader,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use rodio::{Decoder, Source};
+
+use crate::{
+    config::data_dir,
+    metadata::{file_stamp, stable_hash},
+};
+
+/// Точек в огибающей трека: с запасом на самый широкий терминал
+pub const ENVELOPE_POINTS: usize = 1024;
+/// Каталог кэша огибающих внутри каталога данных
+const CACHE_DIR_NAME: &str = "waveforms";
+/// Сколько раз в секунду снимается пик при декодировании, до прореживания до [`ENVELOPE_POINTS`]
+const BLOCKS_PER_SECOND: u32 = 100;
+
+/// Огибающая амплитуды трека: пики [`ENVELOPE_POINTS`] равных отрезков, от 0 до 1
+/// относительно самого громкого места трека
+pub type Envelope = Arc<Vec<f32>>;
+
+/// Кэш огибающих треков для волновой полосы прогресса
+///
+/// Огибающая вычисляется в фоне полным декодированием файла, поэтому готовые огибающие
+/// хранятся в каталоге данных под хэшем пути, времени изменения и размера файла.
+///
+/// # Fields
+///
+/// * `envelopes` - Готовые огибающие по пути к файлу
+/// * `pending` - Файлы, огибающие которых сейчас вычисляются
+#[derive(Default, Clone)]
+pub struct WaveformCache {
+    envelopes: Arc<Mutex<HashMap<String, Envelope>>>,
+    pending: Arc<Mutex<HashSet<String>>>,
+}
+
+impl WaveformCache {
+    /// Огибающая трека, если она уже готова
+    pub fn get(&self, path: &str) -> Option<Envelope> {
+        self.envelopes.lock().unwrap().get(path).cloned()
+    }
+
+    /// Готовит огибающую трека в фоне, если ее еще нет
+    ///
+    /// # Args:
+    /// * `path` - Путь к файлу
+    /// * `on_ready` - Вызывается, когда огибающая готова
+    pub fn request<F>(&self, path: &str, on_ready: F)
+    where
+        F: Fn() + Send + 'static,
+    {
+        if self.get(path).is_some() || !self.pending.lock().unwrap().insert(path.to_string()) {
+            return;
+        }
+
+        let cache = self.clone();
+        let path = path.to_string();
+        thread::spawn(move || {
+            let cache_file = cache_file(&path);
+            let envelope = cache_file
+                .as_ref()
+                .and_then(|file| fs::read(file).ok())
+                .map(decode)
+                .filter(|envelope| envelope.len() == ENVELOPE_POINTS)
+                .or_else(|| {
+                    let envelope = compute_envelope(&path)?;
+                    if let Some(file) = &cache_file {
+                        let _ = fs::write(file, encode(&envelope));
+                    }
+                    Some(envelope)
+                });
+
+            cache.pending.lock().unwrap().remove(&path);
+            if let Some(envelope) = envelope {
+                cache.envelopes.lock().unwrap().insert(path, Arc::new(envelope));
+                on_ready();
+            }
+        });
+    }
+}
+
+/// Файл кэша огибающей; меняется вместе с файлом трека
+fn cache_file(path: &str) -> Option<PathBuf> {
+    let (mtime, size) = file_stamp(path)?;
+    let dir = data_dir()?.join(CACHE_DIR_NAME);
+    fs::create_dir_all(&dir).ok()?;
+
+    let key = format!("{}\0{}\0{}", path, mtime, size);
+    Some(dir.join(format!("{:016x}", stable_hash(key.as_bytes()))))
+}
+
+/// Декодирует трек целиком и прореживает пики до [`ENVELOPE_POINTS`] точек
+fn compute_envelope(path: &str) -> Option<Vec<f32>> {
+    let decoder = Decoder::new(BufReader::new(File::open(path).ok()?)).ok()?;
+    let block_len = (decoder.sample_rate() * decoder.channels() as u32 / BLOCKS_PER_SECOND).max(1) as usize;
+
+    let mut blocks = vec![];
+    let mut peak = 0u16;
+    for (index, sample) in decoder.enumerate() {
+        peak = peak.max(sample.unsigned_abs());
+        if (index + 1) % block_len == 0 {
+            blocks.push(peak);
+            peak = 0;
+        }
+    }
+    blocks.push(peak);
+
+    let loudest = blocks.iter().copied().max().filter(|loudest| *loudest > 0)? as f32;
+    let envelope = (0..ENVELOPE_POINTS)
+        .map(|point| {
+            let first = point * blocks.len() / ENVELOPE_POINTS;
+            let last = ((point + 1) * blocks.len() / ENVELOPE_POINTS).max(first + 1).min(blocks.len());
+            blocks[first.min(last - 1)..last].iter().copied().max().unwrap_or(0) as f32 / loudest
+        })
+        .collect();
+    Some(envelope)
+}
+
+/// Огибающая в файле кэша: по байту на точку
+fn encode(envelope: &[f32]) -> Vec<u8> {
+    envelope.iter().map(|level| (level.clamp(0.0, 1.0) * u8::MAX as f32).round() as u8).collect()
+}
+
+fn decode(bytes: Vec<u8>) -> Vec<f32> {
+    bytes.into_iter().map(|level| level as f32 / u8::MAX as f32).collect()
+}